@@ -25,6 +25,7 @@ bind! {
     _call_user_function_impl,
     _efree,
     _emalloc,
+    _erealloc,
     _zend_executor_globals,
     _sapi_globals_struct,
     _sapi_module_struct,
@@ -92,6 +93,7 @@ bind! {
     zend_hash_str_find,
     zend_hash_str_update,
     zend_internal_arg_info,
+    zend_try_assign_typed_ref,
     zend_is_callable,
     zend_is_identical,
     zend_is_iterable,
@@ -137,6 +139,8 @@ bind! {
     E_RECOVERABLE_ERROR,
     E_DEPRECATED,
     E_USER_DEPRECATED,
+    GC_IMMUTABLE,
+    GC_PERSISTENT,
     HT_MIN_SIZE,
     IS_ARRAY,
     IS_ARRAY_EX,
@@ -255,6 +259,7 @@ bind! {
     zend_hash_get_current_key_zval_ex,
     zend_hash_get_current_data_ex,
     zend_hash_move_backwards_ex,
+    zend_hash_internal_pointer_end_ex,
     zend_array_count,
     gc_possible_root,
     ZEND_ACC_NOT_SERIALIZABLE,
@@ -307,5 +312,57 @@ bind! {
     php_module_shutdown,
     php_request_startup,
     php_request_shutdown,
-    instanceof_function_slow
+    instanceof_function_slow,
+    ZEND_ACC_ENUM,
+    zend_register_internal_enum,
+    zend_enum_add_case_cstr,
+    zend_enum_get_case_cstr,
+    ZEND_ACC_READONLY,
+    zend_property_info,
+    zend_declare_typed_property,
+    zend_get_executed_scope,
+    zend_read_static_property_ex,
+    zend_update_static_property_ex,
+    zend_ce_error,
+    ZEND_ADD,
+    ZEND_SUB,
+    ZEND_MUL,
+    ZEND_DIV,
+    ZEND_MOD,
+    ZEND_SL,
+    ZEND_SR,
+    ZEND_CONCAT,
+    ZEND_BW_OR,
+    ZEND_BW_AND,
+    ZEND_BW_XOR,
+    ZEND_POW,
+    ZEND_BW_NOT,
+    zend_iterator_init,
+    zend_create_internal_iterator_zval,
+    rsrc_dtor_func_t,
+    zend_register_list_destructors_ex,
+    zend_register_resource,
+    zend_register_persistent_resource,
+    zend_list_delete,
+    zend_list_close,
+    smart_str,
+    zend_new_interned_string,
+    zend_get_gc_buffer,
+    zend_get_gc_buffer_create,
+    zend_ce_weakref,
+    zend_ce_fiber,
+    zend_is_unwind_exit,
+    zend_is_graceful_exit,
+    zend_ce_closure,
+    zend_create_fake_closure,
+    zend_is_callable_ex,
+    zend_argument_error,
+    ZEND_CALL_HAS_EXTRA_NAMED_PARAMS,
+    zend_argument_type_error,
+    zend_argument_value_error,
+    zend_zval_type_name,
+    zend_get_default_from_internal_arg_info,
+    zval_update_constant_ex,
+    zend_hash_sort_ex,
+    zend_sort
 }
@@ -322,6 +322,11 @@ fn main() -> Result<()> {
         .into_iter()
         .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("closure")))
         .collect();
+    #[cfg(not(feature = "iterator"))]
+    let test_md: Vec<_> = test_md
+        .into_iter()
+        .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("rust_iterator")))
+        .collect();
     skeptic::generate_doc_tests(&test_md);
 
     Ok(())
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::STATE;
+use crate::{impl_::Visibility, STATE};
 use anyhow::{anyhow, bail, Context, Result};
 use darling::{FromMeta, ToTokens};
 use proc_macro2::{Ident, Span, TokenStream};
@@ -23,12 +23,19 @@ pub struct Class {
     /// of `ClassBuilder` and must return it.
     pub modifier: Option<String>,
     pub flags: Option<String>,
+    /// Object handler hooks of `RegisteredClass` implemented by methods marked
+    /// with `#[handler]`.
+    pub handlers: Vec<String>,
+    /// Built-in PHP interfaces implemented natively through marker attributes,
+    /// i.e. `countable`, `array_access`, `iterable` and `stringable`.
+    pub builtin_interfaces: Vec<String>,
 }
 
 #[derive(Debug)]
 pub enum ParsedAttribute {
     Extends(Expr),
     Implements(Expr),
+    BuiltinInterface(String),
     Property(PropertyAttr),
     Comment(String),
 }
@@ -47,6 +54,7 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
 
     let mut parent = None;
     let mut interfaces = vec![];
+    let mut builtin_interfaces = vec![];
     let mut properties = HashMap::new();
     let mut comments = vec![];
 
@@ -61,6 +69,9 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
                     ParsedAttribute::Implements(class) => {
                         interfaces.push(class.to_token_stream().to_string());
                     }
+                    ParsedAttribute::BuiltinInterface(name) => {
+                        builtin_interfaces.push(name);
+                    }
                     ParsedAttribute::Comment(comment) => {
                         comments.push(comment);
                     }
@@ -93,14 +104,20 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
                                 .ok_or_else(|| anyhow!("Only named fields can be properties."))?
                                 .to_string();
                             let prop_name = prop.rename.unwrap_or_else(|| field_name.clone());
-                            result_prop = Some((
-                                prop_name,
-                                Property::field(
-                                    field_name,
-                                    vec![],
-                                    prop.flags.map(|flags| flags.to_token_stream().to_string()),
-                                ),
-                            ));
+                            let mut property = Property::field(
+                                field_name,
+                                vec![],
+                                prop.flags.map(|flags| flags.to_token_stream().to_string()),
+                            );
+                            property.rust_ty = crate::function::Arg::from_type(
+                                String::new(),
+                                &field.ty,
+                                None,
+                                false,
+                            )
+                            .map(|arg| (arg.ty, arg.nullable));
+                            property.readonly = prop.readonly;
+                            result_prop = Some((prop_name, property));
                         }
                         ParsedAttribute::Comment(doc) => docs.push(doc),
                         _ => bail!("Attribute {:?} is not valid for struct fields.", attr),
@@ -129,6 +146,7 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
         properties,
         modifier: args.modifier,
         flags,
+        builtin_interfaces,
         ..Default::default()
     };
 
@@ -142,12 +160,28 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
         bail!("The `#[php_startup]` macro must be called after all the classes have been defined.");
     }
 
+    // Array access uses the number of elements to check whether offsets exist.
+    let countable = class
+        .builtin_interfaces
+        .iter()
+        .any(|name| name == "countable" || name == "array_access")
+        .then(|| {
+            quote! {
+                impl ::ext_php_rs::interfaces::Countable for #ident {
+                    fn count(&self) -> usize {
+                        self.len()
+                    }
+                }
+            }
+        });
+
     state.classes.insert(ident.to_string(), class);
 
     Ok(quote! {
         #input
 
         ::ext_php_rs::class_derives!(#ident);
+        #countable
     })
 }
 
@@ -155,8 +189,11 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
 pub struct Property {
     pub ty: PropertyType,
     pub docs: Vec<String>,
-    #[allow(dead_code)]
     pub flags: Option<String>,
+    /// The Rust type of the property and whether it is nullable, used to
+    /// declare the type of the property in PHP.
+    pub rust_ty: Option<(String, bool)>,
+    pub readonly: bool,
 }
 
 #[derive(Debug)]
@@ -168,12 +205,16 @@ pub enum PropertyType {
         getter: Option<String>,
         setter: Option<String>,
     },
+    Static {
+        value: String,
+    },
 }
 
 impl Property {
     pub fn add_getter(&mut self, new_getter: String) -> Result<()> {
         match &mut self.ty {
             PropertyType::Field { .. } => bail!("Cannot add getter to field property."),
+            PropertyType::Static { .. } => bail!("Cannot add getter to static property."),
             PropertyType::Method { getter, setter: _ } => match getter {
                 Some(getter) => bail!(
                     "Attempted to add getter `{}` to property that already has a getter `{}`.",
@@ -191,6 +232,7 @@ impl Property {
     pub fn add_setter(&mut self, new_setter: String) -> Result<()> {
         match &mut self.ty {
             PropertyType::Field { .. } => bail!("Cannot add setter to field property."),
+            PropertyType::Static { .. } => bail!("Cannot add setter to static property."),
             PropertyType::Method { getter: _, setter } => match setter {
                 Some(getter) => bail!(
                     "Attempted to add setter `{}` to property that already has a setter `{}`.",
@@ -210,6 +252,8 @@ impl Property {
             ty: PropertyType::Field { field_name },
             docs,
             flags,
+            rust_ty: None,
+            readonly: false,
         }
     }
 
@@ -221,6 +265,33 @@ impl Property {
             },
             docs,
             flags,
+            rust_ty: None,
+            readonly: false,
+        }
+    }
+
+    pub fn static_(
+        value: String,
+        rust_ty: Option<(String, bool)>,
+        docs: Vec<String>,
+        flags: Option<String>,
+    ) -> Self {
+        Self {
+            ty: PropertyType::Static { value },
+            docs,
+            flags,
+            rust_ty,
+            readonly: false,
+        }
+    }
+
+    /// Returns the visibility of the property, based on the flags given to the
+    /// `#[prop]` attribute.
+    pub fn visibility(&self) -> Visibility {
+        match &self.flags {
+            Some(flags) if flags.contains("Private") => Visibility::Private,
+            Some(flags) if flags.contains("Protected") => Visibility::Protected,
+            _ => Visibility::Public,
         }
     }
 
@@ -229,7 +300,7 @@ impl Property {
             PropertyType::Field { field_name } => {
                 let field_name = Ident::new(field_name, Span::call_site());
                 quote! {
-                    (#name, ::ext_php_rs::props::Property::field(|obj: &mut Self| &mut obj.#field_name)),
+                    (#name, ::ext_php_rs::props::Property::field(|obj: &Self| &obj.#field_name, |obj: &mut Self| &mut obj.#field_name)),
                 }
             }
             PropertyType::Method { getter, setter } => {
@@ -249,6 +320,8 @@ impl Property {
                     (#name, ::ext_php_rs::props::Property::method(#getter, #setter)),
                 }
             }
+            // Static properties are stored by PHP on the class entry.
+            PropertyType::Static { .. } => TokenStream::new(),
         }
     }
 }
@@ -257,6 +330,7 @@ impl Property {
 pub struct PropertyAttr {
     pub rename: Option<String>,
     pub flags: Option<Expr>,
+    pub readonly: bool,
 }
 
 impl syn::parse::Parse for PropertyAttr {
@@ -264,6 +338,11 @@ impl syn::parse::Parse for PropertyAttr {
         let mut this = Self::default();
         while !input.is_empty() {
             let field = input.parse::<Ident>()?.to_string();
+            if field == "readonly" {
+                this.readonly = true;
+                let _ = input.parse::<Token![,]>();
+                continue;
+            }
             input.parse::<Token![=]>()?;
 
             match field.as_str() {
@@ -299,6 +378,9 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
                 .map_err(|_| anyhow!("Unable to parse `#[{}]` attribute.", name))?;
             Some(ParsedAttribute::Implements(meta))
         }
+        "countable" | "array_access" | "iterable" | "stringable" => {
+            Some(ParsedAttribute::BuiltinInterface(name.clone()))
+        }
         "doc" => {
             struct DocComment(pub String);
 
@@ -327,3 +409,147 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
         _ => None,
     })
 }
+
+/// Returns the implementation of an object handler hook of `RegisteredClass`
+/// which forwards to the method of the same name on the class, or [`None`] if
+/// there is no hook with the given name.
+pub fn handler_impl(name: &str) -> Option<TokenStream> {
+    Some(match name {
+        "compare" => quote! {
+            fn compare(
+                &self,
+                other: &::ext_php_rs::types::Zval,
+            ) -> ::ext_php_rs::exception::PhpResult<::std::option::Option<::std::cmp::Ordering>> {
+                Self::compare(self, other)
+            }
+        },
+        "cast" => quote! {
+            fn cast(
+                &self,
+                ty: ::ext_php_rs::flags::DataType,
+            ) -> ::ext_php_rs::exception::PhpResult<::std::option::Option<::ext_php_rs::types::Zval>> {
+                Self::cast(self, ty)
+            }
+        },
+        "clone_obj" => quote! {
+            fn clone_obj(&self) -> ::std::option::Option<Self> {
+                Self::clone_obj(self)
+            }
+        },
+        "read_dimension" => quote! {
+            fn read_dimension(
+                &self,
+                offset: &::ext_php_rs::types::Zval,
+            ) -> ::ext_php_rs::exception::PhpResult<::std::option::Option<::ext_php_rs::types::Zval>> {
+                Self::read_dimension(self, offset)
+            }
+        },
+        "write_dimension" => quote! {
+            fn write_dimension(
+                &mut self,
+                offset: ::std::option::Option<&::ext_php_rs::types::Zval>,
+                value: &::ext_php_rs::types::Zval,
+            ) -> ::ext_php_rs::exception::PhpResult<bool> {
+                Self::write_dimension(self, offset, value)
+            }
+        },
+        "has_dimension" => quote! {
+            fn has_dimension(
+                &self,
+                offset: &::ext_php_rs::types::Zval,
+                check_empty: bool,
+            ) -> ::ext_php_rs::exception::PhpResult<::std::option::Option<bool>> {
+                Self::has_dimension(self, offset, check_empty)
+            }
+        },
+        "unset_dimension" => quote! {
+            fn unset_dimension(
+                &mut self,
+                offset: &::ext_php_rs::types::Zval,
+            ) -> ::ext_php_rs::exception::PhpResult<bool> {
+                Self::unset_dimension(self, offset)
+            }
+        },
+        "count_elements" => quote! {
+            fn count_elements(&self) -> ::ext_php_rs::exception::PhpResult<::std::option::Option<i64>> {
+                Self::count_elements(self)
+            }
+        },
+        "debug_info" => quote! {
+            fn debug_info(
+                &self,
+            ) -> ::std::option::Option<::ext_php_rs::boxed::ZBox<::ext_php_rs::types::ZendHashTable>> {
+                Self::debug_info(self)
+            }
+        },
+        "do_operation" => quote! {
+            fn do_operation(
+                op: ::ext_php_rs::flags::Operator,
+                lhs: &::ext_php_rs::types::Zval,
+                rhs: ::std::option::Option<&::ext_php_rs::types::Zval>,
+            ) -> ::ext_php_rs::exception::PhpResult<::std::option::Option<::ext_php_rs::types::Zval>> {
+                Self::do_operation(op, lhs, rhs)
+            }
+        },
+        "get_gc" => quote! {
+            fn get_gc(&self, gc: &mut ::ext_php_rs::zend::GcBuffer) {
+                Self::get_gc(self, gc)
+            }
+        },
+        _ => return None,
+    })
+}
+
+/// Returns the name of the PHP interface implemented by a marker attribute.
+pub fn builtin_interface_name(name: &str) -> &'static str {
+    match name {
+        "countable" => "\\Countable",
+        "array_access" => "\\ArrayAccess",
+        "iterable" => "\\IteratorAggregate",
+        _ => "\\Stringable",
+    }
+}
+
+/// Returns the object handler hooks of `RegisteredClass` implemented by a
+/// marker attribute.
+pub fn builtin_interface_handlers(name: &str) -> TokenStream {
+    match name {
+        "countable" => quote! {
+            fn count_elements(&self) -> ::ext_php_rs::exception::PhpResult<::std::option::Option<i64>> {
+                Ok(Some(::ext_php_rs::interfaces::Countable::count(self) as i64))
+            }
+        },
+        "array_access" => quote! {
+            fn read_dimension(
+                &self,
+                offset: &::ext_php_rs::types::Zval,
+            ) -> ::ext_php_rs::exception::PhpResult<::std::option::Option<::ext_php_rs::types::Zval>> {
+                ::ext_php_rs::interfaces::read_dimension(self, offset).map(Some)
+            }
+
+            fn write_dimension(
+                &mut self,
+                offset: ::std::option::Option<&::ext_php_rs::types::Zval>,
+                value: &::ext_php_rs::types::Zval,
+            ) -> ::ext_php_rs::exception::PhpResult<bool> {
+                ::ext_php_rs::interfaces::write_dimension(self, offset, value).map(|_| true)
+            }
+
+            fn has_dimension(
+                &self,
+                offset: &::ext_php_rs::types::Zval,
+                check_empty: bool,
+            ) -> ::ext_php_rs::exception::PhpResult<::std::option::Option<bool>> {
+                ::ext_php_rs::interfaces::has_dimension(self, offset, check_empty).map(Some)
+            }
+
+            fn unset_dimension(
+                &mut self,
+                offset: &::ext_php_rs::types::Zval,
+            ) -> ::ext_php_rs::exception::PhpResult<bool> {
+                ::ext_php_rs::interfaces::unset_dimension(self, offset).map(|_| true)
+            }
+        },
+        _ => quote! {},
+    }
+}
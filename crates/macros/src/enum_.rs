@@ -0,0 +1,297 @@
+use crate::STATE;
+use anyhow::{anyhow, bail, Result};
+use darling::{FromMeta, ToTokens};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{
+    Attribute, AttributeArgs, Expr, ExprLit, ExprUnary, Fields, ItemEnum, Lit, LitStr, Token, UnOp,
+};
+
+use crate::class::{parse_attribute, ParsedAttribute};
+
+#[derive(Debug, Default)]
+pub struct Enum {
+    pub enum_name: String,
+    pub docs: Vec<String>,
+    pub cases: Vec<Case>,
+    pub interfaces: Vec<String>,
+    pub methods: Vec<crate::method::Method>,
+    pub constants: Vec<crate::constant::Constant>,
+}
+
+#[derive(Debug)]
+pub struct Case {
+    pub name: String,
+    pub variant: String,
+    pub docs: Vec<String>,
+    pub value: Option<CaseValue>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CaseValue {
+    Long(i64),
+    Str(String),
+}
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    name: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct CaseAttr {
+    pub name: Option<String>,
+    pub value: Option<CaseValue>,
+}
+
+pub fn parser(args: AttributeArgs, mut input: ItemEnum) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
+
+    let mut interfaces = vec![];
+    let mut comments = vec![];
+
+    input.attrs = {
+        let mut unused = vec![];
+        for attr in input.attrs.into_iter() {
+            match parse_attribute(&attr)? {
+                Some(parsed) => match parsed {
+                    ParsedAttribute::Implements(class) => {
+                        interfaces.push(class.to_token_stream().to_string());
+                    }
+                    ParsedAttribute::Comment(comment) => {
+                        comments.push(comment);
+                    }
+                    attr => bail!("Attribute `{:?}` is not valid for enums.", attr),
+                },
+                None => unused.push(attr),
+            }
+        }
+        unused
+    };
+
+    let mut cases = vec![];
+    for variant in input.variants.iter_mut() {
+        if !matches!(variant.fields, Fields::Unit) {
+            bail!(
+                "Enum variant `{}` must not have any fields to be exported to PHP.",
+                variant.ident
+            );
+        }
+
+        let mut docs = vec![];
+        let mut case_attr = CaseAttr::default();
+        let attrs = std::mem::take(&mut variant.attrs);
+        for attr in attrs.into_iter() {
+            if attr.path.is_ident("case") {
+                case_attr = parse_case_attribute(&attr)?;
+                continue;
+            }
+            if let Some(ParsedAttribute::Comment(doc)) = parse_attribute(&attr)? {
+                docs.push(doc);
+            }
+            variant.attrs.push(attr);
+        }
+
+        let value = match (case_attr.value, &variant.discriminant) {
+            (Some(value), _) => Some(value),
+            (None, Some((_, expr))) => {
+                Some(CaseValue::Long(parse_long(expr).ok_or_else(|| {
+                    anyhow!(
+                        "Discriminant of enum variant `{}` must be an integer literal.",
+                        variant.ident
+                    )
+                })?))
+            }
+            (None, None) => None,
+        };
+
+        cases.push(Case {
+            name: case_attr.name.unwrap_or_else(|| variant.ident.to_string()),
+            variant: variant.ident.to_string(),
+            docs,
+            value,
+        });
+    }
+
+    let backing = backing_type(&cases)?;
+    let ItemEnum { ident, .. } = &input;
+    let enum_name = args.name.unwrap_or_else(|| ident.to_string());
+    let meta = Ident::new(&format!("_{ident}_META"), Span::call_site());
+
+    let case_defs = cases
+        .iter()
+        .map(|case| {
+            let name = &case.name;
+            let value = match &case.value {
+                Some(CaseValue::Long(val)) => {
+                    quote! { Some(::ext_php_rs::enum_::EnumValue::Long(#val)) }
+                }
+                Some(CaseValue::Str(val)) => {
+                    quote! { Some(::ext_php_rs::enum_::EnumValue::Str(#val)) }
+                }
+                None => quote! { None },
+            };
+            quote! {
+                ::ext_php_rs::enum_::EnumCase {
+                    name: #name,
+                    value: #value,
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+    let to_name = cases
+        .iter()
+        .map(|case| {
+            let name = &case.name;
+            let variant = Ident::new(&case.variant, Span::call_site());
+            quote! { Self::#variant => #name, }
+        })
+        .collect::<Vec<_>>();
+    let from_name = cases
+        .iter()
+        .map(|case| {
+            let name = &case.name;
+            let variant = Ident::new(&case.variant, Span::call_site());
+            quote! { #name => Some(Self::#variant), }
+        })
+        .collect::<Vec<_>>();
+    let backing_tokens = match backing {
+        Some(ty) => {
+            let ty = Ident::new(ty, Span::call_site());
+            quote! { Some(::ext_php_rs::flags::DataType::#ty) }
+        }
+        None => quote! { None },
+    };
+
+    let mut state = STATE.lock();
+
+    if state.built_module {
+        bail!("The `#[php_module]` macro must be called last to ensure functions and classes are registered.");
+    }
+
+    if state.startup_function.is_some() {
+        bail!("The `#[php_startup]` macro must be called after all the enums have been defined.");
+    }
+
+    state.enums.insert(
+        ident.to_string(),
+        Enum {
+            enum_name: enum_name.clone(),
+            docs: comments,
+            cases,
+            interfaces,
+            ..Default::default()
+        },
+    );
+
+    Ok(quote! {
+        #input
+
+        static #meta: ::ext_php_rs::enum_::EnumMetadata = ::ext_php_rs::enum_::EnumMetadata::new();
+
+        impl ::ext_php_rs::enum_::RegisteredEnum for #ident {
+            const CLASS_NAME: &'static str = #enum_name;
+            const BACKING_TYPE: ::std::option::Option<::ext_php_rs::flags::DataType> = #backing_tokens;
+            const CASES: &'static [::ext_php_rs::enum_::EnumCase] = &[
+                #(#case_defs)*
+            ];
+
+            fn get_metadata() -> &'static ::ext_php_rs::enum_::EnumMetadata {
+                &#meta
+            }
+
+            fn case_name(&self) -> &'static str {
+                match self {
+                    #(#to_name)*
+                }
+            }
+
+            fn from_case_name(name: &str) -> ::std::option::Option<Self> {
+                match name {
+                    #(#from_name)*
+                    _ => None,
+                }
+            }
+        }
+
+        ::ext_php_rs::enum_derives!(#ident);
+    })
+}
+
+/// Returns the name of the `DataType` variant backing the enum, or `None` for a
+/// pure enum.
+fn backing_type(cases: &[Case]) -> Result<Option<&'static str>> {
+    let mut backing = None;
+    for (i, case) in cases.iter().enumerate() {
+        let ty = case.value.as_ref().map(|value| match value {
+            CaseValue::Long(_) => "Long",
+            CaseValue::Str(_) => "String",
+        });
+        if i == 0 {
+            backing = ty;
+        } else if ty != backing {
+            bail!(
+                "Case `{}` does not match the backing of the other cases. Either all or none of the cases must have a value of the same type.",
+                case.name
+            );
+        }
+    }
+    Ok(backing)
+}
+
+fn parse_long(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => parse_long(expr).map(|val| -val),
+        _ => None,
+    }
+}
+
+fn parse_case_attribute(attr: &Attribute) -> Result<CaseAttr> {
+    attr.parse_args()
+        .map_err(|e| anyhow!("Unable to parse `#[case]` attribute: {}", e))
+}
+
+impl syn::parse::Parse for CaseAttr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut this = Self::default();
+        while !input.is_empty() {
+            let field = input.parse::<Ident>()?.to_string();
+            input.parse::<Token![=]>()?;
+
+            match field.as_str() {
+                "name" => {
+                    this.name.replace(input.parse::<LitStr>()?.value());
+                }
+                "value" => {
+                    let expr = input.parse::<Expr>()?;
+                    let value = match &expr {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(val), ..
+                        }) => CaseValue::Str(val.value()),
+                        expr => CaseValue::Long(parse_long(expr).ok_or_else(|| {
+                            syn::Error::new_spanned(
+                                expr,
+                                "case value must be an integer or string literal",
+                            )
+                        })?),
+                    };
+                    this.value.replace(value);
+                }
+                _ => return Err(input.error("invalid attribute field")),
+            }
+
+            let _ = input.parse::<Token![,]>();
+        }
+
+        Ok(this)
+    }
+}
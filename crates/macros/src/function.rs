@@ -4,7 +4,7 @@ use crate::helpers::get_docs;
 use crate::{syn_ext::DropLifetimes, STATE};
 use anyhow::{anyhow, bail, Result};
 use darling::{FromMeta, ToTokens};
-use proc_macro2::{Ident, Literal, Span, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use syn::{
     punctuated::Punctuated, AttributeArgs, FnArg, GenericArgument, ItemFn, Lit, PathArguments,
@@ -17,6 +17,7 @@ pub struct AttrArgs {
     optional: Option<String>,
     ignore_module: bool,
     defaults: HashMap<String, Lit>,
+    default_exprs: HashMap<String, String>,
     name: Option<String>,
 }
 
@@ -26,10 +27,21 @@ pub struct Arg {
     pub ty: String,
     pub nullable: bool,
     pub default: Option<String>,
+    pub default_literal: Option<String>,
     pub as_ref: bool,
     pub variadic: bool,
 }
 
+/// The default value of a parameter.
+#[derive(Debug, Clone)]
+pub struct ArgDefault {
+    /// The PHP expression of the value, written into the argument info.
+    pub expr: String,
+    /// The Rust literal the value was given as, which is used directly when the
+    /// argument is not passed.
+    pub literal: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Function {
     pub name: String,
@@ -55,16 +67,29 @@ pub fn parser(args: AttributeArgs, input: ItemFn, should_register: Option<bool>)
     } = &sig;
 
     let internal_ident = Ident::new(&format!("_internal_php_{ident}"), Span::call_site());
-    let args = build_args(inputs, &attr_args.defaults)?;
+    let defaults = php_defaults(&attr_args.defaults, &attr_args.default_exprs);
+    let args = build_args(inputs, &defaults)?;
     let optional = find_optional_parameter(args.iter(), attr_args.optional);
-    let arg_definitions = build_arg_definitions(&args);
-    let arg_parser = build_arg_parser(
-        args.iter(),
-        &optional,
-        &quote! { return; },
-        ParserType::Function,
-    )?;
-    let arg_accessors = build_arg_accessors(&args);
+    // Variadic functions need the arguments to be collected, other functions read
+    // them in place from the call frame.
+    let (arg_parser, arg_accessors) = if args.iter().any(|arg| arg.variadic) {
+        let arg_definitions = build_arg_definitions(&args);
+        let arg_parser = build_arg_parser(
+            args.iter(),
+            &optional,
+            &quote! { return; },
+            ParserType::Function,
+        )?;
+        (
+            quote! {
+                #(#arg_definitions)*
+                #arg_parser
+            },
+            build_arg_accessors(&args),
+        )
+    } else {
+        build_fast_arg_parser(&args, &optional, &quote! { return; }, ParserType::Function)?
+    };
 
     let return_type = get_return_type(output)?;
 
@@ -76,7 +101,6 @@ pub fn parser(args: AttributeArgs, input: ItemFn, should_register: Option<bool>)
             pub extern fn #internal_ident(ex: &mut ::ext_php_rs::zend::ExecuteData, retval: &mut ::ext_php_rs::types::Zval) {
                 use ::ext_php_rs::convert::IntoZval;
 
-                #(#arg_definitions)*
                 #arg_parser
 
                 let result = #ident(#(#arg_accessors, )*);
@@ -113,9 +137,59 @@ pub fn parser(args: AttributeArgs, input: ItemFn, should_register: Option<bool>)
     Ok((func, function))
 }
 
+/// Merges the default values given as Rust literals with the ones given as PHP
+/// expressions.
+pub fn php_defaults(
+    defaults: &HashMap<String, Lit>,
+    exprs: &HashMap<String, String>,
+) -> HashMap<String, ArgDefault> {
+    defaults
+        .iter()
+        .map(|(name, lit)| {
+            let default = ArgDefault {
+                expr: php_default(lit),
+                literal: Some(lit.to_token_stream().to_string()),
+            };
+            (name.clone(), default)
+        })
+        .chain(exprs.iter().map(|(name, expr)| {
+            let default = ArgDefault {
+                expr: expr.clone(),
+                literal: None,
+            };
+            (name.clone(), default)
+        }))
+        .collect()
+}
+
+/// Returns the PHP expression of a default value given as a Rust literal.
+pub fn php_default(lit: &Lit) -> String {
+    match lit {
+        Lit::Str(val) => php_str(&val.value()),
+        Lit::Char(val) => php_str(&val.value().to_string()),
+        Lit::ByteStr(val) => format!(
+            "\"{}\"",
+            val.value()
+                .iter()
+                .map(|byte| format!("\\x{byte:02x}"))
+                .collect::<String>()
+        ),
+        Lit::Byte(val) => val.value().to_string(),
+        Lit::Int(val) => val.base10_digits().to_string(),
+        Lit::Float(val) => val.base10_digits().to_string(),
+        Lit::Bool(val) => val.value.to_string(),
+        Lit::Verbatim(val) => val.to_string(),
+    }
+}
+
+/// Writes a string as a single-quoted PHP string literal.
+fn php_str(val: &str) -> String {
+    format!("'{}'", val.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
 fn build_args(
     inputs: &Punctuated<FnArg, Token![,]>,
-    defaults: &HashMap<String, Lit>,
+    defaults: &HashMap<String, ArgDefault>,
 ) -> Result<Vec<Arg>> {
     inputs
         .iter()
@@ -139,7 +213,7 @@ fn build_arg_definitions(args: &[Arg]) -> Vec<TokenStream> {
     args.iter()
         .map(|ty| {
             let ident = ty.get_name_ident();
-            let definition = ty.get_arg_definition();
+            let definition = ty.get_parser_arg_definition();
             quote! {
                 let mut #ident = #definition;
             }
@@ -172,6 +246,7 @@ pub enum ParserType {
     Function,
     Method,
     StaticMethod,
+    EnumMethod,
 }
 
 pub fn build_arg_parser<'a>(
@@ -205,25 +280,12 @@ pub fn build_arg_parser<'a>(
             }
         })
         .collect::<Result<Vec<_>>>()?;
-    let (parser, this) = match ty {
-        ParserType::Function | ParserType::StaticMethod => {
-            (quote! { let parser = ex.parser(); }, None)
-        }
-        ParserType::Method => (
-            quote! { let (parser, this) = ex.parser_method::<Self>(); },
-            Some(quote! {
-                let this = match this {
-                    Some(this) => this,
-                    None => {
-                        ::ext_php_rs::exception::PhpException::default("Failed to retrieve reference to `$this`".into())
-                            .throw()
-                            .unwrap();
-                        return;
-                    },
-                };
-            }),
-        ),
+    let parser = match ty {
+        ParserType::Function | ParserType::StaticMethod => quote! { let parser = ex.parser(); },
+        ParserType::Method => quote! { let (parser, this) = ex.parser_method::<Self>(); },
+        ParserType::EnumMethod => quote! { let (parser, this) = ex.parser_object(); },
     };
+    let this = get_this(&ty);
 
     Ok(quote! {
         #parser
@@ -239,6 +301,100 @@ pub fn build_arg_parser<'a>(
     })
 }
 
+/// Returns the statement retrieving `$this` from the value returned by the
+/// parser, or [`None`] if the parser is not for a method called on an object.
+fn get_this(ty: &ParserType) -> Option<TokenStream> {
+    let this = match ty {
+        ParserType::Function | ParserType::StaticMethod => return None,
+        ParserType::Method => quote! { this },
+        ParserType::EnumMethod => quote! {
+            this.and_then(|this| {
+                <Self as ::ext_php_rs::enum_::RegisteredEnum>::from_case_object(this)
+            })
+        },
+    };
+
+    Some(quote! {
+        let this = match #this {
+            Some(this) => this,
+            None => {
+                ::ext_php_rs::exception::PhpException::default("Failed to retrieve reference to `$this`".into())
+                    .throw()
+                    .unwrap();
+                return;
+            },
+        };
+    })
+}
+
+/// Builds a parser which reads the arguments in place from the call frame,
+/// returning the parser along with the accessors of the arguments.
+pub fn build_fast_arg_parser(
+    args: &[Arg],
+    optional: &Option<String>,
+    ret: &TokenStream,
+    ty: ParserType,
+) -> Result<(TokenStream, Vec<TokenStream>)> {
+    let max_num_args = args.len();
+    let min_num_args = optional
+        .as_ref()
+        .and_then(|opt| args.iter().position(|arg| arg.name == *opt))
+        .unwrap_or(max_num_args);
+
+    if let Some(arg) = args[min_num_args..]
+        .iter()
+        .find(|arg| !arg.nullable && arg.default.is_none())
+    {
+        bail!(
+            "Parameter `{}` must be a variant of `Option` or have a default value as it is optional.",
+            arg.name
+        );
+    }
+
+    // Default values given as PHP expressions are evaluated into zvals which
+    // outlive the parser, as the arguments may borrow from them.
+    let slots = args.iter().filter(|arg| arg.has_default_expr()).map(|arg| {
+        let slot = arg.get_default_slot_ident();
+        quote! { let mut #slot = ::ext_php_rs::types::Zval::new(); }
+    });
+    let (binding, constructor) = match ty {
+        ParserType::Function | ParserType::StaticMethod => (quote! { mut parser }, quote! { new }),
+        ParserType::Method => (quote! { (mut parser, this) }, quote! { new_method::<Self> }),
+        ParserType::EnumMethod => (quote! { (mut parser, this) }, quote! { new_object }),
+    };
+    let this = get_this(&ty);
+    let parser = quote! {
+        #(#slots)*
+        let #binding = match ::ext_php_rs::args::FastArgParser::#constructor(ex, #min_num_args, #max_num_args) {
+            Ok(parser) => parser,
+            Err(_) => { #ret }
+        };
+        #this
+    };
+    let accessors = args
+        .iter()
+        .map(|arg| {
+            let slot = arg.get_default_slot_ident();
+            let value = match (&arg.default, arg.get_default_literal(), arg.nullable) {
+                (_, Some(lit), true) => quote! { parser.default_nullable_with(|| #lit.into()) },
+                (_, Some(lit), false) => quote! { parser.default_with(|| #lit.into()) },
+                (Some(_), None, true) => quote! { parser.default_nullable(&mut #slot) },
+                (Some(_), None, false) => quote! { parser.default(&mut #slot) },
+                (None, None, true) => quote! { parser.nullable() },
+                (None, None, false) => quote! { parser.required() },
+            };
+            quote! {
+                match #value {
+                    Ok(val) => val,
+                    Err(_) => { #ret }
+                }
+            }
+        })
+        .collect();
+
+    Ok((parser, accessors))
+}
+
 fn build_arg_accessors(args: &[Arg]) -> Vec<TokenStream> {
     args.iter()
         .map(|arg| arg.get_accessor(&quote! { return; }))
@@ -259,15 +415,20 @@ impl Arg {
         name: String,
         ty: String,
         nullable: bool,
-        default: Option<String>,
+        default: Option<ArgDefault>,
         as_ref: bool,
         variadic: bool,
     ) -> Self {
+        let (default, default_literal) = match default {
+            Some(default) => (Some(default.expr), default.literal),
+            None => (None, None),
+        };
         Self {
             name,
             ty,
             nullable,
             default,
+            default_literal,
             as_ref,
             variadic,
         }
@@ -276,17 +437,18 @@ impl Arg {
     pub fn from_type(
         name: String,
         ty: &syn::Type,
-        default: Option<&Lit>,
+        default: Option<&ArgDefault>,
         is_return: bool,
     ) -> Option<Arg> {
-        let default = default.map(|lit| lit.to_token_stream().to_string());
+        let default = default.cloned();
         match ty {
             Type::Path(TypePath { path, .. }) => {
                 let mut path = path.clone();
-                let mut pass_by_ref = false;
                 path.drop_lifetimes();
 
                 let seg = path.segments.last()?;
+                // `Ref<T>` parameters are always passed by reference.
+                let mut pass_by_ref = seg.ident == "Ref";
                 let result = Some(seg)
                     .filter(|seg| seg.ident == "Result")
                     .and_then(|seg| {
@@ -339,13 +501,15 @@ impl Arg {
                         None => path.to_token_stream().to_string(),
                     },
                 };
+                // `Variadic` collects the remaining positional and named arguments.
+                let is_variadic = seg.ident == "Variadic" && !is_return;
                 Some(Arg::new(
                     name,
                     stringified,
                     seg.ident == "Option" || default.is_some(),
                     default,
                     pass_by_ref,
-                    false,
+                    is_variadic,
                 ))
             }
             Type::Reference(ref_) => {
@@ -381,24 +545,68 @@ impl Arg {
         Ident::new(&self.name, Span::call_site())
     }
 
+    /// Returns true if the default value of the argument is a PHP expression,
+    /// which is evaluated by the parser.
+    pub fn has_default_expr(&self) -> bool {
+        self.default.is_some() && self.default_literal.is_none()
+    }
+
+    /// Returns the identifier of the zval the default value of the argument is
+    /// evaluated into.
+    fn get_default_slot_ident(&self) -> Ident {
+        Ident::new(&format!("_default_{}", self.name), Span::call_site())
+    }
+
+    /// Returns the default value of the argument as a Rust literal, if it was
+    /// given as one.
+    fn get_default_literal(&self) -> Option<TokenStream> {
+        self.default_literal
+            .as_ref()
+            .map(|lit| syn::parse_str::<Lit>(lit).unwrap().to_token_stream())
+    }
+
     /// Returns a [`TokenStream`] containing the line required to retrieve the
     /// value from the argument.
     pub fn get_accessor(&self, ret: &TokenStream) -> TokenStream {
         let name = &self.name;
         let name_ident = self.get_name_ident();
 
-        if let Some(default) = self.default.as_ref() {
-            // `bool`s are not literals - need to use Ident.
-            let val = syn::parse_str::<Literal>(default)
-                .map(|lit| lit.to_token_stream())
-                .or_else(|_| Ident::from_string(default).map(|ident| ident.to_token_stream()))
-                .unwrap_or(quote! { Default::default() });
-
-            quote! { #name_ident.val().unwrap_or(#val.into()) }
+        // Default values given as Rust literals are used directly, others are
+        // evaluated by the parser.
+        if let Some(lit) = self.get_default_literal() {
+            quote! {
+                match #name_ident.val_or(|| #lit.into()) {
+                    Some(val) => val,
+                    None => {
+                        ::ext_php_rs::exception::PhpException::default(
+                            concat!("Invalid value given for argument `", #name, "`.").into()
+                        )
+                        .throw()
+                        .expect(concat!("Failed to throw exception: Invalid value given for argument `", #name, "`."));
+                        #ret
+                    }
+                }
+            }
+        } else if self.default.is_some() {
+            quote! {
+                match #name_ident.val_or_default() {
+                    Some(val) => val,
+                    None => {
+                        ::ext_php_rs::exception::PhpException::default(
+                            concat!("Invalid value given for argument `", #name, "`.").into()
+                        )
+                        .throw()
+                        .expect(concat!("Failed to throw exception: Invalid value given for argument `", #name, "`."));
+                        #ret
+                    }
+                }
+            }
         } else if self.nullable {
             quote! { #name_ident.val() }
-        } else if self.variadic {
+        } else if self.variadic && self.ty.starts_with('&') {
             quote! { &#name_ident.variadic_vals() }
+        } else if self.variadic {
+            quote! { #name_ident.variadic_args() }
         } else {
             quote! {
                 match #name_ident.val() {
@@ -419,6 +627,17 @@ impl Arg {
     /// Returns a [`TokenStream`] containing the line required to instantiate
     /// the argument.
     pub fn get_arg_definition(&self) -> TokenStream {
+        self.build_arg_definition(self.default.as_ref())
+    }
+
+    /// Returns a [`TokenStream`] containing the line required to instantiate
+    /// the argument passed to the parser. Default values given as Rust literals
+    /// are left out, as they are not evaluated by the parser.
+    pub fn get_parser_arg_definition(&self) -> TokenStream {
+        self.build_arg_definition(self.default.as_ref().filter(|_| self.has_default_expr()))
+    }
+
+    fn build_arg_definition(&self, default: Option<&String>) -> TokenStream {
         let name = &self.name;
         let mut ty = self.get_type_ident();
 
@@ -428,7 +647,7 @@ impl Arg {
         if self.variadic {
             ty = quote! { ::ext_php_rs::flags::DataType::Mixed }
         }
-        let default = self.default.as_ref().map(|val| {
+        let default = default.map(|val| {
             quote! {
                 .default(#val)
             }
@@ -3,12 +3,15 @@ use darling::{FromMeta, ToTokens};
 use proc_macro2::TokenStream;
 use quote::quote;
 use std::collections::HashMap;
-use syn::{Attribute, AttributeArgs, ItemImpl, Lit, Meta, NestedMeta};
+use syn::{
+    Attribute, AttributeArgs, ImplItem, ImplItemMethod, ItemImpl, Lit, Meta, NestedMeta, Type,
+};
 
 use crate::helpers::get_docs;
 use crate::{
     class::{Property, PropertyAttr},
     constant::Constant,
+    enum_::Enum,
     method,
 };
 
@@ -69,7 +72,7 @@ impl RenameRule {
 
 #[derive(Debug)]
 pub enum ParsedAttribute {
-    Default(HashMap<String, Lit>),
+    Default(HashMap<String, crate::function::ArgDefault>),
     Optional(String),
     Visibility(Visibility),
     Rename(String),
@@ -79,6 +82,7 @@ pub enum ParsedAttribute {
     },
     Constructor,
     This,
+    CalledScope,
     Abstract,
 }
 
@@ -113,9 +117,13 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
         );
     }
 
+    if let Some(enum_) = state.enums.get_mut(&class_name) {
+        return enum_parser(&args, &self_ty, items, enum_);
+    }
+
     let class = state.classes.get_mut(&class_name).ok_or_else(|| {
         anyhow!(
-            "You must use `#[php_class]` on the struct before using this attribute on the impl."
+            "You must use `#[php_class]` on the struct or `#[php_enum]` on the enum before using this attribute on the impl."
         )
     })?;
 
@@ -123,22 +131,89 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
         .into_iter()
         .map(|item| {
             Ok(match item {
-                syn::ImplItem::Const(constant) => {
-                    class.constants.push(Constant {
-                        name: constant.ident.to_string(),
-                        // visibility: Visibility::Public,
-                        docs: get_docs(&constant.attrs),
-                        value: constant.expr.to_token_stream().to_string(),
-                    });
+                syn::ImplItem::Const(mut constant) => {
+                    let docs = get_docs(&constant.attrs);
+                    let mut prop = None;
+                    let mut unused = vec![];
+                    for attr in std::mem::take(&mut constant.attrs) {
+                        match crate::class::parse_attribute(&attr)? {
+                            Some(crate::class::ParsedAttribute::Property(attr)) => {
+                                prop = Some(attr)
+                            }
+                            _ => unused.push(attr),
+                        }
+                    }
+                    constant.attrs = unused;
+
+                    // Constants marked with `#[prop]` are declared as static properties,
+                    // using the value of the constant as the initial value.
+                    match prop {
+                        Some(prop) => {
+                            if prop.readonly {
+                                bail!("Static properties cannot be readonly.");
+                            }
+                            let name = prop.rename.unwrap_or_else(|| constant.ident.to_string());
+                            let rust_ty = crate::function::Arg::from_type(
+                                String::new(),
+                                &constant.ty,
+                                None,
+                                false,
+                            )
+                            .map(|arg| (arg.ty, arg.nullable));
+                            class.properties.insert(
+                                name,
+                                Property::static_(
+                                    constant.expr.to_token_stream().to_string(),
+                                    rust_ty,
+                                    docs,
+                                    prop.flags.map(|flags| flags.to_token_stream().to_string()),
+                                ),
+                            );
+                        }
+                        None => class.constants.push(Constant {
+                            name: constant.ident.to_string(),
+                            // visibility: Visibility::Public,
+                            docs,
+                            value: constant.expr.to_token_stream().to_string(),
+                        }),
+                    }
 
                     quote! {
                         #[allow(dead_code)]
                         #constant
                     }
                 }
-                syn::ImplItem::Method(method) => {
-                    let parsed_method =
-                        method::parser(&self_ty, method, args.rename_methods.unwrap_or_default())?;
+                syn::ImplItem::Method(mut method) => {
+                    if let Some(constant) = parse_constant_method(&self_ty, &mut method)? {
+                        class.constants.push(constant);
+                        return Ok(method.to_token_stream());
+                    }
+
+                    // Methods marked with `#[handler]` override an object handler of the
+                    // class rather than being exported as PHP methods.
+                    if let Some(pos) = method
+                        .attrs
+                        .iter()
+                        .position(|attr| attr.path.is_ident("handler"))
+                    {
+                        method.attrs.remove(pos);
+                        let name = method.sig.ident.to_string();
+                        if crate::class::handler_impl(&name).is_none() {
+                            bail!(
+                                "`{}` is not an object handler which can be overridden.",
+                                name
+                            );
+                        }
+                        class.handlers.push(name);
+                        return Ok(method.to_token_stream());
+                    }
+
+                    let parsed_method = method::parser(
+                        &self_ty,
+                        method,
+                        args.rename_methods.unwrap_or_default(),
+                        false,
+                    )?;
 
                     // TODO(david): How do we handle comments for getter/setter? Take the comments
                     // from the methods??
@@ -150,8 +225,23 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
                         let ident = parsed_method.method.orig_ident.clone();
 
                         match ty {
-                            PropAttrTy::Getter => prop.add_getter(ident)?,
-                            PropAttrTy::Setter => prop.add_setter(ident)?,
+                            PropAttrTy::Getter => {
+                                prop.add_getter(ident)?;
+                                prop.rust_ty = parsed_method.method.output.clone();
+                            }
+                            PropAttrTy::Setter => {
+                                prop.add_setter(ident)?;
+                                if prop.rust_ty.is_none() {
+                                    prop.rust_ty = parsed_method.method.args.iter().find_map(
+                                        |arg| match arg {
+                                            method::Arg::Typed(arg) => {
+                                                Some((arg.ty.clone(), arg.nullable))
+                                            }
+                                            _ => None,
+                                        },
+                                    );
+                                }
+                            }
                         }
                     }
                     if parsed_method.constructor {
@@ -178,6 +268,99 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
     Ok(output)
 }
 
+/// Parses the items of an impl block attached to a `#[php_enum]`.
+fn enum_parser(
+    args: &AttrArgs,
+    self_ty: &Type,
+    items: Vec<ImplItem>,
+    enum_: &mut Enum,
+) -> Result<TokenStream> {
+    let tokens = items
+        .into_iter()
+        .map(|item| {
+            Ok(match item {
+                ImplItem::Const(constant) => {
+                    enum_.constants.push(Constant {
+                        name: constant.ident.to_string(),
+                        docs: get_docs(&constant.attrs),
+                        value: constant.expr.to_token_stream().to_string(),
+                    });
+
+                    quote! {
+                        #[allow(dead_code)]
+                        #constant
+                    }
+                }
+                ImplItem::Method(mut method) => {
+                    if let Some(constant) = parse_constant_method(self_ty, &mut method)? {
+                        enum_.constants.push(constant);
+                        return Ok(method.to_token_stream());
+                    }
+
+                    let parsed_method = method::parser(
+                        self_ty,
+                        method,
+                        args.rename_methods.unwrap_or_default(),
+                        true,
+                    )?;
+
+                    if parsed_method.property.is_some() {
+                        bail!("Enums cannot have properties.");
+                    }
+                    if parsed_method.constructor {
+                        bail!("Enums cannot have constructors.");
+                    }
+                    enum_.methods.push(parsed_method.method);
+                    parsed_method.tokens
+                }
+                item => item.to_token_stream(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #self_ty {
+            #(#tokens)*
+        }
+    })
+}
+
+/// Parses an associated function marked with `#[constant]`, which declares a
+/// class constant whose value is computed by calling the function when the
+/// class is registered. The name of the constant is the name of the function in
+/// upper case, unless renamed with `#[rename]`.
+fn parse_constant_method(self_ty: &Type, method: &mut ImplItemMethod) -> Result<Option<Constant>> {
+    let pos = match method
+        .attrs
+        .iter()
+        .position(|attr| attr.path.is_ident("constant"))
+    {
+        Some(pos) => pos,
+        None => return Ok(None),
+    };
+    method.attrs.remove(pos);
+
+    let ident = &method.sig.ident;
+    if !method.sig.inputs.is_empty() {
+        bail!("Constant function `{}` cannot take any parameters.", ident);
+    }
+
+    let mut name = None;
+    for attr in std::mem::take(&mut method.attrs) {
+        match parse_attribute(&attr)? {
+            Some(ParsedAttribute::Rename(rename)) => name = Some(rename),
+            Some(_) => bail!("Invalid attribute for constant function `{}`.", ident),
+            None => method.attrs.push(attr),
+        }
+    }
+
+    Ok(Some(Constant {
+        name: name.unwrap_or_else(|| ident.to_string().to_uppercase()),
+        docs: get_docs(&method.attrs),
+        value: quote! { <#self_ty>::#ident() }.to_string(),
+    }))
+}
+
 pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
     let name = attr.path.to_token_stream().to_string();
     let meta = attr
@@ -186,9 +369,14 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
 
     Ok(Some(match name.as_ref() {
         "defaults" => {
-            let defaults = HashMap::from_meta(&meta)
+            let defaults: HashMap<String, Lit> = HashMap::from_meta(&meta)
                 .map_err(|_| anyhow!("Unable to parse `#[defaults]` macro."))?;
-            ParsedAttribute::Default(defaults)
+            ParsedAttribute::Default(crate::function::php_defaults(&defaults, &HashMap::new()))
+        }
+        "default_exprs" => {
+            let exprs = HashMap::from_meta(&meta)
+                .map_err(|_| anyhow!("Unable to parse `#[default_exprs]` macro."))?;
+            ParsedAttribute::Default(crate::function::php_defaults(&HashMap::new(), &exprs))
         }
         "optional" => {
             let name = if let Meta::List(list) = meta {
@@ -252,6 +440,7 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
         }
         "constructor" => ParsedAttribute::Constructor,
         "this" => ParsedAttribute::This,
+        "called_scope" => ParsedAttribute::CalledScope,
         _ => return Ok(None),
     }))
 }
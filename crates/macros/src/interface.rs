@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use darling::{FromMeta, ToTokens};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{AttributeArgs, ItemTrait, TraitItem, TraitItemMethod, Type, TypeParamBound};
+
+use crate::{
+    constant::Constant,
+    function,
+    helpers::get_docs,
+    impl_::{parse_attribute, ParsedAttribute, RenameRule, Visibility},
+    method::{self, Arg, Method, MethodType},
+    STATE,
+};
+
+#[derive(Debug, Default)]
+pub struct Interface {
+    pub interface_name: String,
+    pub ident: String,
+    pub docs: Vec<String>,
+    /// Idents of the `#[php_interface]` traits this interface extends.
+    pub extends: Vec<String>,
+    /// PHP names of the interfaces this interface extends.
+    pub parent_names: Vec<String>,
+    pub methods: Vec<Method>,
+    pub constants: Vec<Constant>,
+}
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    name: Option<String>,
+    rename_methods: Option<RenameRule>,
+}
+
+pub fn parser(args: AttributeArgs, mut input: ItemTrait) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
+
+    let ident = &input.ident;
+    let interface_name = args.name.unwrap_or_else(|| ident.to_string());
+    let rename_rule = args.rename_methods.unwrap_or_default();
+    let meta = Ident::new(&format!("_{ident}_META"), Span::call_site());
+
+    let mut state = STATE.lock();
+
+    if state.built_module {
+        bail!("The `#[php_module]` macro must be called last to ensure functions and classes are registered.");
+    }
+
+    if state.startup_function.is_some() {
+        bail!(
+            "The `#[php_startup]` macro must be called after all the interfaces have been defined."
+        );
+    }
+
+    // Only supertraits which are themselves exported interfaces are inherited in
+    // PHP, any other bounds are Rust-only.
+    let (extends, parent_names) = input
+        .supertraits
+        .iter()
+        .filter_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => Some(bound.path.to_token_stream().to_string()),
+            _ => None,
+        })
+        .filter_map(|name| {
+            state
+                .interfaces
+                .iter()
+                .find(|iface| iface.ident == name)
+                .map(|iface| (name, iface.interface_name.clone()))
+        })
+        .unzip();
+
+    let mut methods = vec![];
+    let mut constants = vec![];
+    for item in input.items.iter_mut() {
+        match item {
+            TraitItem::Const(constant) => {
+                let value = match &constant.default {
+                    Some((_, expr)) => expr.to_token_stream().to_string(),
+                    None => bail!(
+                        "Interface constant `{}` must have a value to be exported to PHP.",
+                        constant.ident
+                    ),
+                };
+                constants.push(Constant {
+                    name: constant.ident.to_string(),
+                    docs: get_docs(&constant.attrs),
+                    value,
+                });
+            }
+            TraitItem::Method(method) => methods.push(parse_method(method, rename_rule)?),
+            _ => {}
+        }
+    }
+
+    state.interfaces.push(Interface {
+        interface_name,
+        ident: ident.to_string(),
+        docs: get_docs(&input.attrs),
+        extends,
+        parent_names,
+        methods,
+        constants,
+    });
+
+    Ok(quote! {
+        #input
+
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        static #meta: ::ext_php_rs::class::InterfaceMetadata = ::ext_php_rs::class::InterfaceMetadata::new();
+    })
+}
+
+fn parse_method(input: &mut TraitItemMethod, rename_rule: RenameRule) -> Result<Method> {
+    let mut defaults = HashMap::new();
+    let mut optional = None;
+    let mut identifier = None;
+    let docs = get_docs(&input.attrs);
+
+    let mut unused = vec![];
+    for attr in std::mem::take(&mut input.attrs) {
+        if attr.path.is_ident("doc") {
+            unused.push(attr);
+            continue;
+        }
+        match parse_attribute(&attr)? {
+            Some(ParsedAttribute::Default(list)) => defaults.extend(list),
+            Some(ParsedAttribute::Optional(name)) => optional = Some(name),
+            Some(ParsedAttribute::Rename(ident)) => identifier = Some(ident),
+            Some(_) => bail!("Invalid attribute for interface method."),
+            None => unused.push(attr),
+        }
+    }
+    input.attrs = unused;
+
+    let ident = &input.sig.ident;
+    let name = identifier.unwrap_or_else(|| rename_rule.rename(ident.to_string()));
+    let self_ty: Type = syn::parse_quote!(Self);
+    let args = method::build_args(&self_ty, &mut input.sig.inputs, &defaults)?;
+
+    if args
+        .iter()
+        .any(|arg| matches!(arg, Arg::Receiver(MethodType::ReceiverClassObject)))
+    {
+        bail!("The `#[this]` attribute cannot be used on interface methods.");
+    }
+
+    let optional = function::find_optional_parameter(
+        args.iter().filter_map(|arg| match arg {
+            Arg::Typed(arg) => Some(arg),
+            _ => None,
+        }),
+        optional,
+    );
+    let _static = !args.iter().any(|arg| matches!(arg, Arg::Receiver(_)));
+
+    Ok(Method {
+        name,
+        ident: String::new(),
+        orig_ident: ident.to_string(),
+        docs,
+        args,
+        optional,
+        output: method::get_return_type(&self_ty, &input.sig.output)?,
+        _static,
+        _abstract: true,
+        visibility: Visibility::Public,
+    })
+}
+
+impl Interface {
+    /// Returns the methods required by the interface, including the methods of
+    /// the interfaces it extends.
+    pub fn required_methods<'a>(&'a self, interfaces: &'a [Interface]) -> Vec<&'a Method> {
+        let mut methods: Vec<_> = self.methods.iter().collect();
+        for parent in &self.extends {
+            if let Some(parent) = interfaces.iter().find(|iface| &iface.ident == parent) {
+                methods.extend(parent.required_methods(interfaces));
+            }
+        }
+        methods
+    }
+}
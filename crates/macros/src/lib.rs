@@ -1,12 +1,15 @@
 mod class;
 mod constant;
+mod enum_;
 mod extern_;
 mod fastcall;
 mod function;
 mod helpers;
 mod impl_;
+mod interface;
 mod method;
 mod module;
+mod resource;
 mod startup_function;
 mod syn_ext;
 mod zval;
@@ -21,8 +24,8 @@ use constant::Constant;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use syn::{
-    parse_macro_input, AttributeArgs, DeriveInput, ItemConst, ItemFn, ItemForeignMod, ItemImpl,
-    ItemStruct,
+    parse_macro_input, AttributeArgs, DeriveInput, ItemConst, ItemEnum, ItemFn, ItemForeignMod,
+    ItemImpl, ItemStruct, ItemTrait,
 };
 
 extern crate proc_macro;
@@ -31,6 +34,9 @@ extern crate proc_macro;
 struct State {
     functions: Vec<function::Function>,
     classes: HashMap<String, class::Class>,
+    enums: HashMap<String, enum_::Enum>,
+    interfaces: Vec<interface::Interface>,
+    resources: HashMap<String, resource::Resource>,
     constants: Vec<Constant>,
     startup_function: Option<String>,
     built_module: bool,
@@ -64,6 +70,42 @@ pub fn php_class(args: TokenStream, input: TokenStream) -> TokenStream {
     .into()
 }
 
+#[proc_macro_attribute]
+pub fn php_enum(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let input = parse_macro_input!(input as ItemEnum);
+
+    match enum_::parser(args, input) {
+        Ok(parsed) => parsed,
+        Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
+    }
+    .into()
+}
+
+#[proc_macro_attribute]
+pub fn php_resource(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let input = parse_macro_input!(input as ItemStruct);
+
+    match resource::parser(args, input) {
+        Ok(parsed) => parsed,
+        Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
+    }
+    .into()
+}
+
+#[proc_macro_attribute]
+pub fn php_interface(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let input = parse_macro_input!(input as ItemTrait);
+
+    match interface::parser(args, input) {
+        Ok(parsed) => parsed,
+        Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
+    }
+    .into()
+}
+
 #[proc_macro_attribute]
 pub fn php_function(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as AttributeArgs);
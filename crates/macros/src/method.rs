@@ -10,12 +10,14 @@ use crate::{
 };
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{punctuated::Punctuated, FnArg, ImplItemMethod, Lit, Pat, Token, Type};
+use syn::{punctuated::Punctuated, FnArg, ImplItemMethod, Pat, Token, Type};
 
 #[derive(Debug, Clone)]
 pub enum Arg {
     Receiver(MethodType),
     Typed(function::Arg),
+    /// The class the method was called on, see `#[called_scope]`.
+    CalledScope,
 }
 
 #[derive(Debug, Clone)]
@@ -69,6 +71,7 @@ pub fn parser(
     struct_ty: &Type,
     mut input: ImplItemMethod,
     rename_rule: RenameRule,
+    is_enum: bool,
 ) -> Result<ParsedMethod> {
     let mut defaults = HashMap::new();
     let mut optional = None;
@@ -82,7 +85,7 @@ pub fn parser(
     for attr in input.attrs.iter() {
         if let Some(attr) = parse_attribute(attr)? {
             match attr {
-                ParsedAttribute::Default(list) => defaults = list,
+                ParsedAttribute::Default(list) => defaults.extend(list),
                 ParsedAttribute::Optional(name) => optional = Some(name),
                 ParsedAttribute::Visibility(vis) => visibility = vis,
                 ParsedAttribute::Abstract => is_abstract = true,
@@ -139,16 +142,34 @@ pub fn parser(
         optional,
     );
     let (arg_definitions, method_type) = build_arg_definitions(&args);
-    let arg_parser = build_arg_parser(
-        args.iter(),
-        &optional,
-        &bail,
-        match method_type {
-            MethodType::Static => ParserType::StaticMethod,
-            _ => ParserType::Method,
-        },
-    )?;
-    let arg_accessors = build_arg_accessors(&args, &bail);
+    if is_enum && matches!(method_type, MethodType::ReceiverClassObject) {
+        bail!("The `#[this]` attribute cannot be used on enum methods.");
+    }
+    if is_enum && has_mut_receiver(&input.sig.inputs) {
+        bail!("Enum methods cannot take `&mut self`, as enum cases are immutable.");
+    }
+    let parser_type = match method_type {
+        MethodType::Static => ParserType::StaticMethod,
+        _ if is_enum => ParserType::EnumMethod,
+        _ => ParserType::Method,
+    };
+    // Variadic methods need the arguments to be collected, other methods read
+    // them in place from the call frame.
+    let (arg_parser, arg_accessors) = if args
+        .iter()
+        .any(|arg| matches!(arg, Arg::Typed(arg) if arg.variadic))
+    {
+        let arg_parser = build_arg_parser(args.iter(), &optional, &bail, parser_type)?;
+        (
+            quote! {
+                #(#arg_definitions)*
+                #arg_parser
+            },
+            build_arg_accessors(&args, &bail),
+        )
+    } else {
+        build_fast_arg_parser(&args, &optional, &bail, parser_type)?
+    };
 
     let func = if is_constructor {
         quote! {
@@ -161,7 +182,6 @@ pub fn parser(
                 use ::ext_php_rs::convert::IntoZval;
                 use ::ext_php_rs::class::ConstructorResult;
 
-                #(#arg_definitions)*
                 #arg_parser
 
                 Self::#ident(#(#arg_accessors,)*).into()
@@ -172,6 +192,26 @@ pub fn parser(
             MethodType::Receiver => quote! { this. },
             MethodType::ReceiverClassObject | MethodType::Static => quote! { Self:: },
         };
+        // The value is borrowed while the method runs, which fails if the object
+        // is in use by a method further up the stack, or has not been initialized
+        // as instances of PHP subclasses are not initialized until
+        // `parent::__construct()` is called.
+        let borrow_this = (matches!(method_type, MethodType::Receiver) && !is_enum).then(|| {
+            let (binding, borrow) = if has_mut_receiver(&input.sig.inputs) {
+                (quote! { let mut this }, quote! { try_borrow_mut })
+            } else {
+                (quote! { let this }, quote! { try_borrow })
+            };
+            quote! {
+                #binding = match this.#borrow() {
+                    Ok(this) => this,
+                    Err(e) => {
+                        e.throw().expect("Failed to throw exception");
+                        return;
+                    }
+                };
+            }
+        });
 
         quote! {
             #input
@@ -184,8 +224,8 @@ pub fn parser(
                 ) {
                     use ::ext_php_rs::convert::IntoZval;
 
-                    #(#arg_definitions)*
                     #arg_parser
+                    #borrow_this
 
                     let result = #this #ident(#(#arg_accessors,)*);
 
@@ -290,10 +330,10 @@ fn replace_self(self_ty: &Type, ty: &mut Type) {
     }
 }
 
-fn build_args(
+pub fn build_args(
     struct_ty: &Type,
     inputs: &mut Punctuated<FnArg, Token![,]>,
-    defaults: &HashMap<String, Lit>,
+    defaults: &HashMap<String, function::ArgDefault>,
 ) -> Result<Vec<Arg>> {
     inputs
         .iter_mut()
@@ -306,11 +346,13 @@ fn build_args(
             }
             FnArg::Typed(ty) => {
                 let mut this = false;
+                let mut called_scope = false;
                 let attrs = std::mem::take(&mut ty.attrs);
                 for attr in attrs.into_iter() {
                     if let Some(attr) = parse_attribute(&attr)? {
                         match attr {
                             ParsedAttribute::This => this = true,
+                            ParsedAttribute::CalledScope => called_scope = true,
                             _ => bail!("Invalid attribute for argument."),
                         }
                     }
@@ -318,6 +360,8 @@ fn build_args(
 
                 if this {
                     Ok(Arg::Receiver(MethodType::ReceiverClassObject))
+                } else if called_scope {
+                    Ok(Arg::CalledScope)
                 } else {
                     let name = match &*ty.pat {
                         Pat::Ident(pat) => pat.ident.to_string(),
@@ -337,6 +381,13 @@ fn build_args(
         .collect()
 }
 
+fn has_mut_receiver(inputs: &Punctuated<FnArg, Token![,]>) -> bool {
+    inputs.iter().any(|arg| match arg {
+        FnArg::Receiver(receiver) => receiver.mutability.is_some(),
+        _ => false,
+    })
+}
+
 fn build_arg_definitions(args: &[Arg]) -> (Vec<TokenStream>, MethodType) {
     let mut method_type = MethodType::Static;
 
@@ -349,11 +400,14 @@ fn build_arg_definitions(args: &[Arg]) -> (Vec<TokenStream>, MethodType) {
                 }
                 Arg::Typed(arg) => {
                     let ident = arg.get_name_ident();
-                    let definition = arg.get_arg_definition();
+                    let definition = arg.get_parser_arg_definition();
                     Some(quote! {
                         let mut #ident = #definition;
                     })
                 }
+                Arg::CalledScope => Some(quote! {
+                    let called_scope = ex.called_scope();
+                }),
             })
             .collect(),
         method_type,
@@ -377,11 +431,65 @@ fn build_arg_parser<'a>(
     )
 }
 
+/// Builds a parser which reads the arguments in place from the call frame,
+/// returning the parser along with the accessors of the arguments.
+fn build_fast_arg_parser(
+    args: &[Arg],
+    optional: &Option<String>,
+    ret: &TokenStream,
+    ty: ParserType,
+) -> Result<(TokenStream, Vec<TokenStream>)> {
+    let typed_args = args
+        .iter()
+        .filter_map(|arg| match arg {
+            Arg::Typed(arg) => Some(arg.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let (parser, typed_accessors) =
+        function::build_fast_arg_parser(&typed_args, optional, ret, ty)?;
+    // The called scope is retrieved before the parser borrows the execution data.
+    let called_scope = args
+        .iter()
+        .any(|arg| matches!(arg, Arg::CalledScope))
+        .then(|| quote! { let called_scope = ex.called_scope(); });
+
+    let mut typed_accessors = typed_accessors.into_iter();
+    let accessors = args
+        .iter()
+        .filter_map(|arg| match arg {
+            Arg::Typed(_) => typed_accessors.next(),
+            Arg::Receiver(MethodType::ReceiverClassObject) => Some(quote! { this }),
+            Arg::CalledScope => Some(quote! {
+                match called_scope {
+                    Some(ce) => ce,
+                    None => { #ret }
+                }
+            }),
+            _ => None,
+        })
+        .collect();
+
+    Ok((
+        quote! {
+            #called_scope
+            #parser
+        },
+        accessors,
+    ))
+}
+
 fn build_arg_accessors(args: &[Arg], ret: &TokenStream) -> Vec<TokenStream> {
     args.iter()
         .filter_map(|arg| match arg {
             Arg::Typed(arg) => Some(arg.get_accessor(ret)),
             Arg::Receiver(MethodType::ReceiverClassObject) => Some(quote! { this }),
+            Arg::CalledScope => Some(quote! {
+                match called_scope {
+                    Some(ce) => ce,
+                    None => { #ret }
+                }
+            }),
             _ => None,
         })
         .collect()
@@ -431,6 +539,27 @@ impl Method {
         }
     }
 
+    /// Returns a builder for the method without a handler, used for the
+    /// methods of interfaces.
+    pub fn get_abstract_builder(&self) -> TokenStream {
+        let name = &self.name;
+        let args = self.get_arg_definitions();
+        let output = self.output.as_ref().map(|(ty, nullable)| {
+            let ty: Type = syn::parse_str(ty).unwrap();
+
+            quote! {
+                .returns(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE, false, #nullable)
+            }
+        });
+
+        quote! {
+            ::ext_php_rs::builders::FunctionBuilder::new_abstract(#name)
+                #(#args)*
+                #output
+                .build()
+        }
+    }
+
     pub fn get_flags(&self) -> TokenStream {
         let mut flags = vec![match self.visibility {
             Visibility::Public => quote! { Public },
@@ -6,8 +6,10 @@ use quote::quote;
 use syn::{ItemFn, Signature, Type};
 
 use crate::{
-    class::{Class, Property},
+    class::{Class, Property, PropertyType},
+    enum_::{CaseValue, Enum},
     function::{Arg, Function},
+    interface::Interface,
     startup_function, State, STATE,
 };
 
@@ -25,9 +27,10 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
     state.built_module = true;
 
     // Generate startup function if one hasn't already been tagged with the macro.
-    let startup_fn = if (!state.classes.is_empty() || !state.constants.is_empty())
-        && state.startup_function.is_none()
-    {
+    // It is generated even when the module has no exports, as the classes
+    // defined by ext-php-rs (`RustClosure`, `RustIterator`) may be returned
+    // from functions and must be registered.
+    let startup_fn = if state.startup_function.is_none() {
         drop(state);
 
         let parsed = syn::parse2(quote! {
@@ -53,6 +56,27 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
             .startup_function(#ident)
         }
     });
+    // Default expressions are evaluated once per request, and released when the
+    // request ends.
+    let has_default_exprs = {
+        let method_args = state
+            .classes
+            .values()
+            .flat_map(|class| class.methods.iter().chain(&class.constructor))
+            .chain(state.enums.values().flat_map(|enum_| &enum_.methods))
+            .flat_map(|method| &method.args)
+            .filter_map(|arg| match arg {
+                crate::method::Arg::Typed(arg) => Some(arg),
+                _ => None,
+            });
+        state
+            .functions
+            .iter()
+            .flat_map(|func| &func.args)
+            .chain(method_args)
+            .any(Arg::has_default_expr)
+    };
+    let cache_defaults = has_default_exprs.then(|| quote! { .cache_default_values() });
     let registered_classes_impls = state
         .classes
         .values()
@@ -89,6 +113,7 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
             )
             #startup
             .shutdown_function(shutdown_function)
+            #cache_defaults
             #(.function(#functions.unwrap()))*
             ;
 
@@ -115,6 +140,40 @@ pub fn generate_registered_class_impl(class: &Class) -> Result<TokenStream> {
         .properties
         .iter()
         .map(|(name, prop)| prop.as_prop_tuple(name));
+    let handlers = class
+        .handlers
+        .iter()
+        .filter_map(|name| crate::class::handler_impl(name));
+    for name in &class.builtin_interfaces {
+        let overridden: &[&str] = match name.as_str() {
+            "countable" => &["count_elements"],
+            "array_access" => &[
+                "read_dimension",
+                "write_dimension",
+                "has_dimension",
+                "unset_dimension",
+            ],
+            _ => &[],
+        };
+        if let Some(handler) = class
+            .handlers
+            .iter()
+            .find(|handler| overridden.contains(&handler.as_str()))
+        {
+            bail!(
+                "The `{}` handler of `{}` cannot be overridden as the class is marked with `#[{}]`.",
+                handler,
+                class.class_name,
+                name
+            );
+        }
+    }
+    let cloneable = class.handlers.iter().any(|name| name == "clone_obj");
+    let has_gc = class.handlers.iter().any(|name| name == "get_gc");
+    let builtin_handlers = class
+        .builtin_interfaces
+        .iter()
+        .map(|name| crate::class::builtin_interface_handlers(name));
     let constructor = if let Some(constructor) = &class.constructor {
         let func = Ident::new(&constructor.ident, Span::call_site());
         let args = constructor.get_arg_definitions();
@@ -143,6 +202,8 @@ pub fn generate_registered_class_impl(class: &Class) -> Result<TokenStream> {
             const CONSTRUCTOR: ::std::option::Option<
                 ::ext_php_rs::class::ConstructorMeta<Self>
             > = #constructor;
+            const CLONEABLE: bool = #cloneable;
+            const HAS_GC: bool = #has_gc;
 
             fn get_metadata() -> &'static ::ext_php_rs::class::ClassMetadata<Self> {
                 &#meta
@@ -155,6 +216,9 @@ pub fn generate_registered_class_impl(class: &Class) -> Result<TokenStream> {
                     #(#prop_tuples)*
                 ])
             }
+
+            #(#handlers)*
+            #(#builtin_handlers)*
         }
     })
 }
@@ -245,7 +309,16 @@ impl Describe for Class {
         } else {
             quote! { None }
         };
-        let interfaces = self.interfaces.iter().map(|iface| quote! { #iface.into() });
+        let interfaces = self
+            .interfaces
+            .iter()
+            .map(String::as_str)
+            .chain(
+                self.builtin_interfaces
+                    .iter()
+                    .map(|name| crate::class::builtin_interface_name(name)),
+            )
+            .map(|iface| quote! { #iface.into() });
         let properties = self.properties.iter().map(|d| d.describe());
         let mut methods: Vec<_> = self.methods.iter().map(Describe::describe).collect();
         let docs = self.docs.iter().map(|c| {
@@ -273,6 +346,91 @@ impl Describe for Class {
     }
 }
 
+impl Describe for Interface {
+    fn describe(&self) -> TokenStream {
+        let name = &self.interface_name;
+        let extends = self
+            .parent_names
+            .iter()
+            .map(|parent| quote! { #parent.into() });
+        let methods = self.methods.iter().map(Describe::describe);
+        let constants = self.constants.iter().map(Describe::describe);
+        let docs = self.docs.iter().map(|c| {
+            quote! {
+                #c.into()
+            }
+        });
+
+        quote! {
+            Interface {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                extends: vec![#(#extends,)*].into(),
+                methods: vec![#(#methods,)*].into(),
+                constants: vec![#(#constants,)*].into(),
+            }
+        }
+    }
+}
+
+impl Describe for Enum {
+    fn describe(&self) -> TokenStream {
+        let name = &self.enum_name;
+        let backing = match self.cases.first().and_then(|case| case.value.as_ref()) {
+            Some(CaseValue::Long(_)) => quote! { Some(::ext_php_rs::flags::DataType::Long) },
+            Some(CaseValue::Str(_)) => quote! { Some(::ext_php_rs::flags::DataType::String) },
+            None => quote! { None },
+        };
+        let interfaces = self.interfaces.iter().map(|iface| quote! { #iface.into() });
+        let cases = self.cases.iter().map(|case| {
+            let name = &case.name;
+            let docs = case.docs.iter().map(|doc| {
+                quote! {
+                    #doc.into()
+                }
+            });
+            let value = match &case.value {
+                Some(CaseValue::Long(val)) => {
+                    let val = val.to_string();
+                    quote! { Some(#val.into()) }
+                }
+                Some(CaseValue::Str(val)) => {
+                    let val = format!("{val:?}");
+                    quote! { Some(#val.into()) }
+                }
+                None => quote! { None },
+            };
+
+            quote! {
+                EnumCase {
+                    name: #name.into(),
+                    docs: DocBlock(vec![#(#docs,)*].into()),
+                    value: abi::Option::#value,
+                }
+            }
+        });
+        let methods = self.methods.iter().map(Describe::describe);
+        let constants = self.constants.iter().map(Describe::describe);
+        let docs = self.docs.iter().map(|c| {
+            quote! {
+                #c.into()
+            }
+        });
+
+        quote! {
+            Enum {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                backing: abi::Option::#backing,
+                implements: vec![#(#interfaces,)*].into(),
+                cases: vec![#(#cases,)*].into(),
+                methods: vec![#(#methods,)*].into(),
+                constants: vec![#(#constants,)*].into(),
+            }
+        }
+    }
+}
+
 impl Describe for (&String, &Property) {
     fn describe(&self) -> TokenStream {
         let name = self.0;
@@ -282,16 +440,35 @@ impl Describe for (&String, &Property) {
             }
         });
 
-        // TODO(david): store metadata for ty, vis, static, null, default
+        let ty = match &self.1.rust_ty {
+            Some((ty, _)) => {
+                let ty: Type = syn::parse_str(ty).expect("failed to parse previously parsed type");
+                quote! { abi::Option::Some(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE) }
+            }
+            None => quote! { abi::Option::None },
+        };
+        let nullable = self
+            .1
+            .rust_ty
+            .as_ref()
+            .is_some_and(|(_, nullable)| *nullable);
+        let vis = self.1.visibility().describe();
+        let readonly = self.1.readonly;
+        let (static_, default) = match &self.1.ty {
+            PropertyType::Static { value } => (true, quote! { abi::Option::Some(#value.into()) }),
+            _ => (false, quote! { abi::Option::None }),
+        };
+
         quote! {
             Property {
                 name: #name.into(),
                 docs: DocBlock(vec![#(#docs,)*].into()),
-                ty: abi::Option::None,
-                vis: Visibility::Public,
-                static_: false,
-                nullable: false,
-                default: abi::Option::None,
+                ty: #ty,
+                vis: #vis,
+                static_: #static_,
+                nullable: #nullable,
+                readonly: #readonly,
+                default: #default,
             }
         }
     }
@@ -380,6 +557,8 @@ impl Describe for State {
         let functs = self.functions.iter().map(Describe::describe);
         let classes = self.classes.values().map(|class| class.describe());
         let constants = self.constants.iter().map(Describe::describe);
+        let enums = self.enums.values().map(Describe::describe);
+        let interfaces = self.interfaces.iter().map(Describe::describe);
 
         quote! {
             Module {
@@ -387,6 +566,8 @@ impl Describe for State {
                 functions: vec![#(#functs,)*].into(),
                 classes: vec![#(#classes,)*].into(),
                 constants: vec![#(#constants,)*].into(),
+                enums: vec![#(#enums,)*].into(),
+                interfaces: vec![#(#interfaces,)*].into(),
             }
         }
     }
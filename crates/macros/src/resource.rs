@@ -0,0 +1,69 @@
+use anyhow::{anyhow, bail, Result};
+use darling::FromMeta;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{AttributeArgs, ItemStruct};
+
+use crate::STATE;
+
+#[derive(Debug, Default)]
+pub struct Resource {
+    pub resource_name: String,
+}
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    name: Option<String>,
+}
+
+pub fn parser(args: AttributeArgs, input: ItemStruct) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
+
+    let ItemStruct {
+        ident, generics, ..
+    } = &input;
+    if !generics.params.is_empty() {
+        bail!("Resource `{}` must not have any generic parameters.", ident);
+    }
+
+    let resource_name = args.name.unwrap_or_else(|| ident.to_string());
+    let meta = Ident::new(&format!("_{ident}_META"), Span::call_site());
+
+    let mut state = STATE.lock();
+
+    if state.built_module {
+        bail!("The `#[php_module]` macro must be called last to ensure functions and classes are registered.");
+    }
+
+    if state.startup_function.is_some() {
+        bail!(
+            "The `#[php_startup]` macro must be called after all the resources have been defined."
+        );
+    }
+
+    state.resources.insert(
+        ident.to_string(),
+        Resource {
+            resource_name: resource_name.clone(),
+        },
+    );
+
+    Ok(quote! {
+        #input
+
+        static #meta: ::ext_php_rs::resource::ResourceMetadata = ::ext_php_rs::resource::ResourceMetadata::new();
+
+        impl ::ext_php_rs::resource::RegisteredResource for #ident {
+            const RESOURCE_NAME: &'static str = #resource_name;
+
+            #[inline]
+            fn get_metadata() -> &'static ::ext_php_rs::resource::ResourceMetadata {
+                &#meta
+            }
+        }
+
+        ::ext_php_rs::resource_derives!(#ident);
+    })
+}
@@ -1,12 +1,19 @@
 use std::collections::HashMap;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use darling::FromMeta;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{AttributeArgs, Expr, ItemFn, Signature};
+use syn::{AttributeArgs, Expr, ItemFn, Signature, Type};
 
-use crate::{class::Class, constant::Constant, STATE};
+use crate::{
+    class::{Class, Property, PropertyType},
+    constant::Constant,
+    enum_::Enum,
+    interface::Interface,
+    resource::Resource,
+    STATE,
+};
 use crate::function_hooks::generate_function_hooks;
 
 #[derive(Default, Debug, FromMeta)]
@@ -30,7 +37,10 @@ pub fn parser(args: Option<AttributeArgs>, input: ItemFn) -> Result<TokenStream>
     let mut state = STATE.lock();
     state.startup_function = Some(ident.to_string());
 
-    let classes = build_classes(&state.classes)?;
+    let interfaces = build_interfaces(&state.interfaces);
+    let classes = build_classes(&state.classes, &state.interfaces)?;
+    let enums = build_enums(&state.enums, &state.interfaces)?;
+    let resources = build_resources(&state.resources);
     let constants = build_constants(&state.constants);
     let (before, after) = if args.before {
         (Some(quote! { internal(ty, module_number); }), None)
@@ -53,7 +63,10 @@ pub fn parser(args: Option<AttributeArgs>, input: ItemFn) -> Result<TokenStream>
             ::ext_php_rs::internal::ext_php_rs_startup();
 
             #before
+            #(#interfaces)*
             #(#classes)*
+            #(#enums)*
+            #(#resources)*
             #(#constants)*
             #after
             #function_hooks
@@ -66,8 +79,88 @@ pub fn parser(args: Option<AttributeArgs>, input: ItemFn) -> Result<TokenStream>
     Ok(func)
 }
 
+/// Returns a vector of `ClassBuilder`s for each interface, in the order they
+/// were declared so that parent interfaces are registered first.
+fn build_interfaces(interfaces: &[Interface]) -> Vec<TokenStream> {
+    interfaces
+        .iter()
+        .map(|interface| {
+            let Interface { interface_name, .. } = &interface;
+            let meta = Ident::new(&format!("_{}_META", interface.ident), Span::call_site());
+            let methods = interface.methods.iter().map(|method| {
+                let builder = method.get_abstract_builder();
+                let flags = method.get_flags();
+                quote! { .method(#builder.unwrap(), #flags) }
+            });
+            let constants = interface.constants.iter().map(|constant| {
+                let name = &constant.name;
+                let val = constant.val_tokens();
+                quote! { .constant(#name, #val).unwrap() }
+            });
+            let extends = interface.extends.iter().map(|parent| {
+                let meta = Ident::new(&format!("_{parent}_META"), Span::call_site());
+                quote! { .implements(#meta.ce()) }
+            });
+
+            quote! {{
+                let class = ::ext_php_rs::builders::ClassBuilder::new(#interface_name)
+                    .flags(::ext_php_rs::flags::ClassFlags::Interface)
+                    #(#methods)*
+                    #(#constants)*
+                    #(#extends)*
+                    .build()
+                    .expect(concat!("Unable to build interface `", #interface_name, "`"));
+
+                #meta.set_ce(class);
+            }}
+        })
+        .collect()
+}
+
+/// Checks that a class implements all of the methods required by an exported
+/// interface.
+fn check_interface_methods(
+    class: &Class,
+    interface: &Interface,
+    interfaces: &[Interface],
+) -> Result<()> {
+    // Methods may be inherited from a parent class which we know nothing about.
+    if class.parent.is_some() {
+        return Ok(());
+    }
+
+    for required in interface.required_methods(interfaces) {
+        let found = class
+            .methods
+            .iter()
+            .chain(class.constructor.iter())
+            .find(|method| method.name.eq_ignore_ascii_case(&required.name));
+        match found {
+            Some(method) if method._static != required._static => bail!(
+                "Method `{}::{}` must {}be static to implement interface `{}`.",
+                class.class_name,
+                method.name,
+                if required._static { "" } else { "not " },
+                interface.interface_name
+            ),
+            Some(_) => {}
+            None => bail!(
+                "Class `{}` must implement method `{}` required by interface `{}`.",
+                class.class_name,
+                required.name,
+                interface.interface_name
+            ),
+        }
+    }
+
+    Ok(())
+}
+
 /// Returns a vector of `ClassBuilder`s for each class.
-fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
+fn build_classes(
+    classes: &HashMap<String, Class>,
+    interfaces: &[Interface],
+) -> Result<Vec<TokenStream>> {
     classes
         .iter()
         .map(|(name, class)| {
@@ -98,6 +191,12 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
                 .interfaces
                 .iter()
                 .map(|interface| {
+                    if let Some(exported) = interfaces.iter().find(|iface| &iface.ident == interface) {
+                        check_interface_methods(class, exported, interfaces)?;
+                        let meta = Ident::new(&format!("_{interface}_META"), Span::call_site());
+                        return Ok(quote! { .implements(#meta.ce()) });
+                    }
+
                     let expr: Expr = syn::parse_str(interface).map_err(|_| {
                         anyhow!(
                             "Invalid expression given for `{}` interface: `{}`",
@@ -108,35 +207,15 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
                     Ok(quote! { .implements(#expr) })
                 })
                 .collect::<Result<Vec<_>>>()?;
-            // TODO(david): register properties for reflection (somehow)
-            // let properties = class
-            //     .properties
-            //     .iter()
-            //     .map(|(name, (default, flags))| {
-            //         let default_expr: Expr = syn::parse_str(default).map_err(|_| {
-            //             anyhow!(
-            //                 "Invalid default value given for property `{}` type: `{}`",
-            //                 name,
-            //                 default
-            //             )
-            //         })?;
-            //         let flags_expr: Expr = syn::parse_str(
-            //             flags
-            //                 .as_ref()
-            //                 .map(|flags| flags.as_str())
-            //                 .unwrap_or("PropertyFlags::Public"),
-            //         )
-            //         .map_err(|_| {
-            //             anyhow!(
-            //                 "Invalid default value given for property `{}` type: `{}`",
-            //                 name,
-            //                 default
-            //             )
-            //         })?;
-
-            //         Ok(quote! { .property(#name, #default_expr, #flags_expr) })
-            //     })
-            //     .collect::<Result<Vec<_>>>()?;
+            let builtin_interfaces = class.builtin_interfaces.iter().map(|name| {
+                let method = Ident::new(name, Span::call_site());
+                quote! { .#method::<#ident>() }
+            });
+            let properties = class
+                .properties
+                .iter()
+                .map(|(name, prop)| build_property(name, prop))
+                .collect::<Result<Vec<_>>>()?;
             let class_modifier = class.modifier.as_ref().map(|modifier| {
                 let modifier = Ident::new(modifier, Span::call_site());
                 quote! {
@@ -174,7 +253,8 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
                     #(#methods)*
                     #(#constants)*
                     #(#interfaces)*
-                    // #(#properties)*
+                    #(#builtin_interfaces)*
+                    #(#properties)*
                     #parent
                     #flags
                     #object_override
@@ -189,6 +269,124 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
         .collect::<Result<Vec<_>>>()
 }
 
+/// Returns the `ClassBuilder` call declaring a property on the class.
+fn build_property(name: &str, prop: &Property) -> Result<TokenStream> {
+    let flags: Expr = syn::parse_str(prop.flags.as_deref().unwrap_or("PropertyFlags::Public"))
+        .map_err(|_| anyhow!("Invalid flags given for property `{}`", name))?;
+    let mut flags = quote! { #flags };
+    if prop.readonly {
+        flags = quote! { #flags | PropertyFlags::Readonly };
+    }
+
+    let default = match &prop.ty {
+        PropertyType::Static { value } => {
+            flags = quote! { #flags | PropertyFlags::Static };
+            let value: Expr = syn::parse_str(value)
+                .map_err(|_| anyhow!("Invalid value given for static property `{}`", name))?;
+            quote! {
+                Some(
+                    ::ext_php_rs::convert::IntoZval::into_zval(#value, true)
+                        .expect(concat!("Invalid value given for static property `", #name, "`"))
+                )
+            }
+        }
+        _ => quote! { None },
+    };
+
+    let (ty, nullable) = match &prop.rust_ty {
+        Some((ty, nullable)) => {
+            let ty: Type = syn::parse_str(ty)
+                .map_err(|_| anyhow!("Invalid type given for property `{}`", name))?;
+            (
+                quote! { <#ty as ::ext_php_rs::convert::IntoZval>::TYPE },
+                *nullable,
+            )
+        }
+        None => (quote! { ::ext_php_rs::flags::DataType::Mixed }, true),
+    };
+
+    Ok(quote! { .typed_property(#name, #ty, #nullable, #default, #flags) })
+}
+
+/// Returns a vector of `EnumBuilder`s for each enum.
+fn build_enums(
+    enums: &HashMap<String, Enum>,
+    interfaces: &[Interface],
+) -> Result<Vec<TokenStream>> {
+    enums
+        .iter()
+        .map(|(name, enum_)| {
+            let Enum { enum_name, .. } = &enum_;
+            let ident = Ident::new(name, Span::call_site());
+            let meta = Ident::new(&format!("_{name}_META"), Span::call_site());
+            let methods = enum_.methods.iter().map(|method| {
+                let builder = method.get_builder(&ident);
+                let flags = method.get_flags();
+                quote! { .method(#builder.unwrap(), #flags) }
+            });
+            let constants = enum_.constants.iter().map(|constant| {
+                let name = &constant.name;
+                let val = constant.val_tokens();
+                quote! { .constant(#name, #val).unwrap() }
+            });
+            let interfaces = enum_
+                .interfaces
+                .iter()
+                .map(|interface| {
+                    if interfaces.iter().any(|iface| &iface.ident == interface) {
+                        let meta = Ident::new(&format!("_{interface}_META"), Span::call_site());
+                        return Ok(quote! { .implements(#meta.ce()) });
+                    }
+
+                    let expr: Expr = syn::parse_str(interface).map_err(|_| {
+                        anyhow!(
+                            "Invalid expression given for `{}` interface: `{}`",
+                            enum_name,
+                            interface
+                        )
+                    })?;
+                    Ok(quote! { .implements(#expr) })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(quote! {{
+                use ::ext_php_rs::enum_::RegisteredEnum;
+
+                let mut builder = ::ext_php_rs::builders::EnumBuilder::new(#enum_name)
+                    #(#methods)*
+                    #(#constants)*
+                    #(#interfaces)*
+                    ;
+                if let Some(ty) = <#ident as RegisteredEnum>::BACKING_TYPE {
+                    builder = builder.backed(ty);
+                }
+                for case in <#ident as RegisteredEnum>::CASES {
+                    builder = builder.case(case.name, case.value);
+                }
+                let class = builder.build()
+                    .expect(concat!("Unable to build enum `", #enum_name, "`"));
+
+                #meta.set_ce(class);
+            }})
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+/// Returns a vector of registration calls for each resource type.
+fn build_resources(resources: &HashMap<String, Resource>) -> Vec<TokenStream> {
+    resources
+        .iter()
+        .map(|(name, resource)| {
+            let ident = Ident::new(name, Span::call_site());
+            let resource_name = &resource.resource_name;
+            quote! {
+                ::ext_php_rs::resource::register::<#ident>(module_number)
+                    .expect(concat!("Unable to register resource `", #resource_name, "`"));
+            }
+        })
+        .collect()
+}
+
 fn build_constants(constants: &[Constant]) -> Vec<TokenStream> {
     constants
         .iter()
@@ -103,6 +103,8 @@ pub const IS_MIXED: u32 = 16;
 pub const IS_INDIRECT: u32 = 12;
 pub const IS_PTR: u32 = 13;
 pub const _IS_BOOL: u32 = 18;
+pub const GC_IMMUTABLE: u32 = 64;
+pub const GC_PERSISTENT: u32 = 128;
 pub const Z_TYPE_FLAGS_SHIFT: u32 = 8;
 pub const IS_TYPE_REFCOUNTED: u32 = 1;
 pub const IS_TYPE_COLLECTABLE: u32 = 2;
@@ -156,6 +158,21 @@ pub const ZEND_ACC_RESOLVED_INTERFACES: u32 = 262144;
 pub const ZEND_ACC_UNRESOLVED_VARIANCE: u32 = 524288;
 pub const ZEND_ACC_NEARLY_LINKED: u32 = 1048576;
 pub const ZEND_ACC_NOT_SERIALIZABLE: u32 = 536870912;
+pub const ZEND_ACC_ENUM: u32 = 268435456;
+pub const ZEND_ACC_READONLY: u32 = 128;
+pub const ZEND_ADD: u32 = 1;
+pub const ZEND_SUB: u32 = 2;
+pub const ZEND_MUL: u32 = 3;
+pub const ZEND_DIV: u32 = 4;
+pub const ZEND_MOD: u32 = 5;
+pub const ZEND_SL: u32 = 6;
+pub const ZEND_SR: u32 = 7;
+pub const ZEND_CONCAT: u32 = 8;
+pub const ZEND_BW_OR: u32 = 9;
+pub const ZEND_BW_AND: u32 = 10;
+pub const ZEND_BW_XOR: u32 = 11;
+pub const ZEND_POW: u32 = 12;
+pub const ZEND_BW_NOT: u32 = 13;
 pub const ZEND_ACC_DEPRECATED: u32 = 2048;
 pub const ZEND_ACC_RETURN_REFERENCE: u32 = 4096;
 pub const ZEND_ACC_HAS_RETURN_TYPE: u32 = 8192;
@@ -489,6 +506,16 @@ extern "C" {
         __zend_orig_lineno: u32,
     );
 }
+extern "C" {
+    pub fn _erealloc(
+        ptr: *mut ::std::os::raw::c_void,
+        size: usize,
+        __zend_filename: *const ::std::os::raw::c_char,
+        __zend_lineno: u32,
+        __zend_orig_filename: *const ::std::os::raw::c_char,
+        __zend_orig_lineno: u32,
+    ) -> *mut ::std::os::raw::c_void;
+}
 extern "C" {
     pub fn __zend_malloc(len: usize) -> *mut ::std::os::raw::c_void;
 }
@@ -565,6 +592,9 @@ extern "C" {
 extern "C" {
     pub fn zend_hash_index_del(ht: *mut HashTable, h: zend_ulong) -> zend_result;
 }
+extern "C" {
+    pub fn zend_try_assign_typed_ref(ref_: *mut zend_reference, zv: *mut zval) -> zend_result;
+}
 extern "C" {
     pub fn zend_hash_str_find(
         ht: *const HashTable,
@@ -581,6 +611,9 @@ extern "C" {
 extern "C" {
     pub fn zend_hash_move_backwards_ex(ht: *mut HashTable, pos: *mut HashPosition) -> zend_result;
 }
+extern "C" {
+    pub fn zend_hash_internal_pointer_end_ex(ht: *mut HashTable, pos: *mut HashPosition);
+}
 extern "C" {
     pub fn zend_hash_get_current_key_zval_ex(
         ht: *const HashTable,
@@ -1226,6 +1259,7 @@ pub struct _zend_property_info {
     pub ce: *mut zend_class_entry,
     pub type_: zend_type,
 }
+pub type zend_property_info = _zend_property_info;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _zend_internal_arg_info {
@@ -1379,6 +1413,12 @@ extern "C" {
 extern "C" {
     pub fn zend_objects_clone_members(new_object: *mut zend_object, old_object: *mut zend_object);
 }
+extern "C" {
+    pub fn zend_iterator_init(iter: *mut zend_object_iterator);
+}
+extern "C" {
+    pub fn zend_create_internal_iterator_zval(return_value: *mut zval, obj: *mut zval) -> zend_result;
+}
 extern "C" {
     pub fn zend_object_std_dtor(object: *mut zend_object);
 }
@@ -2381,6 +2421,9 @@ extern "C" {
 extern "C" {
     pub static mut zend_ce_error_exception: *mut zend_class_entry;
 }
+extern "C" {
+    pub static mut zend_ce_error: *mut zend_class_entry;
+}
 extern "C" {
     pub static mut zend_ce_compile_error: *mut zend_class_entry;
 }
@@ -2634,3 +2677,191 @@ pub struct _sapi_post_entry {
         ),
     >,
 }
+extern "C" {
+    pub fn zend_register_internal_enum(
+        name: *const ::std::os::raw::c_char,
+        type_: ::std::os::raw::c_uchar,
+        functions: *const zend_function_entry,
+    ) -> *mut zend_class_entry;
+}
+extern "C" {
+    pub fn zend_enum_add_case_cstr(
+        ce: *mut zend_class_entry,
+        name: *const ::std::os::raw::c_char,
+        value: *mut zval,
+    );
+}
+extern "C" {
+    pub fn zend_enum_get_case_cstr(
+        ce: *mut zend_class_entry,
+        name: *const ::std::os::raw::c_char,
+    ) -> *mut zend_object;
+}
+extern "C" {
+    pub fn zend_declare_typed_property(
+        ce: *mut zend_class_entry,
+        name: *mut zend_string,
+        property: *mut zval,
+        access_type: ::std::os::raw::c_int,
+        doc_comment: *mut zend_string,
+        type_: zend_type,
+    ) -> *mut zend_property_info;
+}
+extern "C" {
+    pub fn zend_get_executed_scope() -> *mut zend_class_entry;
+}
+extern "C" {
+    pub fn zend_read_static_property_ex(
+        scope: *mut zend_class_entry,
+        name: *mut zend_string,
+        silent: bool,
+    ) -> *mut zval;
+}
+extern "C" {
+    pub fn zend_update_static_property_ex(
+        scope: *mut zend_class_entry,
+        name: *mut zend_string,
+        value: *mut zval,
+    ) -> zend_result;
+}
+pub type rsrc_dtor_func_t = ::std::option::Option<unsafe extern "C" fn(res: *mut zend_resource)>;
+extern "C" {
+    pub fn zend_list_delete(res: *mut zend_resource);
+}
+extern "C" {
+    pub fn zend_list_close(res: *mut zend_resource);
+}
+extern "C" {
+    pub fn zend_register_resource(
+        rsrc_pointer: *mut ::std::os::raw::c_void,
+        rsrc_type: ::std::os::raw::c_int,
+    ) -> *mut zend_resource;
+}
+extern "C" {
+    pub fn zend_register_list_destructors_ex(
+        ld: rsrc_dtor_func_t,
+        pld: rsrc_dtor_func_t,
+        type_name: *const ::std::os::raw::c_char,
+        module_number: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zend_register_persistent_resource(
+        key: *const ::std::os::raw::c_char,
+        key_len: usize,
+        rsrc_pointer: *mut ::std::os::raw::c_void,
+        rsrc_type: ::std::os::raw::c_int,
+    ) -> *mut zend_resource;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct smart_str {
+    pub s: *mut zend_string,
+    pub a: usize,
+}
+pub type zend_new_interned_string_func_t =
+    ::std::option::Option<unsafe extern "C" fn(str_: *mut zend_string) -> *mut zend_string>;
+extern "C" {
+    pub static mut zend_new_interned_string: zend_new_interned_string_func_t;
+}
+extern "C" {
+    pub fn zend_get_gc_buffer_create() -> *mut zend_get_gc_buffer;
+}
+extern "C" {
+    pub static mut zend_ce_weakref: *mut zend_class_entry;
+}
+extern "C" {
+    pub static mut zend_ce_fiber: *mut zend_class_entry;
+}
+extern "C" {
+    pub fn zend_is_unwind_exit(ex: *const zend_object) -> bool;
+}
+extern "C" {
+    pub fn zend_is_graceful_exit(ex: *const zend_object) -> bool;
+}
+extern "C" {
+    pub static mut zend_ce_closure: *mut zend_class_entry;
+}
+extern "C" {
+    pub fn zend_create_fake_closure(
+        res: *mut zval,
+        func: *mut zend_function,
+        scope: *mut zend_class_entry,
+        called_scope: *mut zend_class_entry,
+        this_ptr: *mut zval,
+    );
+}
+extern "C" {
+    pub fn zend_is_callable_ex(
+        callable: *mut zval,
+        object: *mut zend_object,
+        check_flags: u32,
+        callable_name: *mut *mut zend_string,
+        fcc: *mut zend_fcall_info_cache,
+        error: *mut *mut ::std::os::raw::c_char,
+    ) -> bool;
+}
+extern "C" {
+    pub fn zend_argument_error(
+        error_ce: *mut zend_class_entry,
+        arg_num: u32,
+        format: *const ::std::os::raw::c_char,
+        ...
+    );
+}
+pub const ZEND_CALL_HAS_EXTRA_NAMED_PARAMS: u32 = 134217728;
+extern "C" {
+    pub fn zend_argument_type_error(arg_num: u32, format: *const ::std::os::raw::c_char, ...);
+}
+extern "C" {
+    pub fn zend_zval_type_name(arg: *const zval) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn zend_argument_value_error(arg_num: u32, format: *const ::std::os::raw::c_char, ...);
+}
+extern "C" {
+    pub fn zend_get_default_from_internal_arg_info(
+        default_value_zval: *mut zval,
+        arg_info: *mut zend_internal_arg_info,
+    ) -> zend_result;
+}
+extern "C" {
+    pub fn zval_update_constant_ex(pp: *mut zval, scope: *mut zend_class_entry) -> zend_result;
+}
+pub type compare_func_t = ::std::option::Option<
+    unsafe extern "C" fn(
+        arg1: *const ::std::os::raw::c_void,
+        arg2: *const ::std::os::raw::c_void,
+    ) -> ::std::os::raw::c_int,
+>;
+pub type swap_func_t = ::std::option::Option<
+    unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void, arg2: *mut ::std::os::raw::c_void),
+>;
+pub type sort_func_t = ::std::option::Option<
+    unsafe extern "C" fn(
+        arg1: *mut ::std::os::raw::c_void,
+        arg2: usize,
+        arg3: usize,
+        arg4: compare_func_t,
+        arg5: swap_func_t,
+    ),
+>;
+pub type bucket_compare_func_t =
+    ::std::option::Option<unsafe extern "C" fn(a: *mut Bucket, b: *mut Bucket) -> ::std::os::raw::c_int>;
+extern "C" {
+    pub fn zend_hash_sort_ex(
+        ht: *mut HashTable,
+        sort_func: sort_func_t,
+        compare_func: bucket_compare_func_t,
+        renumber: bool,
+    );
+}
+extern "C" {
+    pub fn zend_sort(
+        base: *mut ::std::os::raw::c_void,
+        nmemb: usize,
+        siz: usize,
+        cmp: compare_func_t,
+        swp: swap_func_t,
+    );
+}
@@ -1,8 +1,11 @@
 //! Functions relating to the Zend Memory Manager, used to allocate
-//! request-bound memory.
+//! request-bound and persistent memory.
 
-use crate::ffi::{_efree, _emalloc};
-use std::{alloc::Layout, ffi::c_void};
+use crate::ffi::{
+    _efree, _emalloc, _erealloc, ext_php_rs_pefree, ext_php_rs_pemalloc, ext_php_rs_zend_mm_heap,
+    ZEND_MM_ALIGNMENT,
+};
+use std::{alloc::Layout, ffi::c_void, marker::PhantomData, mem, ptr};
 
 /// Uses the PHP memory allocator to allocate request-bound memory.
 ///
@@ -12,11 +15,19 @@ use std::{alloc::Layout, ffi::c_void};
 ///
 /// # Returns
 ///
-/// A pointer to the memory allocated.
+/// A pointer to the memory allocated, or a null pointer if the alignment of
+/// the layout is greater than the alignment guaranteed by the Zend memory
+/// manager, [`ZEND_MM_ALIGNMENT`]. Use [`ZendAllocator`] to allocate memory
+/// with a greater alignment.
 pub fn emalloc(layout: Layout) -> *mut u8 {
-    // TODO account for alignment
-    let size = layout.size();
+    if layout.align() > ZEND_MM_ALIGNMENT as usize {
+        return ptr::null_mut();
+    }
+    emalloc_size(layout.size())
+}
 
+/// Allocates the given number of bytes with the PHP memory allocator.
+fn emalloc_size(size: usize) -> *mut u8 {
     (unsafe {
         #[cfg(php_debug)]
         {
@@ -56,3 +67,242 @@ pub unsafe fn efree(ptr: *mut u8) {
         _efree(ptr as *mut c_void)
     }
 }
+
+/// Allocates memory which is request-bound or persistent, like `pemalloc`.
+/// Persistent memory is allocated with the system allocator and lives until
+/// it is freed, surviving the end of the request.
+///
+/// # Parameters
+///
+/// * `layout` - The layout of the requested memory.
+/// * `persistent` - Whether the memory should persist through the request
+///   boundary.
+///
+/// # Returns
+///
+/// A pointer to the memory allocated, or a null pointer if the alignment of
+/// the layout is greater than [`ZEND_MM_ALIGNMENT`], which both allocators
+/// guarantee.
+pub fn pemalloc(layout: Layout, persistent: bool) -> *mut u8 {
+    if !persistent {
+        return emalloc(layout);
+    }
+    if layout.align() > ZEND_MM_ALIGNMENT as usize {
+        return ptr::null_mut();
+    }
+    unsafe { ext_php_rs_pemalloc(layout.size(), true) }.cast()
+}
+
+/// Frees a given memory pointer which was allocated through [`pemalloc`].
+///
+/// # Parameters
+///
+/// * `ptr` - The pointer to the memory to free.
+/// * `persistent` - Whether the memory was allocated as persistent.
+///
+/// # Safety
+///
+/// Caller must guarantee that the given pointer is valid (aligned and non-null)
+/// and was originally allocated through [`pemalloc`] with the same value of
+/// `persistent`.
+pub unsafe fn pefree(ptr: *mut u8, persistent: bool) {
+    ext_php_rs_pefree(ptr.cast(), persistent)
+}
+
+/// Reallocates memory which was allocated with the PHP memory allocator.
+fn erealloc_size(ptr: *mut u8, size: usize) -> *mut u8 {
+    (unsafe {
+        #[cfg(php_debug)]
+        {
+            _erealloc(
+                ptr as *mut c_void,
+                size as _,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null_mut(),
+                0,
+            )
+        }
+        #[cfg(not(php_debug))]
+        {
+            _erealloc(ptr as *mut c_void, size as _)
+        }
+    }) as *mut u8
+}
+
+/// An allocator backed by the Zend memory manager, which allocates memory
+/// bound to the current request.
+///
+/// Memory is allocated with `emalloc`, so it counts towards `memory_limit` and
+/// is reported by `memory_get_usage()`. Any alignment is supported. Exceeding
+/// `memory_limit` raises a fatal error, as with any other memory allocated by
+/// PHP.
+///
+/// PHP releases all the memory allocated during a request in bulk when the
+/// request ends, whether or not it has been freed. The allocator is therefore
+/// only handed out by [`ZendAllocator::scope`], and borrows the request for the
+/// duration of the call. Memory allocated with it, and collections using it,
+/// cannot be moved out of the call, and cannot be sent to other threads.
+///
+/// ```no_run
+/// use ext_php_rs::alloc::ZendAllocator;
+/// use std::alloc::Layout;
+///
+/// let sum = ZendAllocator::scope(|alloc| unsafe {
+///     let layout = Layout::array::<u64>(1024).unwrap();
+///     let ptr = alloc.alloc(layout).cast::<u64>();
+///     for i in 0..1024 {
+///         ptr.add(i).write(i as u64);
+///     }
+///     let sum = (0..1024).map(|i| *ptr.add(i)).sum::<u64>();
+///     alloc.dealloc(ptr.cast(), layout);
+///     sum
+/// });
+/// ```
+///
+/// With the `allocator_api` feature, which requires a nightly compiler, the
+/// allocator also implements [`Allocator`], so that individual collections
+/// such as `Vec<T, ZendAllocator<'_>>` can be allocated in the request.
+///
+/// [`Allocator`]: std::alloc::Allocator
+#[derive(Debug, Clone, Copy)]
+pub struct ZendAllocator<'r> {
+    /// Binds the allocator to the request, and to the thread it runs on.
+    request: PhantomData<&'r *mut ()>,
+}
+
+impl ZendAllocator<'_> {
+    /// Calls the given function with an allocator bound to the current
+    /// request.
+    ///
+    /// Returns [`None`] without calling the function if no request is active
+    /// on the current thread, such as during module startup or on threads
+    /// which were not started by PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `f` - The function to call with the allocator.
+    pub fn scope<R, F>(f: F) -> Option<R>
+    where
+        F: FnOnce(ZendAllocator<'_>) -> R,
+    {
+        if !Self::is_request_bound() {
+            return None;
+        }
+        Some(f(ZendAllocator {
+            request: PhantomData,
+        }))
+    }
+
+    /// Returns true if a request is active on the current thread, in which case
+    /// [`ZendAllocator::scope`] hands out an allocator.
+    pub fn is_request_bound() -> bool {
+        unsafe { !ext_php_rs_zend_mm_heap(true).is_null() }
+    }
+
+    /// Returns true if blocks of the given layout need to be realigned within
+    /// a larger block.
+    fn is_overaligned(layout: Layout) -> bool {
+        layout.align() > ZEND_MM_ALIGNMENT as usize
+    }
+
+    /// Allocates memory for the given layout.
+    ///
+    /// Returns a null pointer if the size of the block overflows.
+    ///
+    /// # Parameters
+    ///
+    /// * `layout` - The layout of the requested memory.
+    pub fn alloc(&self, layout: Layout) -> *mut u8 {
+        if !Self::is_overaligned(layout) {
+            return emalloc_size(layout.size());
+        }
+        // The block is allocated with room to align the pointer. As the block is
+        // aligned to `ZEND_MM_ALIGNMENT`, there is always room to store the
+        // pointer to the start of the block right before the aligned pointer.
+        let size = match layout.size().checked_add(layout.align()) {
+            Some(size) => size,
+            None => return ptr::null_mut(),
+        };
+        let raw = emalloc_size(size);
+        unsafe {
+            let ptr = raw.add(layout.align() - raw as usize % layout.align());
+            ptr.sub(mem::size_of::<*mut u8>())
+                .cast::<*mut u8>()
+                .write_unaligned(raw);
+            ptr
+        }
+    }
+
+    /// Frees memory which was allocated with [`ZendAllocator::alloc`].
+    ///
+    /// # Parameters
+    ///
+    /// * `ptr` - The pointer to the memory to free.
+    /// * `layout` - The layout the memory was allocated with.
+    ///
+    /// # Safety
+    ///
+    /// The memory must have been allocated by an allocator bound to the same
+    /// request, with the same layout.
+    pub unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if Self::is_overaligned(layout) {
+            efree(
+                ptr.sub(mem::size_of::<*mut u8>())
+                    .cast::<*mut u8>()
+                    .read_unaligned(),
+            )
+        } else {
+            efree(ptr)
+        }
+    }
+
+    /// Resizes memory which was allocated with [`ZendAllocator::alloc`],
+    /// keeping its contents up to the smaller of the two sizes.
+    ///
+    /// Returns a null pointer if the memory could not be resized, in which case
+    /// the original memory is left untouched.
+    ///
+    /// # Parameters
+    ///
+    /// * `ptr` - The pointer to the memory to resize.
+    /// * `layout` - The layout the memory was allocated with.
+    /// * `new_size` - The new size of the memory.
+    ///
+    /// # Safety
+    ///
+    /// The memory must have been allocated by an allocator bound to the same
+    /// request, with the same layout.
+    pub unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if !Self::is_overaligned(layout) {
+            return erealloc_size(ptr, new_size);
+        }
+        let new_ptr = self.alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+#[cfg_attr(docs, doc(cfg(feature = "allocator_api")))]
+unsafe impl std::alloc::Allocator for ZendAllocator<'_> {
+    fn allocate(&self, layout: Layout) -> Result<ptr::NonNull<[u8]>, std::alloc::AllocError> {
+        if layout.size() == 0 {
+            // SAFETY: The alignment of a layout is never zero.
+            let dangling = unsafe { ptr::NonNull::new_unchecked(layout.align() as *mut u8) };
+            return Ok(ptr::NonNull::slice_from_raw_parts(dangling, 0));
+        }
+        ptr::NonNull::new(self.alloc(layout))
+            .map(|ptr| ptr::NonNull::slice_from_raw_parts(ptr, layout.size()))
+            .ok_or(std::alloc::AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: ptr::NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            self.dealloc(ptr.as_ptr(), layout)
+        }
+    }
+}
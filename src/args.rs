@@ -1,20 +1,30 @@
 //! Builder and objects relating to function and method arguments.
 
-use std::{ffi::CString, ptr};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::CString,
+    ptr,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use crate::{
-    convert::{FromZvalMut, IntoZvalDyn},
+    class::RegisteredClass,
+    convert::{FromZvalMut, IntoZval, IntoZvalDyn},
     error::{Error, Result},
     ffi::{
         _zend_expected_type, _zend_expected_type_Z_EXPECTED_ARRAY,
         _zend_expected_type_Z_EXPECTED_BOOL, _zend_expected_type_Z_EXPECTED_DOUBLE,
         _zend_expected_type_Z_EXPECTED_LONG, _zend_expected_type_Z_EXPECTED_OBJECT,
         _zend_expected_type_Z_EXPECTED_RESOURCE, _zend_expected_type_Z_EXPECTED_STRING,
-        zend_internal_arg_info, zend_wrong_parameters_count_error,
+        ext_php_rs_zend_parse_arg_scalar, zend_argument_error, zend_argument_type_error,
+        zend_argument_value_error, zend_get_default_from_internal_arg_info, zend_internal_arg_info,
+        zend_wrong_parameters_count_error, zend_zval_type_name, zval_update_constant_ex,
+        ZEND_RESULT_CODE_SUCCESS,
     },
-    flags::DataType,
-    types::Zval,
-    zend::ZendType,
+    flags::{ClassFlags, DataType},
+    types::{ArrayKey, ZendClassObject, ZendHashTable, ZendLong, ZendObject, Zval},
+    zend::{ce, ClassEntry, ExecuteData, ExecutorGlobals, Function, ZendType},
 };
 
 /// Represents an argument to a function.
@@ -26,8 +36,10 @@ pub struct Arg<'a> {
     allow_null: bool,
     variadic: bool,
     default_value: Option<String>,
+    default_zval: Option<Zval>,
     zval: Option<&'a mut Zval>,
     variadic_zvals: Vec<Option<&'a mut Zval>>,
+    variadic_named: Vec<(String, &'a mut Zval)>,
 }
 
 impl<'a> Arg<'a> {
@@ -45,8 +57,10 @@ impl<'a> Arg<'a> {
             allow_null: false,
             variadic: false,
             default_value: None,
+            default_zval: None,
             zval: None,
             variadic_zvals: vec![],
+            variadic_named: vec![],
         }
     }
 
@@ -69,12 +83,38 @@ impl<'a> Arg<'a> {
         self
     }
 
-    /// Sets the default value for the argument.
+    /// Sets the default value for the argument, as a PHP expression such as
+    /// `5`, `'hello'`, `null`, a constant or an enum case.
+    ///
+    /// The default value is evaluated by PHP when the argument is not passed,
+    /// in the same way as the default values of PHP's own functions. The
+    /// [`ArgParser`] only evaluates it when it is given the function with
+    /// [`ArgParser::function`].
     pub fn default<T: Into<String>>(mut self, default: T) -> Self {
         self.default_value = Some(default.into());
         self
     }
 
+    /// Sets the default value for the argument from a Rust value, which is
+    /// converted into the equivalent PHP expression. `null`, booleans,
+    /// numbers, strings, arrays of those and enum cases can be used as default
+    /// values.
+    ///
+    /// The value is converted into a zval, so this can only be called while
+    /// PHP is running, such as during module startup.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDefaultValue`] if the value cannot be written
+    /// as a PHP expression.
+    pub fn default_value<T: IntoZval>(self, value: T) -> Result<Self> {
+        let zval = value.into_zval(false)?;
+        match php_expr(&zval) {
+            Some(expr) => Ok(self.default(expr)),
+            None => Err(Error::InvalidDefaultValue(self.name)),
+        }
+    }
+
     /// Attempts to consume the argument, converting the inner type into `T`.
     /// Upon success, the result is returned in a [`Result`].
     ///
@@ -83,26 +123,67 @@ impl<'a> Arg<'a> {
     ///
     /// As this function consumes, it cannot return a reference to the
     /// underlying zval.
+    #[allow(clippy::result_large_err)]
     pub fn consume<T>(mut self) -> Result<T, Self>
     where
         for<'b> T: FromZvalMut<'b>,
     {
-        self.zval
-            .as_mut()
-            .and_then(|zv| T::from_zval_mut(zv.dereference_mut()))
-            .ok_or(self)
+        match self.zval.as_mut() {
+            Some(zv) => extract(zv),
+            None => self.default_zval.as_mut().and_then(|zv| extract(zv)),
+        }
+        .ok_or(self)
     }
 
-    /// Attempts to retrieve the value of the argument.
-    /// This will be None until the ArgParser is used to parse
-    /// the arguments.
+    /// Attempts to retrieve the value of the argument, or its default value
+    /// when it was not passed. This will be None until the ArgParser is used
+    /// to parse the arguments.
     pub fn val<T>(&'a mut self) -> Option<T>
     where
         T: FromZvalMut<'a>,
     {
-        self.zval
-            .as_mut()
-            .and_then(|zv| T::from_zval_mut(zv.dereference_mut()))
+        match self.zval.as_mut() {
+            Some(zv) => extract(zv),
+            None => self.default_zval.as_mut().and_then(|zv| extract(zv)),
+        }
+    }
+
+    /// Attempts to retrieve the value of the argument, or its default value
+    /// when it was not passed, or when `null` was passed and `T` does not
+    /// accept `null`. This will be None until the ArgParser is used to parse
+    /// the arguments.
+    pub fn val_or_default<T>(&'a mut self) -> Option<T>
+    where
+        T: FromZvalMut<'a>,
+    {
+        let default = self.default_zval.as_mut();
+        match self.zval.as_mut() {
+            Some(zv) if zv.dereference().is_null() => {
+                extract(zv).or_else(|| default.and_then(|zv| extract(zv)))
+            }
+            Some(zv) => extract(zv),
+            None => default.and_then(|zv| extract(zv)),
+        }
+    }
+
+    /// Attempts to retrieve the value of the argument, or the value returned by
+    /// `default` when it was not passed, or when `null` was passed and `T`
+    /// does not accept `null`. This will be None until the ArgParser is used
+    /// to parse the arguments.
+    ///
+    /// # Parameters
+    ///
+    /// * `default` - Returns the default value of the argument.
+    pub fn val_or<T, F>(&'a mut self, default: F) -> Option<T>
+    where
+        T: FromZvalMut<'a>,
+        F: FnOnce() -> T,
+    {
+        match self.zval.as_mut() {
+            Some(zv) if zv.dereference().is_null() => Some(extract(zv).unwrap_or_else(default)),
+            Some(zv) => extract(zv),
+            None => Some(default()),
+        }
     }
 
     /// Retrice all the variadic values for this Rust argument.
@@ -113,10 +194,41 @@ impl<'a> Arg<'a> {
         self.variadic_zvals
             .iter_mut()
             .filter_map(|zv| zv.as_mut())
-            .filter_map(|zv| T::from_zval_mut(zv.dereference_mut()))
+            .filter_map(|zv| extract(zv))
+            .collect()
+    }
+
+    /// Retrieves the named arguments which were collected by this variadic
+    /// Rust argument, keyed by their name. Named arguments are collected by
+    /// the variadic argument when they do not match any other parameter, like
+    /// `...$args` does in PHP.
+    pub fn variadic_named_vals<T>(&'a mut self) -> HashMap<String, T>
+    where
+        T: FromZvalMut<'a>,
+    {
+        self.variadic_named
+            .iter_mut()
+            .filter_map(|(name, zv)| Some((name.clone(), extract(zv)?)))
             .collect()
     }
 
+    /// Retrieves all the values passed to this variadic Rust argument, both
+    /// positional and named.
+    pub fn variadic_args(&'a mut self) -> Variadic<'a> {
+        Variadic {
+            positional: self
+                .variadic_zvals
+                .iter()
+                .filter_map(|zv| zv.as_deref())
+                .collect(),
+            named: self
+                .variadic_named
+                .iter()
+                .map(|(name, zv)| (name.clone(), &**zv))
+                .collect(),
+        }
+    }
+
     /// Attempts to return a reference to the arguments internal Zval.
     ///
     /// # Returns
@@ -145,7 +257,13 @@ impl<'a> Arg<'a> {
     }
 
     /// Returns the internal PHP argument info.
-    pub(crate) fn as_arg_info(&self) -> Result<ArgInfo> {
+    ///
+    /// # Parameters
+    ///
+    /// * `optional` - Whether the argument is optional. Optional nullable
+    ///   arguments without a default value default to `null`, which allows
+    ///   them to be skipped over by named arguments.
+    pub(crate) fn as_arg_info(&self, optional: bool) -> Result<ArgInfo> {
         Ok(ArgInfo {
             name: CString::new(self.name.as_str())?.into_raw(),
             type_: ZendType::empty_from_type(
@@ -157,6 +275,9 @@ impl<'a> Arg<'a> {
             .ok_or(Error::InvalidCString)?,
             default_value: match &self.default_value {
                 Some(val) => CString::new(val.as_str())?.into_raw(),
+                None if optional && self.allow_null && !self.variadic => {
+                    CString::new("null")?.into_raw()
+                }
                 None => ptr::null(),
             },
         })
@@ -184,6 +305,30 @@ impl From<Arg<'_>> for _zend_expected_type {
     }
 }
 
+/// The values passed to a variadic parameter of a function.
+///
+/// Using this type as the last parameter of a `#[php_function]` or
+/// `#[php_method]` makes the parameter variadic, and collects named arguments
+/// which do not match any other parameter in addition to the remaining
+/// positional arguments.
+#[derive(Debug)]
+pub struct Variadic<'a> {
+    /// The positional arguments, in the order they were given.
+    pub positional: Vec<&'a Zval>,
+    /// The named arguments which did not match any other parameter.
+    pub named: HashMap<String, &'a Zval>,
+}
+
+/// Retrieves a `T` from the zval of an argument, following references unless
+/// `T` handles them itself.
+fn extract<'a, T: FromZvalMut<'a>>(zval: &'a mut Zval) -> Option<T> {
+    if T::DEREFERENCE {
+        T::from_zval_mut(zval.dereference_mut())
+    } else {
+        T::from_zval_mut(zval)
+    }
+}
+
 /// Internal argument information used by Zend.
 pub type ArgInfo = zend_internal_arg_info;
 
@@ -192,6 +337,8 @@ pub struct ArgParser<'a, 'b> {
     args: Vec<&'b mut Arg<'a>>,
     min_num_args: Option<usize>,
     arg_zvals: Vec<Option<&'a mut Zval>>,
+    named: Option<&'a ZendHashTable>,
+    func: Option<&'a Function>,
 }
 
 impl<'a, 'b> ArgParser<'a, 'b> {
@@ -201,6 +348,8 @@ impl<'a, 'b> ArgParser<'a, 'b> {
             args: vec![],
             min_num_args: None,
             arg_zvals,
+            named: None,
+            func: None,
         }
     }
 
@@ -220,6 +369,39 @@ impl<'a, 'b> ArgParser<'a, 'b> {
         self
     }
 
+    /// Inserts a value before the arguments passed by the caller, used to pass
+    /// the object a closure is bound to.
+    ///
+    /// # Parameters
+    ///
+    /// * `zval` - The value to insert.
+    pub(crate) fn prepend(mut self, zval: &'a mut Zval) -> Self {
+        self.arg_zvals.insert(0, Some(zval));
+        self
+    }
+
+    /// Sets the named arguments which PHP could not match to a parameter of
+    /// the function, which are collected by the variadic argument.
+    ///
+    /// # Parameters
+    ///
+    /// * `named` - The named arguments, keyed by name.
+    pub fn named(mut self, named: &'a ZendHashTable) -> Self {
+        self.named = Some(named);
+        self
+    }
+
+    /// Sets the function being called, whose argument info holds the default
+    /// values of the arguments.
+    ///
+    /// # Parameters
+    ///
+    /// * `func` - The function the arguments are passed to.
+    pub fn function(mut self, func: &'a Function) -> Self {
+        self.func = Some(func);
+        self
+    }
+
     /// Uses the argument parser to parse the arguments contained in the given
     /// `ExecuteData` object. Returns successfully if the arguments were
     /// parsed.
@@ -250,6 +432,8 @@ impl<'a, 'b> ArgParser<'a, 'b> {
         }
 
         for (i, arg_zval) in self.arg_zvals.into_iter().enumerate() {
+            // Arguments skipped over by a named argument are left undefined.
+            let arg_zval = arg_zval.filter(|zv| zv.get_type() != DataType::Undef);
             let arg = match self.args.get_mut(i) {
                 Some(arg) => Some(arg),
                 // Only select the last item if it's variadic
@@ -264,6 +448,615 @@ impl<'a, 'b> ArgParser<'a, 'b> {
             }
         }
 
+        // The engine places named arguments in the slot of the parameter with the
+        // same name in the argument info, and throws for duplicate and unknown
+        // names itself. The only named arguments left are the ones collected by a
+        // variadic parameter.
+        if let (Some(named), Some(arg)) =
+            (self.named, self.args.last_mut().filter(|arg| arg.variadic))
+        {
+            let names: Vec<String> = named.iter().map(|(key, _)| key.to_string()).collect();
+            for name in names {
+                if let Some(zval) = named.get_mut(name.as_str()) {
+                    arg.variadic_named.push((name, zval));
+                }
+            }
+        }
+
+        if let Some(i) = self.args[..min_num_args]
+            .iter()
+            .position(|arg| !arg.variadic && arg.zval.is_none())
+        {
+            not_passed_error(i);
+            return Err(Error::IncorrectArguments(num_args, min_num_args));
+        }
+
+        // Arguments which were not passed, or were passed as `null` to a parameter
+        // which is not nullable, fall back to their default value. Default values
+        // are evaluated from the argument info of the function, so they are left
+        // out when the function is not known.
+        if let Some(func) = self.func {
+            for (i, arg) in self.args.iter_mut().enumerate().filter(|(_, arg)| {
+                !arg.variadic
+                    && arg.default_value.is_some()
+                    && match &arg.zval {
+                        Some(zv) => !arg.allow_null && zv.dereference().is_null(),
+                        None => true,
+                    }
+            }) {
+                let mut zval = Zval::new();
+                if !default_value(func, i, &mut zval) {
+                    return Err(Error::InvalidDefaultValue(arg.name.clone()));
+                }
+                arg.default_zval = Some(zval);
+            }
+        }
+
         Ok(())
     }
 }
+
+/// Parses the arguments of a function in place from the call frame, without
+/// allocating. This is the equivalent of the `ZEND_PARSE_PARAMETERS_*` macros
+/// in C extensions, and is used by `#[php_function]` and `#[php_impl]` for
+/// functions and methods which are not variadic.
+///
+/// The arguments are retrieved in order, each one exactly once. Scalar
+/// arguments are coerced to the expected type unless the caller uses
+/// `strict_types`, and errors are reported to PHP with the same messages as
+/// the engine uses, so you should break execution after seeing an error.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{args::FastArgParser, types::Zval, zend::ExecuteData};
+///
+/// #[no_mangle]
+/// pub extern "C" fn example_fn(ex: &mut ExecuteData, retval: &mut Zval) {
+///     let mut parser = match FastArgParser::new(ex, 1, 2) {
+///         Ok(parser) => parser,
+///         Err(_) => return,
+///     };
+///     let (haystack, needle) = match (parser.required::<&str>(), parser.optional::<&str>()) {
+///         (Ok(haystack), Ok(needle)) => (haystack, needle.unwrap_or(" ")),
+///         _ => return,
+///     };
+///
+///     retval.set_bool(haystack.contains(needle));
+/// }
+/// ```
+pub struct FastArgParser<'a> {
+    ex: &'a ExecuteData,
+    num_args: usize,
+    next: usize,
+}
+
+impl<'a> FastArgParser<'a> {
+    /// Creates a parser for the arguments passed to the function, checking
+    /// that the number of arguments is within the given bounds.
+    ///
+    /// # Parameters
+    ///
+    /// * `ex` - The execution data of the function.
+    /// * `min_num_args` - The number of required arguments.
+    /// * `max_num_args` - The total number of arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IncorrectArguments`] if too many or too few arguments
+    /// were passed.
+    pub fn new(ex: &'a mut ExecuteData, min_num_args: usize, max_num_args: usize) -> Result<Self> {
+        // SAFETY: All fields of the `u2` union are the same type.
+        let num_args = unsafe { ex.This.u2.num_args } as usize;
+
+        if num_args < min_num_args || num_args > max_num_args {
+            // SAFETY: Exported C function is safe, return value is unused and parameters
+            // are copied.
+            unsafe { zend_wrong_parameters_count_error(min_num_args as _, max_num_args as _) };
+            return Err(Error::IncorrectArguments(num_args, min_num_args));
+        }
+
+        Ok(Self {
+            ex,
+            num_args,
+            next: 0,
+        })
+    }
+
+    /// Creates a parser for the arguments passed to a method, along with the
+    /// object the method was called on. The object is [`None`] when the
+    /// method is static.
+    ///
+    /// # Parameters
+    ///
+    /// * `ex` - The execution data of the method.
+    /// * `min_num_args` - The number of required arguments.
+    /// * `max_num_args` - The total number of arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IncorrectArguments`] if too many or too few arguments
+    /// were passed.
+    pub fn new_object(
+        ex: &'a mut ExecuteData,
+        min_num_args: usize,
+        max_num_args: usize,
+    ) -> Result<(Self, Option<&'a mut ZendObject>)> {
+        let ex: *mut ExecuteData = ex;
+        // SAFETY: `$this` is not one of the arguments, so it is never aliased by the
+        // arguments retrieved from the parser.
+        let this = unsafe { (*ex).This.object_mut() };
+        Ok((
+            Self::new(unsafe { &mut *ex }, min_num_args, max_num_args)?,
+            this,
+        ))
+    }
+
+    /// Creates a parser for the arguments passed to a method of the class `T`,
+    /// along with the object the method was called on. The object is [`None`]
+    /// when the method is static, or when it is not an instance of `T`.
+    ///
+    /// # Parameters
+    ///
+    /// * `ex` - The execution data of the method.
+    /// * `min_num_args` - The number of required arguments.
+    /// * `max_num_args` - The total number of arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IncorrectArguments`] if too many or too few arguments
+    /// were passed.
+    pub fn new_method<T: RegisteredClass>(
+        ex: &'a mut ExecuteData,
+        min_num_args: usize,
+        max_num_args: usize,
+    ) -> Result<(Self, Option<&'a mut ZendClassObject<T>>)> {
+        let (parser, this) = Self::new_object(ex, min_num_args, max_num_args)?;
+        Ok((parser, this.and_then(ZendClassObject::from_zend_obj_mut)))
+    }
+
+    /// Retrieves the next required argument.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the argument was not passed, or could not be
+    /// converted into `T`.
+    pub fn required<T: FromZvalMut<'a>>(&mut self) -> Result<T> {
+        let num = self.next;
+        match self.next_zval() {
+            Some(zval) => convert(num, zval, false),
+            None => {
+                not_passed_error(num);
+                Err(Error::IncorrectArguments(num, num + 1))
+            }
+        }
+    }
+
+    /// Retrieves the next argument, returning [`None`] if it was not passed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the argument could not be converted into `T`.
+    pub fn optional<T: FromZvalMut<'a>>(&mut self) -> Result<Option<T>> {
+        let num = self.next;
+        self.next_zval()
+            .map(|zval| convert(num, zval, false))
+            .transpose()
+    }
+
+    /// Retrieves the next argument, returning [`None`] if it was not passed or
+    /// is `null`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the argument could not be converted into `T`.
+    pub fn nullable<T: FromZvalMut<'a>>(&mut self) -> Result<Option<T>> {
+        self.optional_nullable().map(Option::flatten)
+    }
+
+    /// Retrieves the next argument, returning [`None`] if it was not passed
+    /// and `Some(None)` if it is `null`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the argument could not be converted into `T`.
+    pub fn optional_nullable<T: FromZvalMut<'a>>(&mut self) -> Result<Option<Option<T>>> {
+        let num = self.next;
+        self.next_zval()
+            .map(|zval| {
+                if zval.dereference().is_null() {
+                    Ok(None)
+                } else {
+                    convert(num, zval, true).map(Some)
+                }
+            })
+            .transpose()
+    }
+
+    /// Retrieves the next argument, or its default value when it was not
+    /// passed. The default value is evaluated from the argument info of the
+    /// function into `slot`, in the same way as PHP does.
+    ///
+    /// # Parameters
+    ///
+    /// * `slot` - The zval to evaluate the default value into.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the default value could not be evaluated, or if the
+    /// argument could not be converted into `T`.
+    pub fn default<T: FromZvalMut<'a>>(&mut self, slot: &'a mut Zval) -> Result<T> {
+        let num = self.next;
+        let zval = match self.next_zval() {
+            Some(zval) => zval,
+            None => self.default_zval(num, slot)?,
+        };
+        convert(num, zval, false)
+    }
+
+    /// Retrieves the next argument, or its default value when it was not
+    /// passed. If `null` is passed and `T` does not accept `null`, such as
+    /// when `T` is not a variant of [`Option`], the default value is used.
+    ///
+    /// # Parameters
+    ///
+    /// * `slot` - The zval to evaluate the default value into.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the default value could not be evaluated, or if the
+    /// argument could not be converted into `T`.
+    pub fn default_nullable<T: FromZvalMut<'a>>(&mut self, slot: &'a mut Zval) -> Result<T> {
+        let num = self.next;
+        let zval = match self.next_zval() {
+            Some(zval) if zval.dereference().is_null() => match extract(zval) {
+                Some(val) => return Ok(val),
+                None => self.default_zval(num, slot)?,
+            },
+            Some(zval) => zval,
+            None => self.default_zval(num, slot)?,
+        };
+        convert(num, zval, true)
+    }
+
+    /// Retrieves the next argument, or the value returned by `default` when it
+    /// was not passed.
+    ///
+    /// # Parameters
+    ///
+    /// * `default` - Returns the default value of the argument.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the argument could not be converted into `T`.
+    pub fn default_with<T, F>(&mut self, default: F) -> Result<T>
+    where
+        T: FromZvalMut<'a>,
+        F: FnOnce() -> T,
+    {
+        let num = self.next;
+        match self.next_zval() {
+            Some(zval) => convert(num, zval, false),
+            None => Ok(default()),
+        }
+    }
+
+    /// Retrieves the next argument, or the value returned by `default` when it
+    /// was not passed. If `null` is passed and `T` does not accept `null`, such
+    /// as when `T` is not a variant of [`Option`], the default value is used.
+    ///
+    /// # Parameters
+    ///
+    /// * `default` - Returns the default value of the argument.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the argument could not be converted into `T`.
+    pub fn default_nullable_with<T, F>(&mut self, default: F) -> Result<T>
+    where
+        T: FromZvalMut<'a>,
+        F: FnOnce() -> T,
+    {
+        let num = self.next;
+        match self.next_zval() {
+            Some(zval) if zval.dereference().is_null() => Ok(extract(zval).unwrap_or_else(default)),
+            Some(zval) => convert(num, zval, true),
+            None => Ok(default()),
+        }
+    }
+
+    /// Evaluates the default value of the argument `num` into `slot`.
+    fn default_zval(&self, num: usize, slot: &'a mut Zval) -> Result<&'a mut Zval> {
+        // SAFETY: The function being called outlives the call.
+        let func = unsafe { &*self.ex.func };
+        if default_value(func, num, slot) {
+            return Ok(slot);
+        }
+        Err(Error::InvalidDefaultValue(num.to_string()))
+    }
+
+    /// Returns the zval of the next argument, or [`None`] if it was not
+    /// passed.
+    fn next_zval(&mut self) -> Option<&'a mut Zval> {
+        let n = self.next;
+        self.next += 1;
+        if n >= self.num_args {
+            return None;
+        }
+
+        // SAFETY: The argument is within the arguments passed to the function, which
+        // live as long as the execution data. Each argument is only retrieved once.
+        unsafe { self.ex.zend_call_arg(n) }.filter(|zval| zval.get_type() != DataType::Undef)
+    }
+}
+
+/// Whether evaluated default values are cached until the end of the request,
+/// which is only the case when the module releases them when the request ends.
+static CACHE_DEFAULTS: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// The default values of arguments evaluated during the current request,
+    /// keyed by the address of the argument info they were evaluated from.
+    static DEFAULTS: RefCell<HashMap<usize, Zval>> = RefCell::new(HashMap::new());
+}
+
+/// Copies the default value of the argument `num` of `func` into `zval`.
+/// Returns false if the argument has no default value, or if it could not be
+/// evaluated.
+///
+/// If caching has been enabled with [`cache_defaults`], the default value is
+/// evaluated the first time it is used during a request, and is then reused
+/// until the request ends. Otherwise it is evaluated each time.
+fn default_value(func: &Function, num: usize, zval: &mut Zval) -> bool {
+    // SAFETY: All fields of the `common` union start with the same fields.
+    let common = unsafe { &func.common };
+    if num >= common.num_args as usize {
+        return false;
+    }
+    // SAFETY: Internal functions have argument info for each of their arguments.
+    let arg_info = unsafe { (common.arg_info as *const ArgInfo).add(num) };
+    if !CACHE_DEFAULTS.load(Ordering::Relaxed) {
+        // SAFETY: The argument info and the scope are valid for as long as the function.
+        return unsafe { eval_default(zval, arg_info, common.scope) };
+    }
+    let key = arg_info as usize;
+
+    if let Some(default) =
+        DEFAULTS.with(|defaults| defaults.borrow().get(&key).map(Zval::shallow_clone))
+    {
+        *zval = default;
+        return true;
+    }
+
+    let mut default = Zval::new();
+    // SAFETY: The argument info and the scope are valid for as long as the function.
+    if !unsafe { eval_default(&mut default, arg_info, common.scope) } {
+        return false;
+    }
+    *zval = default.shallow_clone();
+    DEFAULTS.with(|defaults| defaults.borrow_mut().insert(key, default));
+    true
+}
+
+/// Enables caching of evaluated default values. Called by modules which
+/// release them with [`release_defaults`] when a request ends.
+pub(crate) fn cache_defaults() {
+    CACHE_DEFAULTS.store(true, Ordering::Relaxed);
+}
+
+/// Releases the default values evaluated during the request. Called when the
+/// request ends, before its memory is freed.
+pub(crate) fn release_defaults() {
+    // The values are dropped once the cache is no longer borrowed.
+    let defaults = DEFAULTS.with(|defaults| std::mem::take(&mut *defaults.borrow_mut()));
+    drop(defaults);
+}
+
+/// Evaluates the default value of an argument into `zval`, in the same way as
+/// PHP does when the argument is skipped over by a named argument. Returns
+/// false if the default value could not be evaluated, in which case an
+/// exception may have been thrown.
+///
+/// # Safety
+///
+/// `arg_info` must point to valid argument info, and `scope` must either be
+/// null or point to a valid class entry.
+unsafe fn eval_default(zval: &mut Zval, arg_info: *const ArgInfo, scope: *mut ClassEntry) -> bool {
+    if zend_get_default_from_internal_arg_info(zval, arg_info as *mut _) != ZEND_RESULT_CODE_SUCCESS
+    {
+        return false;
+    }
+
+    // Constants and enum cases are evaluated on first use, like in PHP.
+    zval.get_type() != DataType::ConstantExpression
+        || zval_update_constant_ex(zval, scope) == ZEND_RESULT_CODE_SUCCESS
+}
+
+/// Writes a zval as the equivalent PHP expression, used as the default value
+/// of an argument. Returns [`None`] if the zval has no such expression.
+fn php_expr(zval: &Zval) -> Option<String> {
+    let zval = zval.dereference();
+    Some(match zval.get_type() {
+        DataType::Null => "null".to_string(),
+        DataType::True => "true".to_string(),
+        DataType::False => "false".to_string(),
+        DataType::Long => zval.long()?.to_string(),
+        DataType::Double => match zval.double()? {
+            val if val.is_nan() => "NAN".to_string(),
+            val if val.is_infinite() && val > 0.0 => "INF".to_string(),
+            val if val.is_infinite() => "-INF".to_string(),
+            val => format!("{val:?}"),
+        },
+        DataType::String => php_str(zval.str()?),
+        DataType::Array => {
+            let items = zval
+                .array()?
+                .iter()
+                .enumerate()
+                .map(|(i, (key, val))| {
+                    let val = php_expr(val)?;
+                    Some(match key {
+                        ArrayKey::Long(key) if key == i as i64 => val,
+                        ArrayKey::Long(key) => format!("{key} => {val}"),
+                        key => format!("{} => {val}", php_str(&key.to_string())),
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            format!("[{}]", items.join(", "))
+        }
+        DataType::Object(_) => {
+            let obj = zval.object()?;
+            if !obj.get_class_entry().flags().contains(ClassFlags::Enum) {
+                return None;
+            }
+            let case: String = obj.get_property("name").ok()?;
+            format!("\\{}::{case}", obj.get_class_name().ok()?)
+        }
+        _ => return None,
+    })
+}
+
+/// Writes a string as a single-quoted PHP string literal.
+fn php_str(val: &str) -> String {
+    format!("'{}'", val.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Converts the zval of the argument `num` into `T`, throwing a `TypeError`
+/// with the same message as PHP if the conversion fails.
+///
+/// Like the `Z_PARAM_*` macros, scalar arguments of another type are coerced
+/// in place first, unless the caller uses `strict_types`.
+fn convert<'a, T: FromZvalMut<'a>>(num: usize, zval: &'a mut Zval, allow_null: bool) -> Result<T> {
+    let ptr: *mut Zval = zval;
+    // SAFETY: A failed conversion no longer borrows the zval, so it can be
+    // converted again or read from once it has failed.
+    if let Some(val) = extract(unsafe { &mut *ptr }) {
+        return Ok(val);
+    }
+    if coerce(num, T::TYPE, unsafe { &mut *ptr }) {
+        if let Some(val) = extract(unsafe { &mut *ptr }) {
+            return Ok(val);
+        }
+    }
+
+    // Coercions may emit a deprecation which was turned into an exception, in which
+    // case no other error is thrown, like PHP.
+    if !ExecutorGlobals::has_exception() {
+        wrong_type_error(num, T::TYPE, allow_null, unsafe { &*ptr }, T::RANGE);
+    }
+    Err(Error::ZvalConversion(unsafe { &*ptr }.get_type()))
+}
+
+/// Coerces the zval of the argument `num` to the scalar type `ty` in place.
+/// Returns true if the zval was coerced.
+fn coerce(num: usize, ty: DataType, zval: &mut Zval) -> bool {
+    if !matches!(
+        ty,
+        DataType::Long | DataType::Double | DataType::String | DataType::Bool
+    ) || zval.is_reference()
+    {
+        return false;
+    }
+    // SAFETY: The zval is a valid argument which is not a reference, and is only
+    // replaced by its coerced value.
+    unsafe { ext_php_rs_zend_parse_arg_scalar(zval, ty.as_u32(), (num + 1) as _) }
+}
+
+/// Throws the `ArgumentCountError` PHP uses when the argument `num` (starting at
+/// zero) of the current function was not passed.
+fn not_passed_error(num: usize) {
+    // SAFETY: The class entry is valid for the lifetime of the request, and the
+    // format string is NUL-terminated and contains no format specifiers.
+    unsafe {
+        zend_argument_error(
+            ce::argument_count_error() as *const _ as *mut _,
+            (num + 1) as _,
+            b"not passed\0".as_ptr().cast(),
+        )
+    };
+}
+
+/// Throws the `TypeError` PHP uses when the argument `num` (starting at zero) of
+/// the current function is not of the expected type, or a `ValueError` when it
+/// is of the expected type but its value could not be converted, such as an
+/// integer outside of `bounds`.
+fn wrong_type_error(
+    num: usize,
+    ty: DataType,
+    allow_null: bool,
+    zval: &Zval,
+    bounds: Option<(ZendLong, ZendLong)>,
+) {
+    let name = match ty {
+        DataType::Long => "int",
+        DataType::Double => "float",
+        DataType::String => "string",
+        DataType::Bool | DataType::True | DataType::False => "bool",
+        DataType::Array => "array",
+        DataType::Object(Some(class)) => class,
+        DataType::Object(None) => "object",
+        DataType::Resource => "resource",
+        DataType::Callable => "callable",
+        DataType::Iterable => "iterable",
+        DataType::Null => "null",
+        _ => "mixed",
+    };
+    let nullable = if allow_null && !matches!(ty, DataType::Mixed | DataType::Null) {
+        "?"
+    } else {
+        ""
+    };
+    let expected = match CString::new(format!("{nullable}{name}")) {
+        Ok(expected) => expected,
+        Err(_) => return,
+    };
+    let same_type = matches!(
+        (ty, zval.dereference().get_type()),
+        (DataType::Long, DataType::Long)
+            | (DataType::Double, DataType::Double)
+            | (DataType::String, DataType::String)
+            | (DataType::Array, DataType::Array)
+            | (DataType::Resource, DataType::Resource)
+            | (
+                DataType::Bool | DataType::True | DataType::False,
+                DataType::True | DataType::False
+            )
+            | (DataType::Object(None), DataType::Object(_))
+            | (DataType::Mixed, _)
+    );
+
+    if same_type {
+        let zval = zval.dereference();
+        let message = match (bounds, zval.zend_str()) {
+            (Some((min, max)), _) => format!("must be between {min} and {max}"),
+            (None, Some(str)) => match std::str::from_utf8(str.as_bytes()) {
+                Err(_) => "must be a valid UTF-8 string".to_string(),
+                // Strings are only rejected otherwise when converted into a `char`.
+                Ok(str) if str.chars().count() != 1 => "must be a single character".to_string(),
+                Ok(_) => "contains an invalid value".to_string(),
+            },
+            _ => "contains an invalid value".to_string(),
+        };
+        let message = match CString::new(message) {
+            Ok(message) => message,
+            Err(_) => return,
+        };
+        // SAFETY: The format string is NUL-terminated and its specifier matches the
+        // argument, which is a valid C string.
+        unsafe {
+            zend_argument_value_error((num + 1) as _, b"%s\0".as_ptr().cast(), message.as_ptr())
+        };
+        return;
+    }
+
+    // SAFETY: The format string is NUL-terminated and its specifiers match the
+    // arguments, which are valid C strings.
+    unsafe {
+        zend_argument_type_error(
+            (num + 1) as _,
+            b"must be of type %s, %s given\0".as_ptr().cast(),
+            expected.as_ptr(),
+            zend_zval_type_name(zval),
+        )
+    };
+}
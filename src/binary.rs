@@ -23,6 +23,10 @@ use crate::{
 /// used for passing binary data into Rust functions. Can be treated as a
 /// [`Vec`] in most situations, or can be 'unwrapped' into a [`Vec`] through the
 /// [`From`] implementation on [`Vec`].
+///
+/// `Binary<u8>` is the owned counterpart of a `&[u8]` parameter, passing a
+/// `Vec<u8>` to and from PHP as a string. A plain `Vec<u8>` is converted into
+/// an array of integers, like any other [`Vec`].
 #[derive(Debug)]
 pub struct Binary<T: Pack>(Vec<T>);
 
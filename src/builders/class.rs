@@ -1,4 +1,4 @@
-use std::{ffi::CString, mem::MaybeUninit};
+use std::{ffi::CString, mem::MaybeUninit, os::raw::c_int};
 
 use crate::{
     builders::FunctionBuilder,
@@ -7,15 +7,19 @@ use crate::{
     error::{Error, Result},
     exception::PhpException,
     ffi::{
-        zend_declare_class_constant, zend_declare_property, zend_do_implement_interface,
-        zend_register_internal_class_ex,
+        zend_declare_class_constant, zend_declare_property, zend_declare_typed_property,
+        zend_do_implement_interface, zend_register_internal_class_ex,
     },
-    flags::{ClassFlags, MethodFlags, PropertyFlags},
-    types::{ZendClassObject, ZendObject, ZendStr, Zval},
-    zend::{ClassEntry, ExecuteData, FunctionEntry},
+    flags::{ClassFlags, DataType, MethodFlags, PropertyFlags, ZvalTypeFlags},
+    types::{ZendClassObject, ZendIterator, ZendObject, ZendStr, Zval},
+    zend::{ClassEntry, ExecuteData, FunctionEntry, ZendType},
     zend_fastcall,
 };
 
+/// A property declared on a class: the name, default value, flags and
+/// optionally the type of the property and whether it is nullable.
+type PropertyDecl = (String, Zval, PropertyFlags, Option<(DataType, bool)>);
+
 /// Builder for registering a class in PHP.
 pub struct ClassBuilder {
     name: String,
@@ -24,8 +28,15 @@ pub struct ClassBuilder {
     interfaces: Vec<&'static ClassEntry>,
     methods: Vec<FunctionEntry>,
     object_override: Option<unsafe extern "C" fn(class_type: *mut ClassEntry) -> *mut ZendObject>,
-    properties: Vec<(String, Zval, PropertyFlags)>,
+    properties: Vec<PropertyDecl>,
     constants: Vec<(String, Zval)>,
+    pub(crate) get_iterator: Option<
+        unsafe extern "C" fn(
+            ce: *mut ClassEntry,
+            object: *mut Zval,
+            by_ref: c_int,
+        ) -> *mut ZendIterator,
+    >,
 }
 
 impl ClassBuilder {
@@ -47,6 +58,7 @@ impl ClassBuilder {
             object_override: None,
             properties: vec![],
             constants: vec![],
+            get_iterator: None,
         }
     }
 
@@ -115,7 +127,41 @@ impl ClassBuilder {
             Err(_) => panic!("Invalid default value for property `{}`.", name.into()),
         };
 
-        self.properties.push((name.into(), default, flags));
+        self.properties.push((name.into(), default, flags, None));
+        self
+    }
+
+    /// Adds a typed property to the class. PHP will refuse to assign values
+    /// which do not match the type of the property.
+    ///
+    /// If the data type cannot be used as a property type in PHP, such as
+    /// [`DataType::Callable`], the property is declared without a type.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property to add to the class.
+    /// * `ty` - The type of the property.
+    /// * `allow_null` - Whether the property can be set to null.
+    /// * `default` - The default value of the property. If [`None`], the
+    ///   property is left uninitialized, which is the case for properties
+    ///   whose value is provided by a Rust struct field.
+    /// * `flags` - Flags relating to the property. See [`PropertyFlags`].
+    pub fn typed_property<T: Into<String>>(
+        mut self,
+        name: T,
+        ty: DataType,
+        allow_null: bool,
+        default: Option<Zval>,
+        flags: PropertyFlags,
+    ) -> Self {
+        let default = default.unwrap_or_else(|| {
+            let mut zv = Zval::new();
+            zv.u1.type_info = ZvalTypeFlags::Undef.bits();
+            zv
+        });
+
+        self.properties
+            .push((name.into(), default, flags, Some((ty, allow_null))));
         self
     }
 
@@ -256,6 +302,12 @@ impl ClassBuilder {
             }
         }
 
+        // The native iterator must be set before implementing `IteratorAggregate`,
+        // otherwise the engine would iterate through the `getIterator` method.
+        if self.get_iterator.is_some() {
+            class.get_iterator = self.get_iterator;
+        }
+
         for iface in self.interfaces {
             unsafe {
                 zend_do_implement_interface(
@@ -266,15 +318,32 @@ impl ClassBuilder {
             };
         }
 
-        for (name, mut default, flags) in self.properties {
-            unsafe {
-                zend_declare_property(
-                    class,
-                    CString::new(name.as_str())?.as_ptr(),
-                    name.len() as _,
-                    &mut default,
-                    flags.bits() as _,
-                );
+        for (name, mut default, flags, ty) in self.properties {
+            match ty.and_then(|(ty, allow_null)| ZendType::property_type(ty, allow_null)) {
+                Some(ty) => unsafe {
+                    zend_declare_typed_property(
+                        class,
+                        ZendStr::new_interned(&name, true).into_raw(),
+                        &mut default,
+                        flags.bits() as _,
+                        std::ptr::null_mut(),
+                        ty,
+                    );
+                },
+                None => {
+                    if default.get_type() == DataType::Undef {
+                        default.set_null();
+                    }
+                    unsafe {
+                        zend_declare_property(
+                            class,
+                            CString::new(name.as_str())?.as_ptr(),
+                            name.len() as _,
+                            &mut default,
+                            flags.bits() as _,
+                        );
+                    }
+                }
             }
         }
 
@@ -0,0 +1,185 @@
+use std::ffi::CString;
+
+use crate::{
+    convert::IntoZval,
+    enum_::EnumValue,
+    error::{Error, Result},
+    ffi::{
+        zend_declare_class_constant, zend_do_implement_interface, zend_enum_add_case_cstr,
+        zend_register_internal_enum, IS_LONG, IS_STRING, IS_UNDEF,
+    },
+    flags::{DataType, MethodFlags},
+    types::Zval,
+    zend::{ClassEntry, FunctionEntry},
+};
+
+/// Builder for registering a native enum in PHP.
+pub struct EnumBuilder {
+    name: String,
+    backing: Option<DataType>,
+    interfaces: Vec<&'static ClassEntry>,
+    methods: Vec<FunctionEntry>,
+    cases: Vec<(String, Option<EnumValue>)>,
+    constants: Vec<(String, Zval)>,
+}
+
+impl EnumBuilder {
+    /// Creates a new enum builder, used to build pure enums to be exported
+    /// to PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the enum.
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            backing: None,
+            interfaces: vec![],
+            methods: vec![],
+            cases: vec![],
+            constants: vec![],
+        }
+    }
+
+    /// Sets the backing type of the enum, turning it into a backed enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `ty` - The backing type. Must be either [`DataType::Long`] or
+    ///   [`DataType::String`], otherwise building the enum will fail.
+    pub fn backed(mut self, ty: DataType) -> Self {
+        self.backing = Some(ty);
+        self
+    }
+
+    /// Implements an interface on the enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `interface` - Interface to implement on the enum.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the given class entry `interface` is not an interface.
+    pub fn implements(mut self, interface: &'static ClassEntry) -> Self {
+        assert!(
+            interface.is_interface(),
+            "Given class entry was not an interface."
+        );
+        self.interfaces.push(interface);
+        self
+    }
+
+    /// Adds a method to the enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `func` - The function entry to add to the enum.
+    /// * `flags` - Flags relating to the function. See [`MethodFlags`].
+    pub fn method(mut self, mut func: FunctionEntry, flags: MethodFlags) -> Self {
+        func.flags |= flags.bits();
+        self.methods.push(func);
+        self
+    }
+
+    /// Adds a case to the enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the case.
+    /// * `value` - The backing value of the case. Must be [`None`] for pure
+    ///   enums, and must match the backing type for backed enums.
+    pub fn case<T: Into<String>>(mut self, name: T, value: Option<EnumValue>) -> Self {
+        self.cases.push((name.into(), value));
+        self
+    }
+
+    /// Adds a constant to the enum. The type of the constant is defined by the
+    /// type of the given value.
+    ///
+    /// Returns a result containing the enum builder if the constant was
+    /// successfully added.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant to add to the enum.
+    /// * `value` - The value of the constant.
+    pub fn constant<T: Into<String>>(mut self, name: T, value: impl IntoZval) -> Result<Self> {
+        let value = value.into_zval(true)?;
+
+        self.constants.push((name.into(), value));
+        Ok(self)
+    }
+
+    /// Builds the enum, returning a reference to the class entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] variant if the enum could not be registered, the
+    /// backing type is not `int` or `string`, or a case value does not match
+    /// the backing type.
+    pub fn build(mut self) -> Result<&'static mut ClassEntry> {
+        let backing = match self.backing {
+            None => IS_UNDEF,
+            Some(DataType::Long) => IS_LONG,
+            Some(DataType::String) => IS_STRING,
+            Some(ty) => return Err(Error::InvalidEnumBacking(ty)),
+        };
+
+        for (name, value) in &self.cases {
+            if value.map(|value| value.data_type()) != self.backing {
+                return Err(Error::InvalidEnumCase(name.clone(), self.backing));
+            }
+        }
+
+        let name = CString::new(self.name.as_str())?;
+
+        self.methods.push(FunctionEntry::end());
+        let func = Box::into_raw(self.methods.into_boxed_slice()) as *const FunctionEntry;
+
+        let class = unsafe {
+            zend_register_internal_enum(name.as_ptr(), backing as _, func)
+                .as_mut()
+                .ok_or(Error::InvalidPointer)?
+        };
+
+        for iface in self.interfaces {
+            unsafe {
+                zend_do_implement_interface(
+                    class,
+                    iface as *const crate::ffi::_zend_class_entry
+                        as *mut crate::ffi::_zend_class_entry,
+                )
+            };
+        }
+
+        for (name, value) in self.cases {
+            let name = CString::new(name)?;
+            let mut value = value.map(|value| value.to_zval()).transpose()?;
+            unsafe {
+                zend_enum_add_case_cstr(
+                    class,
+                    name.as_ptr(),
+                    match &mut value {
+                        Some(value) => value,
+                        None => std::ptr::null_mut(),
+                    },
+                )
+            };
+        }
+
+        for (name, value) in self.constants {
+            let value = Box::into_raw(Box::new(value));
+            unsafe {
+                zend_declare_class_constant(
+                    class,
+                    CString::new(name.as_str())?.as_ptr(),
+                    name.len(),
+                    value,
+                )
+            };
+        }
+
+        Ok(class)
+    }
+}
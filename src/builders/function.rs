@@ -148,9 +148,11 @@ impl<'a> FunctionBuilder<'a> {
     pub fn build(mut self) -> Result<FunctionEntry> {
         let mut args = Vec::with_capacity(self.args.len() + 1);
 
+        let n_req = self.n_req.unwrap_or(self.args.len());
+
         // argument header, retval etc
         args.push(ArgInfo {
-            name: self.n_req.unwrap_or(self.args.len()) as *const _,
+            name: n_req as *const _,
             type_: match self.retval {
                 Some(retval) => {
                     ZendType::empty_from_type(retval, self.ret_as_ref, false, self.ret_as_null)
@@ -165,7 +167,8 @@ impl<'a> FunctionBuilder<'a> {
         args.extend(
             self.args
                 .iter()
-                .map(|arg| arg.as_arg_info())
+                .enumerate()
+                .map(|(i, arg)| arg.as_arg_info(i >= n_req))
                 .collect::<Result<Vec<_>>>()?,
         );
 
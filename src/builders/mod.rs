@@ -2,12 +2,16 @@
 //! Generally zero-cost abstractions.
 
 mod class;
+#[cfg(any(php81, php82))]
+mod enum_;
 mod function;
 mod module;
 #[cfg(feature = "embed")]
 mod sapi;
 
 pub use class::ClassBuilder;
+#[cfg(any(php81, php82))]
+pub use enum_::EnumBuilder;
 pub use function::FunctionBuilder;
 pub use module::ModuleBuilder;
 #[cfg(feature = "embed")]
@@ -1,12 +1,21 @@
 use crate::{
+    args,
     error::Result,
     ffi::{ext_php_rs_php_build_id, ZEND_MODULE_API_NO},
     zend::{FunctionEntry, ModuleEntry},
     PHP_DEBUG, PHP_ZTS,
 };
 
+use once_cell::sync::OnceCell;
 use std::{ffi::CString, mem, ptr};
 
+/// The request shutdown function set by the extension, called by
+/// [`request_shutdown`].
+static REQUEST_SHUTDOWN: OnceCell<Option<RequestFunc>> = OnceCell::new();
+
+/// A request startup or shutdown function, as stored in a [`ModuleEntry`].
+type RequestFunc = unsafe extern "C" fn(i32, i32) -> i32;
+
 /// Builds a Zend module extension to be registered with PHP. Must be called
 /// from within an external function called `get_module`, returning a mutable
 /// pointer to a `ModuleEntry`.
@@ -40,6 +49,7 @@ pub struct ModuleBuilder {
     version: String,
     module: ModuleEntry,
     functions: Vec<FunctionEntry>,
+    cache_defaults: bool,
 }
 
 impl ModuleBuilder {
@@ -83,6 +93,7 @@ impl ModuleBuilder {
                 build_id: unsafe { ext_php_rs_php_build_id() },
             },
             functions: vec![],
+            cache_defaults: false,
         }
     }
 
@@ -151,6 +162,18 @@ impl ModuleBuilder {
         self
     }
 
+    /// Caches the default values of arguments given as PHP expressions, such as
+    /// with the `default_exprs` option of the macros, once they have been
+    /// evaluated, until the end of the request. Otherwise default values are
+    /// evaluated each time they are used.
+    ///
+    /// This is enabled by [`macro@crate::php_module`] when a function or a
+    /// method has default expressions.
+    pub fn cache_default_values(mut self) -> Self {
+        self.cache_defaults = true;
+        self
+    }
+
     /// Adds a function to the extension.
     ///
     /// # Arguments
@@ -165,6 +188,14 @@ impl ModuleBuilder {
     ///
     /// Returns a result containing the module entry if successful.
     pub fn build(mut self) -> Result<ModuleEntry> {
+        if self.cache_defaults
+            && REQUEST_SHUTDOWN
+                .set(self.module.request_shutdown_func)
+                .is_ok()
+        {
+            self.module.request_shutdown_func = Some(request_shutdown);
+            args::cache_defaults();
+        }
         self.functions.push(FunctionEntry::end());
         self.module.functions =
             Box::into_raw(self.functions.into_boxed_slice()) as *const FunctionEntry;
@@ -180,3 +211,13 @@ pub type StartupShutdownFunc = extern "C" fn(_type: i32, _module_number: i32) ->
 
 /// A function to be called when `phpinfo();` is called.
 pub type InfoFunc = extern "C" fn(zend_module: *mut ModuleEntry);
+
+/// Called when a request ends, if default values are cached. Calls the request
+/// shutdown function of the extension, then releases the default argument
+/// values evaluated during the request.
+extern "C" fn request_shutdown(ty: i32, module_number: i32) -> i32 {
+    let func = REQUEST_SHUTDOWN.get().copied().flatten();
+    let result = func.map_or(0, |func| unsafe { func(ty, module_number) });
+    args::release_defaults();
+    result
+}
@@ -1,6 +1,7 @@
 //! Types and traits used for registering classes with PHP.
 
 use std::{
+    cmp,
     collections::HashMap,
     marker::PhantomData,
     sync::atomic::{AtomicPtr, Ordering},
@@ -9,10 +10,13 @@ use std::{
 use once_cell::sync::OnceCell;
 
 use crate::{
+    boxed::ZBox,
     builders::FunctionBuilder,
-    exception::PhpException,
+    exception::{PhpException, PhpResult},
+    flags::{DataType, Operator},
     props::Property,
-    zend::{ClassEntry, ExecuteData, ZendObjectHandlers},
+    types::{ZendHashTable, Zval},
+    zend::{ClassEntry, ExecuteData, GcBuffer, ZendObjectHandlers},
 };
 
 /// Implemented on Rust types which are exported to PHP. Allows users to get and
@@ -24,6 +28,27 @@ pub trait RegisteredClass: Sized + 'static {
     /// Optional class constructor.
     const CONSTRUCTOR: Option<ConstructorMeta<Self>> = None;
 
+    /// Whether objects of the class can be cloned, in which case
+    /// [`clone_obj`] is called to create the copy. Otherwise the engine throws
+    /// an `Error` when the object is cloned.
+    ///
+    /// This is set by the [`macro@php_impl`] macro when a `clone_obj` handler
+    /// is defined.
+    ///
+    /// [`clone_obj`]: RegisteredClass::clone_obj
+    /// [`macro@php_impl`]: crate::php_impl
+    const CLONEABLE: bool = false;
+
+    /// Whether the Rust value of objects of the class holds PHP values, in
+    /// which case [`get_gc`] is called by the cycle collector to find them.
+    ///
+    /// This is set by the [`macro@php_impl`] macro when a `get_gc` handler is
+    /// defined.
+    ///
+    /// [`get_gc`]: RegisteredClass::get_gc
+    /// [`macro@php_impl`]: crate::php_impl
+    const HAS_GC: bool = false;
+
     /// Returns a reference to the class metadata, which stores the class entry
     /// and handlers.
     ///
@@ -43,6 +68,107 @@ pub trait RegisteredClass: Sized + 'static {
     /// through the [`ClassMetadata::get_properties`] function, which builds the
     /// hashmap one and stores it in memory.
     fn get_properties<'a>() -> HashMap<&'static str, Property<'a, Self>>;
+
+    /// Compares the object with another value, used by the `==`, `<`, `>` and
+    /// `<=>` operators. The object may be on either side of the operator, the
+    /// result is always from the point of view of `self`.
+    ///
+    /// Returns [`None`] to use the default PHP object comparison.
+    fn compare(&self, _other: &Zval) -> PhpResult<Option<cmp::Ordering>> {
+        Ok(None)
+    }
+
+    /// Converts the object to a scalar type, used by `(string)`, `(int)`,
+    /// `(float)` and `(bool)` casts as well as implicit conversions. The
+    /// returned value must be of the requested type.
+    ///
+    /// Returns [`None`] to use the default PHP conversion.
+    fn cast(&self, _ty: DataType) -> PhpResult<Option<Zval>> {
+        Ok(None)
+    }
+
+    /// Creates a copy of the object when it is cloned with the `clone`
+    /// keyword. Declared properties are copied over to the new object
+    /// afterwards.
+    ///
+    /// Only called when [`CLONEABLE`] is `true`. Returns [`None`] if this
+    /// particular object cannot be cloned, in which case an `Error` is thrown.
+    ///
+    /// [`CLONEABLE`]: RegisteredClass::CLONEABLE
+    fn clone_obj(&self) -> Option<Self> {
+        None
+    }
+
+    /// Reads the value at `offset` when the object is accessed as an array,
+    /// i.e. `$obj[$offset]`.
+    ///
+    /// Returns [`None`] to use the default behaviour, which throws an `Error`
+    /// unless the class implements `ArrayAccess`.
+    fn read_dimension(&self, _offset: &Zval) -> PhpResult<Option<Zval>> {
+        Ok(None)
+    }
+
+    /// Writes `value` at `offset` when the object is accessed as an array,
+    /// i.e. `$obj[$offset] = $value`. The offset is [`None`] when appending,
+    /// i.e. `$obj[] = $value`.
+    ///
+    /// Returns `false` to use the default behaviour.
+    fn write_dimension(&mut self, _offset: Option<&Zval>, _value: &Zval) -> PhpResult<bool> {
+        Ok(false)
+    }
+
+    /// Checks whether `offset` exists when the object is accessed as an
+    /// array. When `check_empty` is `false` this is used by `isset()` and
+    /// should return whether the value exists and is not null, otherwise it is
+    /// used by `empty()` and should return whether the value is truthy.
+    ///
+    /// Returns [`None`] to use the default behaviour.
+    fn has_dimension(&self, _offset: &Zval, _check_empty: bool) -> PhpResult<Option<bool>> {
+        Ok(None)
+    }
+
+    /// Removes the value at `offset` when the object is accessed as an array,
+    /// i.e. `unset($obj[$offset])`.
+    ///
+    /// Returns `false` to use the default behaviour.
+    fn unset_dimension(&mut self, _offset: &Zval) -> PhpResult<bool> {
+        Ok(false)
+    }
+
+    /// Returns the number of elements in the object, used by `count()`.
+    ///
+    /// Returns [`None`] to use the default behaviour, which calls
+    /// `Countable::count()` if the class implements it.
+    fn count_elements(&self) -> PhpResult<Option<i64>> {
+        Ok(None)
+    }
+
+    /// Returns the properties to display when the object is dumped with
+    /// `var_dump()`.
+    ///
+    /// Returns [`None`] to display the properties of the object.
+    fn debug_info(&self) -> Option<ZBox<ZendHashTable>> {
+        None
+    }
+
+    /// Performs an arithmetic or bitwise operation where at least one of the
+    /// operands is an instance of the class. The right-hand side is [`None`]
+    /// for unary operators.
+    ///
+    /// Returns [`None`] to use the default PHP behaviour for the operator.
+    fn do_operation(_op: Operator, _lhs: &Zval, _rhs: Option<&Zval>) -> PhpResult<Option<Zval>> {
+        Ok(None)
+    }
+
+    /// Adds the PHP values held by the Rust value of the object to `gc`, used
+    /// by the cycle collector. Values which are not added are invisible to the
+    /// collector, so reference cycles going through them are never collected.
+    ///
+    /// Only called when [`HAS_GC`] is `true`. The properties of the object are
+    /// always visited.
+    ///
+    /// [`HAS_GC`]: RegisteredClass::HAS_GC
+    fn get_gc(&self, _gc: &mut GcBuffer) {}
 }
 
 /// Stores metadata about a classes Rust constructor, including the function
@@ -170,3 +296,71 @@ impl<T: RegisteredClass> ClassMetadata<T> {
         self.properties.get_or_init(T::get_properties)
     }
 }
+
+/// Stores the class entry of a PHP type which has been exported to PHP and
+/// has no Rust object layout, such as an interface or an enum. Usually
+/// allocated statically.
+pub struct ClassEntryMetadata {
+    ce: AtomicPtr<ClassEntry>,
+}
+
+impl ClassEntryMetadata {
+    /// Creates a new metadata instance.
+    pub const fn new() -> Self {
+        Self {
+            ce: AtomicPtr::new(std::ptr::null_mut()),
+        }
+    }
+
+    /// Checks if the class entry has been stored, returning a boolean.
+    pub fn has_ce(&self) -> bool {
+        !self.ce.load(Ordering::SeqCst).is_null()
+    }
+
+    /// Retrieves a reference to the stored class entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no class entry stored inside the metadata.
+    pub fn ce(&self) -> &'static ClassEntry {
+        // SAFETY: There are only two values that can be stored in the atomic ptr: null
+        // or a static reference to a class entry. On the latter case,
+        // `as_ref()` will return `None` and the function will panic.
+        unsafe { self.ce.load(Ordering::SeqCst).as_ref() }
+            .expect("Attempted to retrieve class entry before it has been stored.")
+    }
+
+    /// Stores a reference to a class entry inside the metadata.
+    ///
+    /// # Parameters
+    ///
+    /// * `ce` - The class entry to store.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the class entry has already been set in the metadata. This
+    /// function should only be called once.
+    pub fn set_ce(&self, ce: &'static mut ClassEntry) {
+        self.ce
+            .compare_exchange(
+                std::ptr::null_mut(),
+                ce,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            )
+            .expect("Class entry has already been set");
+    }
+}
+
+impl Default for ClassEntryMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stores the class entry of an interface which has been exported to PHP.
+/// Usually allocated statically, and is usually done through the
+/// [`macro@php_interface`] macro.
+///
+/// [`macro@php_interface`]: crate::php_interface
+pub type InterfaceMetadata = ClassEntryMetadata;
@@ -1,17 +1,20 @@
 //! Types and functions used for exporting Rust closures to PHP.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, ffi::CString, ptr};
 
 use crate::{
-    args::{Arg, ArgParser},
+    args::{Arg, ArgInfo, ArgParser},
     builders::{ClassBuilder, FunctionBuilder},
     class::{ClassMetadata, RegisteredClass},
-    convert::{FromZval, IntoZval},
+    convert::{FromZval, IntoZendObject, IntoZval},
+    error::{Error, Result},
     exception::PhpException,
+    ffi::zend_create_fake_closure,
     flags::{DataType, MethodFlags},
     props::Property,
-    types::Zval,
-    zend::ExecuteData,
+    rc::ZRc,
+    types::{ZendClassObject, ZendObject, Zval},
+    zend::{ExecuteData, Function, ZendType},
     zend_fastcall,
 };
 
@@ -37,6 +40,11 @@ static CLOSURE_META: ClassMetadata<Closure> = ClassMetadata::new();
 /// }
 /// ```
 ///
+/// When a closure is returned to PHP, it is converted into a real `\Closure`
+/// created from the `__invoke` method of a `RustClosure` object, so it passes
+/// `Closure` and `callable` type checks. The `\Closure` is given the parameter
+/// and return types of the Rust closure, which are visible through reflection.
+///
 /// The Rust closure is then double boxed, firstly as a `Box<dyn Fn(...) ->
 /// ...>` (depending on the signature of the closure) and then finally boxed as
 /// a `Box<dyn PhpClosure>`. This is a workaround, as `PhpClosure` is not
@@ -46,7 +54,12 @@ static CLOSURE_META: ClassMetadata<Closure> = ClassMetadata::new();
 /// When the `__invoke` method is called from PHP, the `invoke` method is called
 /// on the `dyn PhpClosure`\ trait object, and from there everything is
 /// basically the same as a regular PHP function.
-pub struct Closure(Box<dyn PhpClosure>);
+pub struct Closure {
+    func: Box<dyn PhpClosure>,
+    arg_names: Vec<String>,
+    this: Option<Zval>,
+    signature: Option<Signature>,
+}
 
 unsafe impl Send for Closure {}
 unsafe impl Sync for Closure {}
@@ -77,7 +90,12 @@ impl Closure {
     where
         T: PhpClosure + 'static,
     {
-        Self(Box::new(func) as Box<dyn PhpClosure>)
+        Self {
+            func: Box::new(func) as Box<dyn PhpClosure>,
+            arg_names: vec![],
+            this: None,
+            signature: None,
+        }
     }
 
     /// Wraps a [`FnOnce`] Rust closure into a type which can be returned to
@@ -110,6 +128,59 @@ impl Closure {
         func.into_closure()
     }
 
+    /// Sets the names of the parameters of the closure, as seen by PHP through
+    /// reflection and named arguments. Parameters without a name are named
+    /// `$arg0`, `$arg1` and so on.
+    ///
+    /// # Parameters
+    ///
+    /// * `names` - The names of the parameters, without the leading `$`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ext_php_rs::closure::Closure;
+    ///
+    /// let closure = Closure::wrap(Box::new(|name| {
+    ///     format!("Hello {}", name)
+    /// }) as Box<dyn Fn(String) -> String>)
+    /// .with_arg_names(&["name"]);
+    /// ```
+    pub fn with_arg_names(mut self, names: &[&str]) -> Self {
+        self.arg_names = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Binds the closure to an object, similar to `Closure::bind()` in PHP.
+    /// The object is passed as the first argument of the Rust closure each
+    /// time it is called, and is kept alive for as long as the closure. The
+    /// parameter does not appear in the parameters seen by PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `this` - The object to bind the closure to.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ext_php_rs::{closure::Closure, rc::ZRc, types::ZendObject};
+    ///
+    /// let obj = ZendObject::new_stdclass();
+    /// let closure = Closure::wrap(Box::new(|this: ZRc<ZendObject>, suffix: String| {
+    ///     format!("{}{}", this.get_class_name().unwrap_or_default(), suffix)
+    /// }) as Box<dyn Fn(ZRc<ZendObject>, String) -> String>)
+    /// .bind(&obj);
+    /// ```
+    pub fn bind(mut self, this: &ZendObject) -> Self {
+        self.this = ZRc::from_ref(this).into_zval(false).ok();
+        self
+    }
+
+    /// Returns the object the closure is bound to, if any.
+    pub fn bound_this(&self) -> Option<&ZendObject> {
+        self.this.as_ref().and_then(Zval::object)
+    }
+
     /// Builds the class entry for [`Closure`], registering it with PHP. This
     /// function should only be called once inside your module startup
     /// function.
@@ -138,17 +209,73 @@ impl Closure {
         CLOSURE_META.set_ce(ce);
     }
 
+    /// Builds the argument information shown to PHP for the closure.
+    fn build_signature(&self) -> Result<Signature> {
+        let (types, variadic) = match self.func.arg_types() {
+            Some(types) => (types, false),
+            None => (vec![(DataType::Mixed, false)], true),
+        };
+        // The first parameter receives the bound object.
+        let skip = usize::from(self.this.is_some() && !variadic);
+        let types = types.into_iter().skip(skip).collect::<Vec<_>>();
+
+        let names = (0..types.len())
+            .map(|i| match (variadic, self.arg_names.get(i)) {
+                (_, Some(name)) => CString::new(name.as_str()),
+                (true, None) => CString::new("args"),
+                (false, None) => CString::new(format!("arg{}", i)),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let (ret, ret_null) = self.func.return_type();
+        let mut arg_info = Vec::with_capacity(types.len() + 1);
+        arg_info.push(ArgInfo {
+            name: if variadic { 0 } else { types.len() } as *const _,
+            type_: ZendType::runtime_arg_type(ret, false, false, ret_null),
+            default_value: ptr::null(),
+        });
+        arg_info.extend(
+            types
+                .iter()
+                .zip(&names)
+                .map(|(&(type_, allow_null), name)| ArgInfo {
+                    name: name.as_ptr(),
+                    type_: ZendType::runtime_arg_type(type_, false, variadic, allow_null),
+                    default_value: ptr::null(),
+                }),
+        );
+
+        Ok(Signature {
+            arg_info: arg_info.into_boxed_slice(),
+            variadic,
+            _names: names,
+        })
+    }
+
     zend_fastcall! {
         /// External function used by the Zend interpreter to call the closure.
         extern "C" fn invoke(ex: &mut ExecuteData, ret: &mut Zval) {
             let (parser, this) = ex.parser_method::<Self>();
-            let this = this.expect("Internal closure function called on non-closure class");
+            let this: &mut Self = this.expect("Internal closure function called on non-closure class");
 
-            this.0.invoke(parser, ret)
+            let parser = match &mut this.this {
+                Some(obj) => parser.prepend(obj),
+                None => parser,
+            };
+            this.func.invoke(parser, ret)
         }
     }
 }
 
+/// Argument information of a closure converted into a `\Closure`. The
+/// `\Closure` holds a reference to the `RustClosure` object owning the
+/// information, so it is freed once the `\Closure` is destroyed.
+struct Signature {
+    arg_info: Box<[ArgInfo]>,
+    variadic: bool,
+    _names: Vec<CString>,
+}
+
 impl RegisteredClass for Closure {
     const CLASS_NAME: &'static str = "RustClosure";
 
@@ -161,7 +288,84 @@ impl RegisteredClass for Closure {
     }
 }
 
-class_derives!(Closure);
+impl<'a> crate::convert::FromZendObject<'a> for &'a Closure {
+    #[inline]
+    fn from_zend_object(obj: &'a ZendObject) -> Result<Self> {
+        let obj = ZendClassObject::<Closure>::from_zend_obj(obj).ok_or(Error::InvalidScope)?;
+        Ok(&**obj)
+    }
+}
+
+impl<'a> crate::convert::FromZendObjectMut<'a> for &'a mut Closure {
+    #[inline]
+    fn from_zend_object_mut(obj: &'a mut ZendObject) -> Result<Self> {
+        let obj = ZendClassObject::<Closure>::from_zend_obj_mut(obj).ok_or(Error::InvalidScope)?;
+        Ok(&mut **obj)
+    }
+}
+
+impl<'a> FromZval<'a> for &'a Closure {
+    const TYPE: DataType = DataType::Object(Some(Closure::CLASS_NAME));
+
+    #[inline]
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        <Self as crate::convert::FromZendObject>::from_zend_object(zval.object()?).ok()
+    }
+}
+
+impl<'a> crate::convert::FromZvalMut<'a> for &'a mut Closure {
+    const TYPE: DataType = DataType::Object(Some(Closure::CLASS_NAME));
+
+    #[inline]
+    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
+        <Self as crate::convert::FromZendObjectMut>::from_zend_object_mut(zval.object_mut()?).ok()
+    }
+}
+
+impl IntoZendObject for Closure {
+    /// Converts the closure into a `RustClosure` object. Unlike the value
+    /// returned by [`IntoZval`], the object is not an instance of `\Closure`.
+    #[inline]
+    fn into_zend_object(self) -> Result<crate::boxed::ZBox<ZendObject>> {
+        Ok(ZendClassObject::new(self).into())
+    }
+}
+
+impl IntoZval for Closure {
+    const TYPE: DataType = DataType::Object(Some("Closure"));
+
+    fn set_zval(mut self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        let ce = CLOSURE_META.ce();
+        let mut func =
+            Function::try_from_method(Self::CLASS_NAME, "__invoke").ok_or(Error::Callable)?;
+        let signature = self.build_signature()?;
+
+        // The method is copied by PHP into the `\Closure`, so the copy is given the
+        // argument information of the Rust closure.
+        unsafe {
+            let internal = &mut func.internal_function;
+            internal.arg_info = signature.arg_info.as_ptr().add(1) as *mut ArgInfo;
+            internal.num_args = (signature.arg_info.len() - 1) as u32;
+            internal.required_num_args = signature.arg_info[0].name as usize as u32;
+            internal.fn_flags |= MethodFlags::HasReturnType.bits();
+            if signature.variadic {
+                internal.fn_flags |= MethodFlags::Variadic.bits();
+            } else {
+                internal.fn_flags &= !MethodFlags::Variadic.bits();
+            }
+        }
+        self.signature = Some(signature);
+
+        let mut this = Zval::new();
+        self.into_zend_object()?.set_zval(&mut this, persistent)?;
+
+        let ce = ce as *const _ as *mut _;
+        // SAFETY: The `\Closure` holds a reference to the `RustClosure` object, which
+        // owns the argument information pointed to by the copied method.
+        unsafe { zend_create_fake_closure(zv, &mut func, ce, ce, &mut this) };
+        Ok(())
+    }
+}
 
 /// Implemented on types which can be used as PHP closures.
 ///
@@ -175,6 +379,20 @@ class_derives!(Closure);
 pub unsafe trait PhpClosure {
     /// Invokes the closure.
     fn invoke<'a>(&'a mut self, parser: ArgParser<'a, '_>, ret: &mut Zval);
+
+    /// Returns the types of the parameters of the closure and whether they
+    /// accept `null`, as shown to PHP. Returns [`None`] if the parameters are
+    /// not known, in which case the closure is shown as accepting any number
+    /// of arguments.
+    fn arg_types(&self) -> Option<Vec<(DataType, bool)>> {
+        None
+    }
+
+    /// Returns the return type of the closure and whether it can return
+    /// `null`, as shown to PHP.
+    fn return_type(&self) -> (DataType, bool) {
+        (DataType::Mixed, false)
+    }
 }
 
 /// Implemented on [`FnOnce`] types which can be used as PHP closures. See
@@ -198,6 +416,14 @@ where
                 .throw();
         }
     }
+
+    fn arg_types(&self) -> Option<Vec<(DataType, bool)>> {
+        Some(vec![])
+    }
+
+    fn return_type(&self) -> (DataType, bool) {
+        (R::TYPE, R::NULLABLE)
+    }
 }
 
 unsafe impl<R> PhpClosure for Box<dyn FnMut() -> R>
@@ -210,6 +436,14 @@ where
                 .throw();
         }
     }
+
+    fn arg_types(&self) -> Option<Vec<(DataType, bool)>> {
+        Some(vec![])
+    }
+
+    fn return_type(&self) -> (DataType, bool) {
+        (R::TYPE, R::NULLABLE)
+    }
 }
 
 impl<R> PhpOnceClosure for Box<dyn FnOnce() -> R>
@@ -302,6 +536,14 @@ macro_rules! php_closure_impl {
                     let _ = PhpException::default(format!("Failed to return closure result to PHP: {}", e)).throw();
                 }
             }
+
+            fn arg_types(&self) -> Option<Vec<(DataType, bool)>> {
+                Some(vec![$(($gen::TYPE, $gen::NULLABLE)),*])
+            }
+
+            fn return_type(&self) -> (DataType, bool) {
+                (Ret::TYPE, Ret::NULLABLE)
+            }
         }
     };
 }
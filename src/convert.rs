@@ -5,7 +5,7 @@ use crate::{
     error::Result,
     exception::PhpException,
     flags::DataType,
-    types::{ZendObject, Zval},
+    types::{ZendLong, ZendObject, Zval},
 };
 
 /// Allows zvals to be converted into Rust types in a fallible way. Reciprocal
@@ -14,6 +14,14 @@ pub trait FromZval<'a>: Sized {
     /// The corresponding type of the implemented value in PHP.
     const TYPE: DataType;
 
+    /// Whether `null` is a valid value of the type, as is the case for
+    /// [`Option`].
+    const NULLABLE: bool = false;
+
+    /// The range of integers which can be converted into the type, for integer
+    /// types. Used to report integers which are out of range.
+    const RANGE: Option<(ZendLong, ZendLong)> = None;
+
     /// Attempts to retrieve an instance of `Self` from a reference to a
     /// [`Zval`].
     ///
@@ -28,6 +36,8 @@ where
     T: FromZval<'a>,
 {
     const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = true;
+    const RANGE: Option<(ZendLong, ZendLong)> = T::RANGE;
 
     fn from_zval(zval: &'a Zval) -> Option<Self> {
         Some(T::from_zval(zval))
@@ -43,6 +53,20 @@ pub trait FromZvalMut<'a>: Sized {
     /// The corresponding type of the implemented value in PHP.
     const TYPE: DataType;
 
+    /// Whether `null` is a valid value of the type, as is the case for
+    /// [`Option`].
+    const NULLABLE: bool = false;
+
+    /// Whether PHP references are followed before the value is retrieved from
+    /// a function argument. Types which handle references themselves, such as
+    /// [`Ref`](crate::types::Ref), set this to `false` to receive the
+    /// reference.
+    const DEREFERENCE: bool = true;
+
+    /// The range of integers which can be converted into the type, for integer
+    /// types. Used to report integers which are out of range.
+    const RANGE: Option<(ZendLong, ZendLong)> = None;
+
     /// Attempts to retrieve an instance of `Self` from a mutable reference to a
     /// [`Zval`].
     ///
@@ -57,6 +81,8 @@ where
     T: FromZval<'a>,
 {
     const TYPE: DataType = <T as FromZval>::TYPE;
+    const NULLABLE: bool = <T as FromZval>::NULLABLE;
+    const RANGE: Option<(ZendLong, ZendLong)> = <T as FromZval>::RANGE;
 
     #[inline]
     fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
@@ -119,6 +145,10 @@ pub trait IntoZval: Sized {
     /// The corresponding type of the implemented value in PHP.
     const TYPE: DataType;
 
+    /// Whether `null` is a valid value of the type, as is the case for
+    /// [`Option`].
+    const NULLABLE: bool = false;
+
     /// Converts a Rust primitive type into a Zval. Returns a result containing
     /// the Zval if successful.
     ///
@@ -158,6 +188,7 @@ where
     T: IntoZval,
 {
     const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = true;
 
     #[inline]
     fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
@@ -177,6 +208,7 @@ where
     E: Into<PhpException>,
 {
     const TYPE: DataType = T::TYPE;
+    const NULLABLE: bool = T::NULLABLE;
 
     fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
         match self {
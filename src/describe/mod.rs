@@ -38,6 +38,8 @@ pub struct Module {
     pub functions: Vec<Function>,
     pub classes: Vec<Class>,
     pub constants: Vec<Constant>,
+    pub enums: Vec<Enum>,
+    pub interfaces: Vec<Interface>,
 }
 
 /// Represents a set of comments on an export.
@@ -74,6 +76,36 @@ pub struct Class {
     pub constants: Vec<Constant>,
 }
 
+/// Represents an exported interface.
+#[repr(C)]
+pub struct Interface {
+    pub name: Str,
+    pub docs: DocBlock,
+    pub extends: Vec<Str>,
+    pub methods: Vec<Method>,
+    pub constants: Vec<Constant>,
+}
+
+/// Represents an exported enum.
+#[repr(C)]
+pub struct Enum {
+    pub name: Str,
+    pub docs: DocBlock,
+    pub backing: Option<DataType>,
+    pub implements: Vec<Str>,
+    pub cases: Vec<EnumCase>,
+    pub methods: Vec<Method>,
+    pub constants: Vec<Constant>,
+}
+
+/// Represents a case attached to an exported enum.
+#[repr(C)]
+pub struct EnumCase {
+    pub name: Str,
+    pub docs: DocBlock,
+    pub value: Option<Str>,
+}
+
 /// Represents a property attached to an exported class.
 #[repr(C)]
 pub struct Property {
@@ -83,6 +115,7 @@ pub struct Property {
     pub vis: Visibility,
     pub static_: bool,
     pub nullable: bool,
+    pub readonly: bool,
     pub default: Option<Str>,
 }
 
@@ -4,8 +4,8 @@ use crate::flags::DataType;
 use std::{cmp::Ordering, collections::HashMap};
 
 use super::{
-    abi::*, Class, Constant, DocBlock, Function, Method, MethodType, Module, Parameter, Property,
-    Visibility,
+    abi::*, Class, Constant, DocBlock, Enum, EnumCase, Function, Interface, Method, MethodType,
+    Module, Parameter, Property, Visibility,
 };
 use std::fmt::{Error as FmtError, Result as FmtResult, Write};
 use std::{option::Option as StdOption, vec::Vec as StdVec};
@@ -66,11 +66,21 @@ impl ToStub for Module {
             insert(ns, func.to_stub()?);
         }
 
+        for interface in &*self.interfaces {
+            let (ns, _) = split_namespace(interface.name.as_ref());
+            insert(ns, interface.to_stub()?);
+        }
+
         for class in &*self.classes {
             let (ns, _) = split_namespace(class.name.as_ref());
             insert(ns, class.to_stub()?);
         }
 
+        for enum_ in &*self.enums {
+            let (ns, _) = split_namespace(enum_.name.as_ref());
+            insert(ns, enum_.to_stub()?);
+        }
+
         let mut entries: StdVec<_> = entries.iter().collect();
         entries.sort_by(|(l, _), (r, _)| match (l, r) {
             (None, _) => Ordering::Greater,
@@ -235,6 +245,107 @@ impl ToStub for Class {
     }
 }
 
+impl ToStub for Interface {
+    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+        self.docs.fmt_stub(buf)?;
+
+        let (_, name) = split_namespace(self.name.as_ref());
+        write!(buf, "interface {name} ")?;
+
+        if !self.extends.is_empty() {
+            write!(
+                buf,
+                "extends {} ",
+                self.extends
+                    .iter()
+                    .map(|s| s.str())
+                    .collect::<StdVec<_>>()
+                    .join(", ")
+            )?;
+        }
+
+        writeln!(buf, "{{")?;
+
+        let constants = self
+            .constants
+            .iter()
+            .map(|constant| constant.to_stub().map(|stub| indent(&stub, 4)));
+        let methods = self.methods.iter().map(|method| {
+            let mut stub = String::new();
+            method.fmt_signature(&mut stub)?;
+            writeln!(stub, ";")?;
+            Ok(indent(&stub, 4))
+        });
+
+        buf.push_str(
+            &constants
+                .chain(methods)
+                .collect::<Result<StdVec<_>, FmtError>>()?
+                .join(NEW_LINE_SEPARATOR),
+        );
+
+        writeln!(buf, "}}")
+    }
+}
+
+impl ToStub for Enum {
+    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+        self.docs.fmt_stub(buf)?;
+
+        let (_, name) = split_namespace(self.name.as_ref());
+        write!(buf, "enum {name}")?;
+
+        if let Option::Some(backing) = &self.backing {
+            write!(buf, ": ")?;
+            backing.fmt_stub(buf)?;
+        }
+
+        write!(buf, " ")?;
+
+        if !self.implements.is_empty() {
+            write!(
+                buf,
+                "implements {} ",
+                self.implements
+                    .iter()
+                    .map(|s| s.str())
+                    .collect::<StdVec<_>>()
+                    .join(", ")
+            )?;
+        }
+
+        writeln!(buf, "{{")?;
+
+        fn stub<T: ToStub>(items: &[T]) -> impl Iterator<Item = Result<String, FmtError>> + '_ {
+            items
+                .iter()
+                .map(|item| item.to_stub().map(|stub| indent(&stub, 4)))
+        }
+
+        buf.push_str(
+            &stub(&self.cases)
+                .chain(stub(&self.constants))
+                .chain(stub(&self.methods))
+                .collect::<Result<StdVec<_>, FmtError>>()?
+                .join(NEW_LINE_SEPARATOR),
+        );
+
+        writeln!(buf, "}}")
+    }
+}
+
+impl ToStub for EnumCase {
+    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+        self.docs.fmt_stub(buf)?;
+
+        write!(buf, "case {}", self.name)?;
+        if let Option::Some(value) = &self.value {
+            write!(buf, " = {value}")?;
+        }
+        writeln!(buf, ";")
+    }
+}
+
 impl ToStub for Property {
     fn fmt_stub(&self, buf: &mut String) -> FmtResult {
         self.docs.fmt_stub(buf)?;
@@ -245,8 +356,15 @@ impl ToStub for Property {
         if self.static_ {
             write!(buf, "static ")?;
         }
+        if self.readonly {
+            write!(buf, "readonly ")?;
+        }
         if let Option::Some(ty) = &self.ty {
+            if self.nullable && *ty != DataType::Mixed {
+                write!(buf, "?")?;
+            }
             ty.fmt_stub(buf)?;
+            write!(buf, " ")?;
         }
         write!(buf, "${}", self.name)?;
         if let Option::Some(default) = &self.default {
@@ -270,8 +388,9 @@ impl ToStub for Visibility {
     }
 }
 
-impl ToStub for Method {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+impl Method {
+    /// Writes the doc block and signature of the method, without a body.
+    fn fmt_signature(&self, buf: &mut String) -> FmtResult {
         self.docs.fmt_stub(buf)?;
         self.visibility.fmt_stub(buf)?;
 
@@ -302,6 +421,13 @@ impl ToStub for Method {
             }
         }
 
+        Ok(())
+    }
+}
+
+impl ToStub for Method {
+    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+        self.fmt_signature(buf)?;
         writeln!(buf, " {{}}")
     }
 }
@@ -0,0 +1,133 @@
+//! Types and traits used for registering enums with PHP.
+//!
+//! Native enums are only available from PHP 8.1 onwards.
+
+use std::ffi::CString;
+
+use crate::{
+    class::ClassEntryMetadata,
+    error::{Error, Result},
+    ffi::zend_enum_get_case_cstr,
+    flags::DataType,
+    types::{ZendObject, Zval},
+    zend::ClassEntry,
+};
+
+/// Implemented on Rust enums which are exported to PHP as native enums.
+///
+/// Each variant of the Rust enum maps to exactly one case of the PHP enum.
+/// Unlike [`RegisteredClass`], PHP enum cases do not carry a Rust value;
+/// instead the Rust value is recovered from the name of the case.
+///
+/// [`RegisteredClass`]: crate::class::RegisteredClass
+pub trait RegisteredEnum: Sized + 'static {
+    /// PHP class name of the registered enum.
+    const CLASS_NAME: &'static str;
+
+    /// The backing type of the enum. [`None`] for pure enums, otherwise either
+    /// [`DataType::Long`] or [`DataType::String`].
+    const BACKING_TYPE: Option<DataType> = None;
+
+    /// The cases of the enum, in declaration order.
+    const CASES: &'static [EnumCase];
+
+    /// Returns a reference to the enum metadata, which stores the class entry.
+    ///
+    /// This must be statically allocated, and is usually done through the
+    /// [`macro@php_enum`] macro.
+    ///
+    /// [`macro@php_enum`]: crate::php_enum
+    fn get_metadata() -> &'static EnumMetadata;
+
+    /// Returns the name of the PHP case which represents `self`.
+    fn case_name(&self) -> &'static str;
+
+    /// Returns the Rust value which represents the PHP case with the given
+    /// name, or [`None`] if there is no such case.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the case.
+    fn from_case_name(name: &str) -> Option<Self>;
+
+    /// Attempts to retrieve the Rust value of a PHP enum case object.
+    ///
+    /// Returns [`None`] if the object is not a case of this enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The enum case object.
+    fn from_case_object(obj: &ZendObject) -> Option<Self> {
+        if !obj.instance_of(Self::get_metadata().ce()) {
+            return None;
+        }
+
+        let name: String = obj.get_property("name").ok()?;
+        Self::from_case_name(&name)
+    }
+
+    /// Returns the PHP case object which represents `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the case could not be found on the registered enum.
+    fn case_object(&self) -> Result<&'static mut ZendObject> {
+        let name = CString::new(self.case_name())?;
+        let ce = Self::get_metadata().ce();
+
+        // SAFETY: The class entry is a registered enum, and `zend_enum_get_case_cstr`
+        // only reads from the class entry.
+        unsafe {
+            zend_enum_get_case_cstr(ce as *const ClassEntry as *mut ClassEntry, name.as_ptr())
+                .as_mut()
+        }
+        .ok_or(Error::InvalidPointer)
+    }
+}
+
+/// A single case of a PHP enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnumCase {
+    /// Name of the case.
+    pub name: &'static str,
+    /// Backing value of the case, [`None`] for cases of pure enums.
+    pub value: Option<EnumValue>,
+}
+
+/// The backing value of a case of a backed enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumValue {
+    /// Value of a case of an `int` backed enum.
+    Long(i64),
+    /// Value of a case of a `string` backed enum.
+    Str(&'static str),
+}
+
+impl EnumValue {
+    /// Returns the data type of the backing value.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Self::Long(_) => DataType::Long,
+            Self::Str(_) => DataType::String,
+        }
+    }
+
+    /// Converts the backing value into a zval, suitable for use as the value
+    /// of an enum case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string could not be converted into a zval.
+    pub fn to_zval(&self) -> Result<Zval> {
+        let mut zv = Zval::new();
+        match self {
+            Self::Long(val) => zv.set_long(*val),
+            Self::Str(val) => zv.set_interned_string(val, true)?,
+        }
+        Ok(zv)
+    }
+}
+
+/// Stores the class entry of a Rust enum which has been exported to PHP.
+/// Usually allocated statically.
+pub type EnumMetadata = ClassEntryMetadata;
@@ -69,6 +69,34 @@ pub enum Error {
     StreamWrapperRegistrationFailure,
     /// A failure occurred while unregistering the stream wrapper
     StreamWrapperUnregistrationFailure,
+    /// An enum was given a backing type other than `int` or `string`.
+    ///
+    /// The enum carries the data type that was given.
+    InvalidEnumBacking(DataType),
+    /// The value of an enum case does not match the backing type of the enum.
+    ///
+    /// The enum carries the name of the case and the backing type of the enum,
+    /// which is [`None`] for pure enums.
+    InvalidEnumCase(String, Option<DataType>),
+    /// A failure occurred while registering a resource type.
+    ResourceRegistrationFailure,
+    /// A value could not be shared between requests, as it contains a value
+    /// other than a scalar, a persistent string or a persistent array.
+    InvalidPersistentValue,
+    /// PHP is unwinding the stack, because `exit()` was called or because a
+    /// fiber is being destroyed. The pending exit is left in place and
+    /// continues once control returns to PHP, so the Rust code should return
+    /// as soon as possible.
+    Unwinding,
+    /// A positional argument was given after a named argument when calling a
+    /// PHP function.
+    PositionalAfterNamed,
+    /// The same named argument was given more than once when calling a PHP
+    /// function.
+    DuplicateNamedArgument(String),
+    /// The default value of the given argument could not be written as, or
+    /// evaluated from, a PHP expression.
+    InvalidDefaultValue(String),
 }
 
 impl Display for Error {
@@ -112,6 +140,34 @@ impl Display for Error {
                     "A failure occurred while unregistering the stream wrapper"
                 )
             }
+            Error::InvalidEnumBacking(ty) => write!(
+                f,
+                "Enums can only be backed by `int` or `string`, `{ty}` was given."
+            ),
+            Error::InvalidEnumCase(name, Some(ty)) => write!(
+                f,
+                "Enum case `{name}` must have a value of type `{ty}`."
+            ),
+            Error::InvalidEnumCase(name, None) => {
+                write!(f, "Enum case `{name}` of a pure enum cannot have a value.")
+            }
+            Error::ResourceRegistrationFailure => {
+                write!(f, "A failure occurred while registering the resource type")
+            }
+            Error::InvalidPersistentValue => write!(
+                f,
+                "Only scalars, persistent strings and persistent arrays can be shared between requests."
+            ),
+            Error::Unwinding => write!(f, "PHP is unwinding the stack."),
+            Error::PositionalAfterNamed => {
+                write!(f, "Cannot use a positional argument after a named argument.")
+            }
+            Error::DuplicateNamedArgument(name) => {
+                write!(f, "Named argument `{name}` was given more than once.")
+            }
+            Error::InvalidDefaultValue(name) => {
+                write!(f, "Invalid default value for argument `{name}`.")
+            }
         }
     }
 }
@@ -3,7 +3,10 @@
 #![allow(clippy::all)]
 #![allow(warnings)]
 
-use std::{ffi::c_void, os::raw::c_char};
+use std::{
+    ffi::c_void,
+    os::raw::{c_char, c_int},
+};
 
 pub const ZEND_MM_ALIGNMENT: u32 = 8;
 pub const ZEND_MM_ALIGNMENT_MASK: i32 = -8;
@@ -21,6 +24,35 @@ extern "C" {
     pub fn ext_php_rs_zend_string_release(zs: *mut zend_string);
     pub fn ext_php_rs_is_known_valid_utf8(zs: *const zend_string) -> bool;
     pub fn ext_php_rs_set_known_valid_utf8(zs: *mut zend_string);
+    pub fn ext_php_rs_zend_string_forget_hash_val(zs: *mut zend_string);
+    pub fn ext_php_rs_zend_new_reference(val: *mut zval) -> *mut zend_reference;
+    pub fn ext_php_rs_zend_string_alloc(len: usize, persistent: bool) -> *mut zend_string;
+    pub fn ext_php_rs_smart_str_alloc(str_: *mut smart_str, len: usize, persistent: bool) -> usize;
+    pub fn ext_php_rs_smart_str_append(
+        str_: *mut smart_str,
+        val: *const c_char,
+        len: usize,
+        persistent: bool,
+    );
+    pub fn ext_php_rs_smart_str_append_long(str_: *mut smart_str, num: zend_long, persistent: bool);
+    pub fn ext_php_rs_smart_str_extract(str_: *mut smart_str) -> *mut zend_string;
+    pub fn ext_php_rs_smart_str_free(str_: *mut smart_str, persistent: bool);
+    pub fn ext_php_rs_pemalloc(size: usize, persistent: bool) -> *mut c_void;
+    pub fn ext_php_rs_pefree(ptr: *mut c_void, persistent: bool);
+    pub fn ext_php_rs_zend_new_persistent_array(size: u32) -> *mut HashTable;
+    pub fn ext_php_rs_persistent_array_free(ht: *mut HashTable);
+    pub fn ext_php_rs_persistent_zval_dtor(zv: *mut zval);
+    pub fn ext_php_rs_persistent_freeze(zv: *mut zval) -> bool;
+    pub fn ext_php_rs_zend_hash_renumber(ht: *mut HashTable);
+    pub fn ext_php_rs_zend_mm_heap(during_request: bool) -> *mut c_void;
+    pub fn ext_php_rs_zend_parse_arg_scalar(arg: *mut zval, type_: u32, arg_num: u32) -> bool;
+    pub fn ext_php_rs_gc_buffer_add_zval(buf: *mut zend_get_gc_buffer, zv: *mut zval);
+    pub fn ext_php_rs_gc_buffer_add_obj(buf: *mut zend_get_gc_buffer, obj: *mut zend_object);
+    pub fn ext_php_rs_gc_buffer_use(
+        buf: *mut zend_get_gc_buffer,
+        table: *mut *mut zval,
+        n: *mut c_int,
+    );
 
     pub fn ext_php_rs_php_build_id() -> *const c_char;
     pub fn ext_php_rs_zend_object_alloc(obj_size: usize, ce: *mut zend_class_entry) -> *mut c_void;
@@ -0,0 +1,489 @@
+//! Types and functions used to create, suspend and resume PHP fibers from
+//! Rust, and to run Rust futures inside of fibers.
+//!
+//! PHP fibers have their own stack, which includes the Rust frames of the
+//! functions called inside of the fiber. Rust code running inside of a fiber
+//! can therefore suspend it with [`Fiber::suspend`], and carry on where it left
+//! off when the fiber is resumed.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    future::Future,
+    sync::{Arc, Condvar, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+use crate::{
+    convert::{FromZval, IntoZval, IntoZvalDyn},
+    error::{Error, Result},
+    ffi::{zend_is_graceful_exit, zend_is_unwind_exit},
+    flags::DataType,
+    rc::ZRc,
+    types::{ZendObject, Zval},
+    zend::{ce, ExecutorGlobals, Function},
+};
+
+/// A PHP `Fiber`.
+///
+/// Fibers can be created from Rust closures with [`Fiber::new`], or received
+/// from PHP. The methods of the handle call the methods of the same name on the
+/// PHP object. Exceptions thrown inside of the fiber are returned in
+/// [`Error::Exception`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::fiber::Fiber;
+///
+/// let mut fiber = Fiber::new(|| {
+///     let sent = Fiber::suspend("ready").unwrap();
+///     sent.long().unwrap_or_default() * 2
+/// })
+/// .unwrap();
+///
+/// assert_eq!(fiber.start().unwrap().and_then(|v| v.string()), Some("ready".into()));
+/// assert!(fiber.resume(21).unwrap().is_none());
+/// assert_eq!(fiber.return_value().unwrap().long(), Some(42));
+/// ```
+#[derive(Clone)]
+pub struct Fiber {
+    obj: ZRc<ZendObject>,
+}
+
+impl Fiber {
+    /// Creates a new fiber running the given closure. The fiber does not run
+    /// until it is [started](Fiber::start).
+    ///
+    /// # Parameters
+    ///
+    /// * `func` - The closure to run inside of the fiber. Its return value is
+    ///   returned by [`return_value`](Fiber::return_value).
+    #[cfg(any(docs, feature = "closure"))]
+    #[cfg_attr(docs, doc(cfg(feature = "closure")))]
+    pub fn new<F, R>(func: F) -> Result<Self>
+    where
+        F: FnOnce() -> R + 'static,
+        R: IntoZval + 'static,
+    {
+        let func = crate::closure::Closure::wrap_once(Box::new(func) as Box<dyn FnOnce() -> R>)
+            .into_zval(false)?;
+        let obj = ZendObject::new(ce::fiber());
+        call_method(&obj, "__construct", vec![&func])?;
+        Ok(Self { obj: obj.into() })
+    }
+
+    /// Returns a handle to the given object if it is a `Fiber`.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The object to wrap.
+    pub fn from_object(obj: &ZendObject) -> Option<Self> {
+        if obj.instance_of(ce::fiber()) {
+            Some(Self {
+                obj: ZRc::from_ref(obj),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the fiber the code is currently running in, if any.
+    pub fn current() -> Option<Self> {
+        let current = call_static("getCurrent", vec![]).ok()?;
+        Self::from_object(current.object()?)
+    }
+
+    /// Suspends the fiber the code is currently running in, returning
+    /// `value` from the call to [`start`](Fiber::start) or
+    /// [`resume`](Fiber::resume) which ran the fiber. Returns the value the
+    /// fiber is resumed with.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value to hand over to the code resuming the fiber.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the code is not running inside of a fiber, or the
+    /// exception thrown into the fiber with `Fiber::throw()`. If the fiber is
+    /// destroyed while suspended, [`Error::Unwinding`] is returned and the
+    /// caller must return without suspending again.
+    pub fn suspend(value: impl IntoZval) -> Result<Zval> {
+        let value = value.into_zval(false)?;
+        call_static("suspend", vec![&value])
+    }
+
+    /// Starts the fiber, running it until it suspends or returns.
+    ///
+    /// Returns the value the fiber was suspended with, or [`None`] if the
+    /// fiber has returned.
+    pub fn start(&mut self) -> Result<Option<Zval>> {
+        let value = call_method(&self.obj, "start", vec![])?;
+        Ok(self.suspended_value(value))
+    }
+
+    /// Resumes the suspended fiber, returning `value` from the call to
+    /// [`Fiber::suspend`] which suspended it.
+    ///
+    /// Returns the value the fiber was suspended with next, or [`None`] if
+    /// the fiber has returned.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value to hand over to the fiber.
+    pub fn resume(&mut self, value: impl IntoZval) -> Result<Option<Zval>> {
+        let value = value.into_zval(false)?;
+        let value = call_method(&self.obj, "resume", vec![&value])?;
+        Ok(self.suspended_value(value))
+    }
+
+    /// Returns the value returned by the fiber.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the fiber has not returned yet.
+    pub fn return_value(&self) -> Result<Zval> {
+        call_method(&self.obj, "getReturn", vec![])
+    }
+
+    /// Returns true if the fiber has been started.
+    pub fn is_started(&self) -> bool {
+        self.flag("isStarted")
+    }
+
+    /// Returns true if the fiber is currently suspended.
+    pub fn is_suspended(&self) -> bool {
+        self.flag("isSuspended")
+    }
+
+    /// Returns true if the fiber is currently running, which includes
+    /// fibers which have started another fiber.
+    pub fn is_running(&self) -> bool {
+        self.flag("isRunning")
+    }
+
+    /// Returns true if the fiber has returned or thrown an exception.
+    pub fn is_terminated(&self) -> bool {
+        self.flag("isTerminated")
+    }
+
+    /// Returns the PHP object of the fiber.
+    pub fn object(&self) -> &ZendObject {
+        &self.obj
+    }
+
+    fn flag(&self, method: &str) -> bool {
+        call_method(&self.obj, method, vec![])
+            .ok()
+            .and_then(|value| value.bool())
+            .unwrap_or(false)
+    }
+
+    fn suspended_value(&self, value: Zval) -> Option<Zval> {
+        if self.is_terminated() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+impl Debug for Fiber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Fiber")
+            .field("started", &self.is_started())
+            .field("suspended", &self.is_suspended())
+            .field("terminated", &self.is_terminated())
+            .finish()
+    }
+}
+
+impl IntoZval for Fiber {
+    const TYPE: DataType = DataType::Object(Some("Fiber"));
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        self.obj.set_zval(zv, persistent)
+    }
+}
+
+impl FromZval<'_> for Fiber {
+    const TYPE: DataType = DataType::Object(Some("Fiber"));
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        Self::from_object(zval.object()?)
+    }
+}
+
+/// Calls a method on a fiber object, returning the exception thrown by the
+/// method if any.
+fn call_method(obj: &ZendObject, name: &str, params: Vec<&dyn IntoZvalDyn>) -> Result<Zval> {
+    let value = obj.try_call_method(name, params)?;
+    check_exception()?;
+    Ok(value)
+}
+
+/// Calls a static method of the `Fiber` class, returning the exception thrown
+/// by the method if any.
+fn call_static(name: &str, params: Vec<&dyn IntoZvalDyn>) -> Result<Zval> {
+    let func = Function::try_from_method("Fiber", name).ok_or(Error::Callable)?;
+    let value = func.try_call(params)?;
+    check_exception()?;
+    Ok(value)
+}
+
+/// Takes the pending exception, unless PHP is unwinding the stack, in which
+/// case the exit is left pending.
+fn check_exception() -> Result<()> {
+    let unwinding = {
+        let globals = ExecutorGlobals::get();
+        if globals.exception.is_null() {
+            return Ok(());
+        }
+        // SAFETY: The exception is a valid object.
+        unsafe {
+            zend_is_unwind_exit(globals.exception) || zend_is_graceful_exit(globals.exception)
+        }
+    };
+    if unwinding {
+        return Err(Error::Unwinding);
+    }
+    Err(ExecutorGlobals::take_exception()
+        .map(Error::Exception)
+        .unwrap_or(Error::Callable))
+}
+
+/// Runs a future to completion from Rust code running inside of a fiber,
+/// suspending the fiber while the future is pending.
+///
+/// The future is polled again every time the fiber is resumed, so the code
+/// driving the fiber, such as the event loop of a PHP coroutine framework,
+/// decides when it makes progress. Use [`FiberExecutor`] to resume fibers
+/// when their futures are woken instead.
+///
+/// Outside of a fiber, the current thread is blocked until the future
+/// completes.
+///
+/// # Parameters
+///
+/// * `future` - The future to run.
+///
+/// # Returns
+///
+/// Returns the errors returned by [`Fiber::suspend`].
+pub fn block_on<F: Future>(future: F) -> Result<F::Output> {
+    if Fiber::current().is_some() {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        drive(future, &waker)
+    } else {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut future = Box::pin(future);
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(value) => return Ok(value),
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+}
+
+/// Polls a future inside of the current fiber, suspending the fiber every time
+/// the future is pending.
+fn drive<F: Future>(future: F, waker: &Waker) -> Result<F::Output> {
+    let mut future = Box::pin(future);
+    let mut cx = Context::from_waker(waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(value) => return Ok(value),
+            Poll::Pending => {
+                Fiber::suspend(())?;
+            }
+        }
+    }
+}
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs Rust futures inside of PHP fibers, resuming each fiber when its
+/// future is woken.
+///
+/// Every future is run in its own fiber, so it can call PHP code which
+/// suspends, and PHP code can call Rust code which awaits other futures with
+/// [`block_on`]. Wakers can be called from any thread, for example by a
+/// thread performing I/O.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::fiber::FiberExecutor;
+///
+/// let mut executor = FiberExecutor::new();
+/// let task = executor.spawn(async { 5 }).unwrap();
+/// executor.run().unwrap();
+/// assert_eq!(task.return_value().unwrap().long(), Some(5));
+/// ```
+#[derive(Default)]
+pub struct FiberExecutor {
+    queue: Arc<ReadyQueue>,
+    tasks: HashMap<u64, Fiber>,
+    next_id: u64,
+}
+
+/// Identifiers of the tasks whose futures have been woken.
+#[derive(Default)]
+struct ReadyQueue {
+    ready: Mutex<VecDeque<u64>>,
+    cond: Condvar,
+}
+
+struct TaskWaker {
+    id: u64,
+    queue: Arc<ReadyQueue>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if let Ok(mut ready) = self.queue.ready.lock() {
+            ready.push_back(self.id);
+            self.queue.cond.notify_one();
+        }
+    }
+}
+
+impl FiberExecutor {
+    /// Creates a new executor with no tasks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns a future in a new fiber, which is started immediately and runs
+    /// until the future is first pending.
+    ///
+    /// Returns the fiber running the future. The output of the future is
+    /// returned by [`Fiber::return_value`] once the fiber has terminated.
+    ///
+    /// # Parameters
+    ///
+    /// * `future` - The future to run.
+    #[cfg(any(docs, feature = "closure"))]
+    #[cfg_attr(docs, doc(cfg(feature = "closure")))]
+    pub fn spawn<F>(&mut self, future: F) -> Result<Fiber>
+    where
+        F: Future + 'static,
+        F::Output: IntoZval + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            queue: self.queue.clone(),
+        }));
+
+        let mut fiber = Fiber::new(move || match drive(future, &waker) {
+            Ok(value) => Some(value),
+            Err(Error::Exception(e)) => {
+                // Exceptions thrown into the fiber are thrown out of it again.
+                if let Ok(e) = e.into_zval(false) {
+                    let _ = crate::exception::throw_object(e);
+                }
+                None
+            }
+            Err(_) => None,
+        })?;
+        let result = fiber.start();
+        if !fiber.is_terminated() {
+            self.tasks.insert(id, fiber.clone());
+        }
+        result.map(|_| fiber)
+    }
+
+    /// Resumes the fibers whose futures have been woken, without waiting.
+    ///
+    /// Returns the number of fibers which were resumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the exception thrown out of a fiber, in which case the
+    /// remaining fibers are resumed by the next call.
+    pub fn tick(&mut self) -> Result<usize> {
+        let ready: Vec<u64> = match self.queue.ready.lock() {
+            Ok(mut ready) => ready.drain(..).collect(),
+            Err(_) => return Ok(0),
+        };
+        let mut resumed = 0;
+        for (i, id) in ready.iter().enumerate() {
+            if let Err(e) = self.resume(*id, &mut resumed) {
+                if let Ok(mut queue) = self.queue.ready.lock() {
+                    queue.extend(&ready[i + 1..]);
+                }
+                return Err(e);
+            }
+        }
+        Ok(resumed)
+    }
+
+    /// Runs the fibers until all of them have terminated, blocking the
+    /// current thread while no future has been woken.
+    ///
+    /// Futures which are never woken block this function forever.
+    ///
+    /// # Returns
+    ///
+    /// Returns the exception thrown out of a fiber.
+    pub fn run(&mut self) -> Result<()> {
+        while !self.tasks.is_empty() {
+            if let Ok(mut ready) = self.queue.ready.lock() {
+                while ready.is_empty() {
+                    ready = match self.queue.cond.wait(ready) {
+                        Ok(ready) => ready,
+                        Err(_) => return Ok(()),
+                    };
+                }
+            }
+            self.tick()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of fibers which have not terminated.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns true if every fiber has terminated.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    fn resume(&mut self, id: u64, resumed: &mut usize) -> Result<()> {
+        let fiber = match self.tasks.get_mut(&id) {
+            Some(fiber) if fiber.is_suspended() => fiber,
+            // Tasks may be woken more than once before they are resumed.
+            _ => return Ok(()),
+        };
+        *resumed += 1;
+        let result = fiber.resume(());
+        if fiber.is_terminated() {
+            self.tasks.remove(&id);
+        }
+        result.map(|_| ())
+    }
+}
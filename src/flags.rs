@@ -21,9 +21,10 @@ use crate::ffi::{
     ZEND_ACC_PROMOTED, ZEND_ACC_PROTECTED, ZEND_ACC_PUBLIC, ZEND_ACC_RESOLVED_INTERFACES,
     ZEND_ACC_RESOLVED_PARENT, ZEND_ACC_RETURN_REFERENCE, ZEND_ACC_STATIC, ZEND_ACC_STRICT_TYPES,
     ZEND_ACC_TOP_LEVEL, ZEND_ACC_TRAIT, ZEND_ACC_TRAIT_CLONE, ZEND_ACC_UNRESOLVED_VARIANCE,
-    ZEND_ACC_USES_THIS, ZEND_ACC_USE_GUARDS, ZEND_ACC_VARIADIC, ZEND_EVAL_CODE,
-    ZEND_HAS_STATIC_IN_METHODS, ZEND_INTERNAL_FUNCTION, ZEND_USER_FUNCTION, Z_TYPE_FLAGS_SHIFT,
-    _IS_BOOL,
+    ZEND_ACC_USES_THIS, ZEND_ACC_USE_GUARDS, ZEND_ACC_VARIADIC, ZEND_ADD, ZEND_BW_AND, ZEND_BW_NOT,
+    ZEND_BW_OR, ZEND_BW_XOR, ZEND_CONCAT, ZEND_DIV, ZEND_EVAL_CODE, ZEND_HAS_STATIC_IN_METHODS,
+    ZEND_INTERNAL_FUNCTION, ZEND_MOD, ZEND_MUL, ZEND_POW, ZEND_SL, ZEND_SR, ZEND_SUB,
+    ZEND_USER_FUNCTION, Z_TYPE_FLAGS_SHIFT, _IS_BOOL,
 };
 
 use std::{convert::TryFrom, fmt::Display};
@@ -93,6 +94,8 @@ bitflags! {
 
         #[cfg(any(php81,php82))]
         const NotSerializable = crate::ffi::ZEND_ACC_NOT_SERIALIZABLE;
+        #[cfg(any(php81,php82))]
+        const Enum = crate::ffi::ZEND_ACC_ENUM;
     }
 }
 
@@ -142,6 +145,8 @@ bitflags! {
         const Changed = ZEND_ACC_CHANGED;
         const Static = ZEND_ACC_STATIC;
         const Promoted = ZEND_ACC_PROMOTED;
+        #[cfg(any(php81,php82))]
+        const Readonly = crate::ffi::ZEND_ACC_READONLY;
     }
 }
 
@@ -226,6 +231,60 @@ impl From<u8> for FunctionType {
     }
 }
 
+/// Arithmetic and bitwise operators which can be overloaded by objects.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum Operator {
+    /// `$a + $b`
+    Add,
+    /// `$a - $b`
+    Sub,
+    /// `$a * $b`
+    Mul,
+    /// `$a / $b`
+    Div,
+    /// `$a % $b`
+    Mod,
+    /// `$a << $b`
+    ShiftLeft,
+    /// `$a >> $b`
+    ShiftRight,
+    /// `$a . $b`
+    Concat,
+    /// `$a | $b`
+    BitwiseOr,
+    /// `$a & $b`
+    BitwiseAnd,
+    /// `$a ^ $b`
+    BitwiseXor,
+    /// `$a ** $b`
+    Pow,
+    /// `~$a`
+    BitwiseNot,
+}
+
+impl Operator {
+    /// Returns the operator represented by a Zend VM opcode, or [`None`] if
+    /// the opcode is not an overloadable operator.
+    pub fn from_opcode(opcode: u8) -> Option<Self> {
+        Some(match opcode as u32 {
+            ZEND_ADD => Self::Add,
+            ZEND_SUB => Self::Sub,
+            ZEND_MUL => Self::Mul,
+            ZEND_DIV => Self::Div,
+            ZEND_MOD => Self::Mod,
+            ZEND_SL => Self::ShiftLeft,
+            ZEND_SR => Self::ShiftRight,
+            ZEND_CONCAT => Self::Concat,
+            ZEND_BW_OR => Self::BitwiseOr,
+            ZEND_BW_AND => Self::BitwiseAnd,
+            ZEND_BW_XOR => Self::BitwiseXor,
+            ZEND_POW => Self::Pow,
+            ZEND_BW_NOT => Self::BitwiseNot,
+            _ => return None,
+        })
+    }
+}
+
 /// Valid data types for PHP.
 #[repr(C, u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
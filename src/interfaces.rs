@@ -0,0 +1,583 @@
+//! Implementations of PHP's built-in interfaces for Rust classes.
+//!
+//! Classes exported with the [`php_class`] macro can implement `Countable`,
+//! `ArrayAccess`, `IteratorAggregate` and `Stringable` through the
+//! `#[countable]`, `#[array_access]`, `#[iterable]` and `#[stringable]`
+//! attributes. The methods required by the interfaces are implemented natively,
+//! and the object handlers of the class call into Rust directly instead of
+//! dispatching through the PHP methods.
+//!
+//! [`php_class`]: crate::php_class
+
+use std::{
+    alloc::Layout,
+    convert::TryFrom,
+    fmt::Display,
+    ops::{Index, IndexMut},
+    os::raw::c_int,
+    ptr,
+};
+
+use crate::{
+    alloc::emalloc,
+    args::Arg,
+    builders::{ClassBuilder, FunctionBuilder},
+    class::RegisteredClass,
+    convert::{FromZval, IntoZval},
+    exception::{PhpException, PhpResult},
+    ffi::{
+        zend_create_internal_iterator_zval, zend_is_true, zend_iterator_init,
+        zend_object_iterator, zend_object_iterator_funcs, ZEND_RESULT_CODE_FAILURE,
+        ZEND_RESULT_CODE_SUCCESS,
+    },
+    flags::{DataType, MethodFlags},
+    types::{ClassRef, ClassRefMut, ZendClassObject, Zval},
+    zend::{ce, ClassEntry, ExecuteData, FunctionEntry},
+    zend_fastcall,
+};
+
+/// Implemented on Rust classes which can be counted with `count()`,
+/// implementing PHP's `Countable` interface.
+///
+/// The `#[countable]` attribute implements this trait using the `len()` method
+/// of the class.
+pub trait Countable: RegisteredClass {
+    /// Returns the number of elements in the object.
+    fn count(&self) -> usize;
+}
+
+/// Implemented on Rust classes which can be accessed like arrays with integer
+/// offsets, implementing PHP's `ArrayAccess` interface when the class is
+/// annotated with the `#[array_access]` attribute.
+///
+/// This is implemented automatically on classes implementing [`Countable`],
+/// [`Index<usize>`] and [`IndexMut<usize>`], as well as [`Extend`] to append
+/// values with `$obj[] = $value`. Offsets from `0` up to the number of elements
+/// exist, and reading any other offset returns `null`. Values cannot be unset.
+pub trait ArrayAccess:
+    Countable + Index<usize, Output = Self::Value> + IndexMut<usize> + Extend<Self::Value>
+{
+    /// Type of the values contained in the object.
+    type Value: Clone + IntoZval + for<'a> FromZval<'a>;
+}
+
+impl<T, V> ArrayAccess for T
+where
+    T: Countable + Index<usize, Output = V> + IndexMut<usize> + Extend<V>,
+    V: Clone + IntoZval + for<'a> FromZval<'a>,
+{
+    type Value = V;
+}
+
+impl ClassBuilder {
+    /// Implements the `Countable` interface on the class using the
+    /// [`Countable`] implementation of `T`.
+    pub fn countable<T: Countable>(self) -> Self {
+        zend_fastcall! {
+            extern fn count<T: Countable>(ex: &mut ExecuteData, retval: &mut Zval) {
+                if let Some(this) = parse_method::<T>(ex, &mut []) {
+                    retval.set_long(this.count() as i64);
+                }
+            }
+        }
+
+        self.implements(ce::countable()).method(
+            FunctionBuilder::new("count", count::<T>)
+                .returns(DataType::Long, false, false)
+                .build()
+                .expect("Failed to build `count` method"),
+            MethodFlags::Public,
+        )
+    }
+
+    /// Implements the `ArrayAccess` interface on the class using the
+    /// [`ArrayAccess`] implementation of `T`.
+    pub fn array_access<T: ArrayAccess>(self) -> Self {
+        zend_fastcall! {
+            extern fn offset_exists<T: ArrayAccess>(ex: &mut ExecuteData, retval: &mut Zval) {
+                let mut args = [Arg::new("offset", DataType::Mixed)];
+                if let Some(this) = parse_method::<T>(ex, &mut args) {
+                    let result = arg_zval(&mut args[0])
+                        .and_then(|offset| has_dimension(&*this, offset, false));
+                    match result {
+                        Ok(exists) => retval.set_bool(exists),
+                        Err(e) => throw(e),
+                    }
+                }
+            }
+        }
+
+        zend_fastcall! {
+            extern fn offset_get<T: ArrayAccess>(ex: &mut ExecuteData, retval: &mut Zval) {
+                let mut args = [Arg::new("offset", DataType::Mixed)];
+                if let Some(this) = parse_method::<T>(ex, &mut args) {
+                    let result = arg_zval(&mut args[0])
+                        .and_then(|offset| read_dimension(&*this, offset));
+                    match result {
+                        Ok(value) => *retval = value,
+                        Err(e) => throw(e),
+                    }
+                }
+            }
+        }
+
+        zend_fastcall! {
+            extern fn offset_set<T: ArrayAccess>(ex: &mut ExecuteData, _: &mut Zval) {
+                let mut args = [
+                    Arg::new("offset", DataType::Mixed),
+                    Arg::new("value", DataType::Mixed),
+                ];
+                if let Some(mut this) = parse_method_mut::<T>(ex, &mut args) {
+                    let [offset, value] = &mut args;
+                    let result = arg_zval(offset).and_then(|offset| {
+                        let offset = Some(offset).filter(|offset| !offset.is_null());
+                        write_dimension(&mut *this, offset, arg_zval(value)?)
+                    });
+                    if let Err(e) = result {
+                        throw(e);
+                    }
+                }
+            }
+        }
+
+        zend_fastcall! {
+            extern fn offset_unset<T: ArrayAccess>(ex: &mut ExecuteData, _: &mut Zval) {
+                let mut args = [Arg::new("offset", DataType::Mixed)];
+                if let Some(mut this) = parse_method_mut::<T>(ex, &mut args) {
+                    if let Err(e) = arg_zval(&mut args[0]).and_then(|offset| unset_dimension(&mut *this, offset)) {
+                        throw(e);
+                    }
+                }
+            }
+        }
+
+        self.implements(ce::arrayaccess())
+            .method(
+                build_method(
+                    FunctionBuilder::new("offsetExists", offset_exists::<T>),
+                    &["offset"],
+                    DataType::Bool,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                build_method(
+                    FunctionBuilder::new("offsetGet", offset_get::<T>),
+                    &["offset"],
+                    DataType::Mixed,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                build_method(
+                    FunctionBuilder::new("offsetSet", offset_set::<T>),
+                    &["offset", "value"],
+                    DataType::Void,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                build_method(
+                    FunctionBuilder::new("offsetUnset", offset_unset::<T>),
+                    &["offset"],
+                    DataType::Void,
+                ),
+                MethodFlags::Public,
+            )
+    }
+
+    /// Implements the `IteratorAggregate` interface on the class, iterating
+    /// over the items returned by the [`IntoIterator`] implementation of `&T`.
+    ///
+    /// Iterating over the object in `foreach` does not call into PHP, each
+    /// item is taken from the Rust iterator as the loop advances and passed
+    /// directly to the loop. The object is borrowed until the loop finishes,
+    /// so modifying it inside of the loop throws an `Error`.
+    pub fn iterable<T>(self) -> Self
+    where
+        T: RegisteredClass,
+        for<'a> &'a T: IntoIterator,
+        for<'a> <&'a T as IntoIterator>::Item: IntoZval,
+    {
+        zend_fastcall! {
+            extern fn get_iterator_method(ex: &mut ExecuteData, retval: &mut Zval) {
+                // SAFETY: `retval` and `This` are valid zvals, and the class of `This` has
+                // a native iterator.
+                unsafe { zend_create_internal_iterator_zval(retval, &mut ex.This) };
+            }
+        }
+
+        let mut builder = self.implements(ce::aggregate()).method(
+            FunctionBuilder::new("getIterator", get_iterator_method)
+                .returns(DataType::Object(Some("Iterator")), false, false)
+                .build()
+                .expect("Failed to build `getIterator` method"),
+            MethodFlags::Public,
+        );
+        builder.get_iterator = Some(ClassIterator::get_iterator::<T>);
+        builder
+    }
+
+    /// Implements the `Stringable` interface on the class, converting the
+    /// object to a string using the [`Display`] implementation of `T`.
+    pub fn stringable<T: RegisteredClass + Display>(self) -> Self {
+        zend_fastcall! {
+            extern fn to_string<T: RegisteredClass + Display>(ex: &mut ExecuteData, retval: &mut Zval) {
+                if let Some(this) = parse_method::<T>(ex, &mut []) {
+                    if let Err(e) = retval.set_string(&this.to_string(), false) {
+                        throw(format!("Failed to convert object to string: {}", e).into());
+                    }
+                }
+            }
+        }
+
+        self.implements(ce::stringable()).method(
+            FunctionBuilder::new("__toString", to_string::<T>)
+                .returns(DataType::String, false, false)
+                .build()
+                .expect("Failed to build `__toString` method"),
+            MethodFlags::Public,
+        )
+    }
+}
+
+/// Implementation of the `read_dimension` object handler for a class
+/// implementing [`ArrayAccess`].
+#[doc(hidden)]
+pub fn read_dimension<T: ArrayAccess>(this: &T, offset: &Zval) -> PhpResult<Zval> {
+    match position(this, offset)? {
+        Some(pos) => this[pos]
+            .clone()
+            .into_zval(false)
+            .map_err(|e| format!("Failed to convert value: {}", e).into()),
+        None => Ok(Zval::new()),
+    }
+}
+
+/// Implementation of the `write_dimension` object handler for a class
+/// implementing [`ArrayAccess`].
+#[doc(hidden)]
+pub fn write_dimension<T: ArrayAccess>(
+    this: &mut T,
+    offset: Option<&Zval>,
+    value: &Zval,
+) -> PhpResult<()> {
+    let pos = match offset {
+        Some(offset) => Some(position(this, offset)?.ok_or_else(|| {
+            PhpException::new(
+                format!(
+                    "Offset {} is out of range for {}",
+                    offset.long().unwrap_or_default(),
+                    T::CLASS_NAME
+                ),
+                0,
+                ce::error(),
+            )
+        })?),
+        None => None,
+    };
+    let value = T::Value::from_zval(value).ok_or_else(|| {
+        PhpException::new(
+            format!(
+                "Cannot assign {} to an element of {}",
+                value.get_type(),
+                T::CLASS_NAME
+            ),
+            0,
+            ce::type_error(),
+        )
+    })?;
+    match pos {
+        Some(pos) => this[pos] = value,
+        None => this.extend(Some(value)),
+    }
+    Ok(())
+}
+
+/// Implementation of the `has_dimension` object handler for a class
+/// implementing [`ArrayAccess`].
+#[doc(hidden)]
+pub fn has_dimension<T: ArrayAccess>(
+    this: &T,
+    offset: &Zval,
+    check_empty: bool,
+) -> PhpResult<bool> {
+    if position(this, offset)?.is_none() {
+        return Ok(false);
+    }
+    if !check_empty {
+        return Ok(true);
+    }
+
+    let mut value = read_dimension(this, offset)?;
+    cfg_if::cfg_if! {
+        if #[cfg(php84)] {
+            #[allow(clippy::unnecessary_mut_passed)]
+            Ok(unsafe { zend_is_true(&mut value) })
+        } else {
+            #[allow(clippy::unnecessary_mut_passed)]
+            Ok(unsafe { zend_is_true(&mut value) } == 1)
+        }
+    }
+}
+
+/// Implementation of the `unset_dimension` object handler for a class
+/// implementing [`ArrayAccess`].
+#[doc(hidden)]
+pub fn unset_dimension<T: ArrayAccess>(_: &mut T, _: &Zval) -> PhpResult<()> {
+    Err(PhpException::new(
+        format!("Cannot unset offsets of {}", T::CLASS_NAME),
+        0,
+        ce::error(),
+    ))
+}
+
+/// Returns the position referred to by an offset, or [`None`] if the offset
+/// is out of range.
+fn position<T: ArrayAccess>(this: &T, offset: &Zval) -> PhpResult<Option<usize>> {
+    let offset = offset.long().ok_or_else(|| {
+        PhpException::new(
+            format!(
+                "Cannot access offset of type {} on {}",
+                offset.get_type(),
+                T::CLASS_NAME
+            ),
+            0,
+            ce::type_error(),
+        )
+    })?;
+    Ok(usize::try_from(offset)
+        .ok()
+        .filter(|offset| *offset < this.count()))
+}
+
+/// Builds a native method taking `mixed` arguments.
+fn build_method(func: FunctionBuilder<'_>, args: &[&str], ret: DataType) -> FunctionEntry {
+    args.iter()
+        .fold(func, |func, arg| func.arg(Arg::new(*arg, DataType::Mixed)))
+        .returns(ret, false, false)
+        .build()
+        .expect("Failed to build interface method")
+}
+
+/// Parses the arguments of a native method, returning a borrow of the object
+/// the method was called on. Returns [`None`] if the arguments were invalid, the
+/// object has not been initialized or the object is being modified, in which
+/// case an exception has been thrown.
+pub(crate) fn parse_method<'a, T: RegisteredClass>(
+    ex: &'a mut ExecuteData,
+    args: &mut [Arg<'a>],
+) -> Option<ClassRef<'a, T>> {
+    borrowed(parse_this::<T>(ex, args)?.try_borrow())
+}
+
+/// Parses the arguments of a native method, returning a mutable borrow of the
+/// object the method was called on. See [`parse_method`].
+pub(crate) fn parse_method_mut<'a, T: RegisteredClass>(
+    ex: &'a mut ExecuteData,
+    args: &mut [Arg<'a>],
+) -> Option<ClassRefMut<'a, T>> {
+    borrowed(parse_this::<T>(ex, args)?.try_borrow_mut())
+}
+
+fn parse_this<'a, T: RegisteredClass>(
+    ex: &'a mut ExecuteData,
+    args: &mut [Arg<'a>],
+) -> Option<&'a mut ZendClassObject<T>> {
+    let (parser, this) = ex.parser_method::<T>();
+    let parser = args.iter_mut().fold(parser, |parser, arg| parser.arg(arg));
+    parser.parse().ok()?;
+    if this.is_none() {
+        throw(PhpException::default(
+            "Failed to retrieve reference to `$this`".into(),
+        ));
+    }
+    this
+}
+
+fn borrowed<B>(borrow: PhpResult<B>) -> Option<B> {
+    borrow.map_err(throw).ok()
+}
+
+/// Returns the value of a parsed argument.
+fn arg_zval<'a>(arg: &'a mut Arg<'_>) -> PhpResult<&'a Zval> {
+    arg.zval()
+        .map(|zv| zv.dereference())
+        .ok_or_else(|| "Missing argument".into())
+}
+
+pub(crate) fn throw(e: PhpException) {
+    let _ = e.throw();
+}
+
+/// Iterator over the items of a Rust class, converted into zvals.
+type Items = Box<dyn Iterator<Item = PhpResult<Zval>>>;
+
+/// Native iterator over the items of a Rust class.
+#[repr(C)]
+struct ClassIterator {
+    it: zend_object_iterator,
+    /// Starts iterating over the items of the object contained in a zval.
+    start: unsafe fn(&Zval) -> PhpResult<Items>,
+    /// The Rust iterator, or [`None`] once it has been exhausted.
+    items: Option<Items>,
+    current: Option<Zval>,
+    pos: i64,
+}
+
+/// Items of a Rust class, holding a borrow of the object for as long as the
+/// Rust iterator is alive.
+struct BorrowedItems<T: 'static, I> {
+    iter: I,
+    _this: ClassRef<'static, T>,
+}
+
+impl<T, I> Iterator for BorrowedItems<T, I>
+where
+    I: Iterator,
+    I::Item: IntoZval,
+{
+    type Item = PhpResult<Zval>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|item| {
+            item.into_zval(false)
+                .map_err(|e| format!("Failed to convert item: {}", e).into())
+        })
+    }
+}
+
+static CLASS_ITERATOR_FUNCS: zend_object_iterator_funcs = zend_object_iterator_funcs {
+    dtor: Some(ClassIterator::dtor),
+    valid: Some(ClassIterator::valid),
+    get_current_data: Some(ClassIterator::get_current_data),
+    get_current_key: Some(ClassIterator::get_current_key),
+    move_forward: Some(ClassIterator::move_forward),
+    rewind: Some(ClassIterator::rewind),
+    invalidate_current: None,
+    get_gc: None,
+};
+
+impl ClassIterator {
+    unsafe extern "C" fn get_iterator<T>(
+        _: *mut ClassEntry,
+        object: *mut Zval,
+        by_ref: c_int,
+    ) -> *mut zend_object_iterator
+    where
+        T: RegisteredClass,
+        for<'a> &'a T: IntoIterator,
+        for<'a> <&'a T as IntoIterator>::Item: IntoZval,
+    {
+        unsafe fn start<T>(object: &Zval) -> PhpResult<Items>
+        where
+            T: RegisteredClass,
+            for<'a> &'a T: IntoIterator,
+            for<'a> <&'a T as IntoIterator>::Item: IntoZval,
+        {
+            let obj = object
+                .object()
+                .and_then(ZendClassObject::<T>::from_zend_obj)
+                .ok_or("Invalid object given to iterator")?;
+            // SAFETY: The iterator holds a reference to the object, which therefore
+            // outlives the items.
+            let obj = &*(obj as *const ZendClassObject<T>);
+            let this = obj.try_borrow()?;
+            let value = &*(&*this as *const T);
+            Ok(Box::new(BorrowedItems {
+                iter: value.into_iter(),
+                _this: this,
+            }))
+        }
+
+        if by_ref != 0 {
+            throw(PhpException::new(
+                "An iterator cannot be used with foreach by reference".into(),
+                0,
+                ce::error(),
+            ));
+            return ptr::null_mut();
+        }
+
+        let iter = emalloc(Layout::new::<Self>()) as *mut Self;
+        zend_iterator_init(&mut (*iter).it);
+        // The iterator keeps a reference to the object while it is alive.
+        ptr::write(&mut (*iter).it.data, (*object).shallow_clone());
+        (*iter).it.funcs = &CLASS_ITERATOR_FUNCS;
+        ptr::write(&mut (*iter).start, start::<T>);
+        ptr::write(&mut (*iter).items, None);
+        ptr::write(&mut (*iter).current, None);
+        (*iter).pos = 0;
+
+        &mut (*iter).it
+    }
+
+    /// Takes the next item from the Rust iterator. The iterator is dropped
+    /// once it has been exhausted, releasing the borrow of the object.
+    fn fetch(&mut self) {
+        self.current = match self.items.as_mut().and_then(Iterator::next) {
+            Some(Ok(item)) => Some(item),
+            Some(Err(e)) => {
+                throw(e);
+                None
+            }
+            None => None,
+        };
+        if self.current.is_none() {
+            self.items = None;
+        }
+    }
+
+    unsafe extern "C" fn dtor(iter: *mut zend_object_iterator) {
+        // The memory of the iterator itself is freed by the engine. The Rust iterator
+        // borrows the object, so it is dropped before the reference to the object.
+        let iter = iter as *mut Self;
+        ptr::drop_in_place(&mut (*iter).current);
+        ptr::drop_in_place(&mut (*iter).items);
+        ptr::drop_in_place(&mut (*iter).it.data);
+    }
+
+    unsafe extern "C" fn valid(iter: *mut zend_object_iterator) -> c_int {
+        let iter = &*(iter as *mut Self);
+        if iter.current.is_some() {
+            ZEND_RESULT_CODE_SUCCESS
+        } else {
+            ZEND_RESULT_CODE_FAILURE
+        }
+    }
+
+    unsafe extern "C" fn get_current_data(iter: *mut zend_object_iterator) -> *mut Zval {
+        let iter = &mut *(iter as *mut Self);
+        match iter.current.as_mut() {
+            Some(item) => item,
+            None => ptr::null_mut(),
+        }
+    }
+
+    unsafe extern "C" fn get_current_key(iter: *mut zend_object_iterator, key: *mut Zval) {
+        let iter = &*(iter as *mut Self);
+        let mut zv = Zval::new();
+        zv.set_long(iter.pos);
+        // SAFETY: `key` is uninitialized, so the value is written without dropping the
+        // previous contents.
+        ptr::write(key, zv);
+    }
+
+    unsafe extern "C" fn move_forward(iter: *mut zend_object_iterator) {
+        let iter = &mut *(iter as *mut Self);
+        iter.pos += 1;
+        iter.fetch();
+    }
+
+    unsafe extern "C" fn rewind(iter: *mut zend_object_iterator) {
+        let iter = &mut *(iter as *mut Self);
+        iter.pos = 0;
+        // The previous iterator is dropped first to release its borrow of the object.
+        iter.items = None;
+        match (iter.start)(&iter.it.data) {
+            Ok(items) => iter.items = Some(items),
+            Err(e) => throw(e),
+        }
+        iter.fetch();
+    }
+}
@@ -1,11 +1,14 @@
 //! Internal, public functions that are called from downstream extensions.
 
 /// Called by startup functions registered with the [`#[php_startup]`] macro.
-/// Initializes all classes that are defined by ext-php-rs (i.e. `Closure`).
+/// Initializes all classes that are defined by ext-php-rs (i.e. `Closure` and
+/// `RustIterator`).
 ///
 /// [`#[php_startup]`]: crate::php_startup
 #[inline(always)]
 pub fn ext_php_rs_startup() {
     #[cfg(feature = "closure")]
     crate::closure::Closure::build();
+    #[cfg(feature = "iterator")]
+    crate::iterator::RustIterator::build();
 }
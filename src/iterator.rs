@@ -0,0 +1,470 @@
+//! Types used for exporting lazy Rust iterators to PHP.
+
+use std::{alloc::Layout, collections::HashMap, os::raw::c_int, ptr};
+
+use crate::{
+    alloc::emalloc,
+    builders::{ClassBuilder, FunctionBuilder},
+    class::{ClassMetadata, RegisteredClass},
+    convert::IntoZval,
+    error::Result,
+    exception::{PhpException, PhpResult},
+    ffi::{
+        zend_iterator_init, zend_object_iterator, zend_object_iterator_funcs,
+        ZEND_RESULT_CODE_FAILURE, ZEND_RESULT_CODE_SUCCESS,
+    },
+    flags::{ClassFlags, DataType, MethodFlags},
+    interfaces::{parse_method_mut, throw},
+    props::Property,
+    types::{ZendClassObject, Zval},
+    zend::{ce, ClassEntry, ExecuteData, FunctionEntry, GcBuffer},
+    zend_fastcall,
+};
+
+/// Class entry and handlers for Rust iterators.
+static ITERATOR_META: ClassMetadata<RustIterator> = ClassMetadata::new();
+
+/// Produces the next key and value of an iterator. The key is [`None`] when
+/// the iterator only yields values.
+type NextFn = dyn FnMut() -> Option<Result<(Option<Zval>, Zval)>>;
+
+/// A lazy sequence of values exported to PHP, backed by a Rust [`Iterator`].
+///
+/// Internally, a class `RustIterator` is registered which implements PHP's
+/// `Iterator` interface:
+///
+/// ```php
+/// <?php
+///
+/// final class RustIterator implements Iterator {
+///     public function current(): mixed {}
+///     public function key(): mixed {}
+///     public function next(): void {}
+///     public function rewind(): void {}
+///     public function valid(): bool {}
+/// }
+/// ```
+///
+/// Items are only pulled from the Rust iterator when PHP advances, so large or
+/// infinite sequences can be streamed without collecting them first. Iterating
+/// over the object in `foreach` uses a native iterator and does not call the
+/// PHP methods.
+///
+/// Like a PHP `Generator`, the iterator can only be traversed once, and an
+/// exception is thrown when it is rewound after it has been advanced. It is not
+/// a `Generator` however, so values cannot be sent into it.
+///
+/// Boxed iterators can also be returned directly, as
+/// `Box<dyn Iterator<Item = T>>` implements [`IntoZval`] through this type.
+/// Boxed iterators of `(K, V)` tuples are converted with
+/// [`with_keys`](RustIterator::with_keys).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ext_php_rs::{iterator::RustIterator, prelude::*};
+///
+/// #[php_function]
+/// pub fn squares(count: i64) -> RustIterator {
+///     RustIterator::new((0..count).map(|i| i * i))
+/// }
+/// ```
+pub struct RustIterator {
+    next: Box<NextFn>,
+    current: Option<(Zval, Zval)>,
+    pos: i64,
+    started: bool,
+    advanced: bool,
+}
+
+unsafe impl Send for RustIterator {}
+unsafe impl Sync for RustIterator {}
+
+impl RustIterator {
+    /// Wraps a Rust iterator into a type which can be returned to PHP. The
+    /// keys of the items are their position in the sequence, starting at 0.
+    ///
+    /// # Parameters
+    ///
+    /// * `iter` - The iterator to wrap.
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: 'static,
+        I::Item: IntoZval,
+    {
+        let mut iter = iter.into_iter();
+        Self::from_fn(Box::new(move || {
+            iter.next().map(|value| Ok((None, value.into_zval(false)?)))
+        }))
+    }
+
+    /// Wraps a Rust iterator of key and value pairs into a type which can be
+    /// returned to PHP. Keys can be of any type, and may be repeated.
+    ///
+    /// # Parameters
+    ///
+    /// * `iter` - The iterator to wrap.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ext_php_rs::{iterator::RustIterator, prelude::*};
+    ///
+    /// #[php_function]
+    /// pub fn letters() -> RustIterator {
+    ///     RustIterator::with_keys(('a'..='e').map(|c| (c.to_string(), c as u32)))
+    /// }
+    /// ```
+    pub fn with_keys<I, K, V>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        I::IntoIter: 'static,
+        K: IntoZval,
+        V: IntoZval,
+    {
+        let mut iter = iter.into_iter();
+        Self::from_fn(Box::new(move || {
+            iter.next()
+                .map(|(key, value)| Ok((Some(key.into_zval(false)?), value.into_zval(false)?)))
+        }))
+    }
+
+    fn from_fn(next: Box<NextFn>) -> Self {
+        Self {
+            next,
+            current: None,
+            pos: 0,
+            started: false,
+            advanced: false,
+        }
+    }
+
+    /// Builds the class entry for [`RustIterator`], registering it with PHP.
+    /// This function should only be called once inside your module startup
+    /// function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function is called more than once.
+    pub fn build() {
+        if ITERATOR_META.has_ce() {
+            panic!("RustIterator has already been built.");
+        }
+
+        let mut builder = ClassBuilder::new("RustIterator")
+            .flags(ClassFlags::Final)
+            .implements(ce::iterator())
+            .method(
+                method(
+                    FunctionBuilder::new("current", Self::current_method),
+                    DataType::Mixed,
+                    true,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    FunctionBuilder::new("key", Self::key_method),
+                    DataType::Mixed,
+                    true,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    FunctionBuilder::new("next", Self::next_method),
+                    DataType::Void,
+                    false,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    FunctionBuilder::new("rewind", Self::rewind_method),
+                    DataType::Void,
+                    false,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    FunctionBuilder::new("valid", Self::valid_method),
+                    DataType::Bool,
+                    false,
+                ),
+                MethodFlags::Public,
+            )
+            .object_override::<Self>();
+        builder.get_iterator = Some(NativeIterator::get_iterator);
+        let ce = builder
+            .build()
+            .expect("Failed to build `RustIterator` PHP class.");
+        ITERATOR_META.set_ce(ce);
+    }
+
+    /// Pulls the next item from the Rust iterator.
+    fn fetch(&mut self) -> PhpResult<()> {
+        self.current = None;
+        let (key, value) = match (self.next)() {
+            Some(item) => item.map_err(|e| format!("Failed to convert iterator item: {}", e))?,
+            None => return Ok(()),
+        };
+        let key = match key {
+            Some(key) => key,
+            None => self.pos.into_zval(false)?,
+        };
+        self.pos += 1;
+        self.current = Some((key, value));
+        Ok(())
+    }
+
+    /// Pulls the first item if the iteration has not started yet.
+    fn start(&mut self) -> PhpResult<()> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+        self.fetch()
+    }
+
+    fn rewind(&mut self) -> PhpResult<()> {
+        if self.advanced {
+            return Err(PhpException::new(
+                "Cannot rewind a RustIterator that was already run".into(),
+                0,
+                ce::exception(),
+            ));
+        }
+        self.start()
+    }
+
+    fn valid(&mut self) -> PhpResult<bool> {
+        self.start()?;
+        Ok(self.current.is_some())
+    }
+
+    fn current(&mut self) -> PhpResult<Option<&mut Zval>> {
+        self.start()?;
+        Ok(self.current.as_mut().map(|(_, value)| value))
+    }
+
+    fn key(&mut self) -> PhpResult<Option<&Zval>> {
+        self.start()?;
+        Ok(self.current.as_ref().map(|(key, _)| key))
+    }
+
+    fn next(&mut self) -> PhpResult<()> {
+        self.start()?;
+        self.advanced = true;
+        self.fetch()
+    }
+
+    zend_fastcall! {
+        extern "C" fn current_method(ex: &mut ExecuteData, retval: &mut Zval) {
+            if let Some(mut this) = parse_method_mut::<Self>(ex, &mut []) {
+                match this.current() {
+                    Ok(Some(value)) => *retval = value.shallow_clone(),
+                    Ok(None) => retval.set_null(),
+                    Err(e) => throw(e),
+                }
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn key_method(ex: &mut ExecuteData, retval: &mut Zval) {
+            if let Some(mut this) = parse_method_mut::<Self>(ex, &mut []) {
+                match this.key() {
+                    Ok(Some(key)) => *retval = key.shallow_clone(),
+                    Ok(None) => retval.set_null(),
+                    Err(e) => throw(e),
+                }
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn next_method(ex: &mut ExecuteData, _: &mut Zval) {
+            if let Some(mut this) = parse_method_mut::<Self>(ex, &mut []) {
+                if let Err(e) = this.next() {
+                    throw(e);
+                }
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn rewind_method(ex: &mut ExecuteData, _: &mut Zval) {
+            if let Some(mut this) = parse_method_mut::<Self>(ex, &mut []) {
+                if let Err(e) = this.rewind() {
+                    throw(e);
+                }
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn valid_method(ex: &mut ExecuteData, retval: &mut Zval) {
+            if let Some(mut this) = parse_method_mut::<Self>(ex, &mut []) {
+                match this.valid() {
+                    Ok(valid) => retval.set_bool(valid),
+                    Err(e) => throw(e),
+                }
+            }
+        }
+    }
+}
+
+impl RegisteredClass for RustIterator {
+    const CLASS_NAME: &'static str = "RustIterator";
+
+    fn get_metadata() -> &'static ClassMetadata<Self> {
+        &ITERATOR_META
+    }
+
+    fn get_properties<'a>() -> HashMap<&'static str, Property<'a, Self>> {
+        HashMap::new()
+    }
+
+    fn get_gc(&self, gc: &mut GcBuffer) {
+        if let Some((key, value)) = &self.current {
+            gc.add_zval(key);
+            gc.add_zval(value);
+        }
+    }
+}
+
+class_derives!(RustIterator);
+
+impl<T: IntoZval + 'static> IntoZval for Box<dyn Iterator<Item = T>> {
+    const TYPE: DataType = DataType::Object(Some(RustIterator::CLASS_NAME));
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        RustIterator::new(self).set_zval(zv, persistent)
+    }
+}
+
+impl<K, V> IntoZval for Box<dyn Iterator<Item = (K, V)>>
+where
+    K: IntoZval + 'static,
+    V: IntoZval + 'static,
+{
+    const TYPE: DataType = DataType::Object(Some(RustIterator::CLASS_NAME));
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        RustIterator::with_keys(self).set_zval(zv, persistent)
+    }
+}
+
+/// Builds a native method of `RustIterator` taking no arguments.
+fn method(func: FunctionBuilder<'_>, ret: DataType, allow_null: bool) -> FunctionEntry {
+    func.returns(ret, false, allow_null)
+        .build()
+        .expect("Failed to build `RustIterator` method")
+}
+
+/// Native iterator used by `foreach`, advancing the `RustIterator` object it
+/// was created from.
+#[repr(C)]
+struct NativeIterator {
+    it: zend_object_iterator,
+}
+
+static NATIVE_ITERATOR_FUNCS: zend_object_iterator_funcs = zend_object_iterator_funcs {
+    dtor: Some(NativeIterator::dtor),
+    valid: Some(NativeIterator::valid),
+    get_current_data: Some(NativeIterator::get_current_data),
+    get_current_key: Some(NativeIterator::get_current_key),
+    move_forward: Some(NativeIterator::move_forward),
+    rewind: Some(NativeIterator::rewind),
+    invalidate_current: None,
+    get_gc: None,
+};
+
+impl NativeIterator {
+    unsafe extern "C" fn get_iterator(
+        _: *mut ClassEntry,
+        object: *mut Zval,
+        by_ref: c_int,
+    ) -> *mut zend_object_iterator {
+        if by_ref != 0 {
+            throw(PhpException::new(
+                "An iterator cannot be used with foreach by reference".into(),
+                0,
+                ce::error(),
+            ));
+            return ptr::null_mut();
+        }
+
+        let iter = emalloc(Layout::new::<Self>()) as *mut Self;
+        zend_iterator_init(&mut (*iter).it);
+        // The iterator keeps a reference to the object while it is alive.
+        ptr::write(&mut (*iter).it.data, (*object).shallow_clone());
+        (*iter).it.funcs = &NATIVE_ITERATOR_FUNCS;
+
+        &mut (*iter).it
+    }
+
+    /// Returns the `RustIterator` object the iterator was created from.
+    unsafe fn this<'a>(iter: *mut zend_object_iterator) -> Option<&'a mut RustIterator> {
+        (*iter)
+            .data
+            .object_mut()
+            .and_then(ZendClassObject::<RustIterator>::from_zend_obj_mut)
+            .and_then(|obj| obj.obj.as_mut())
+    }
+
+    unsafe extern "C" fn dtor(iter: *mut zend_object_iterator) {
+        // The memory of the iterator itself is freed by the engine.
+        ptr::drop_in_place(&mut (*iter).data);
+    }
+
+    unsafe extern "C" fn valid(iter: *mut zend_object_iterator) -> c_int {
+        match Self::this(iter).map(|this| this.valid()) {
+            Some(Ok(true)) => ZEND_RESULT_CODE_SUCCESS,
+            Some(Err(e)) => {
+                throw(e);
+                ZEND_RESULT_CODE_FAILURE
+            }
+            _ => ZEND_RESULT_CODE_FAILURE,
+        }
+    }
+
+    unsafe extern "C" fn get_current_data(iter: *mut zend_object_iterator) -> *mut Zval {
+        match Self::this(iter).map(|this| this.current()) {
+            Some(Ok(Some(value))) => value,
+            Some(Err(e)) => {
+                throw(e);
+                ptr::null_mut()
+            }
+            _ => ptr::null_mut(),
+        }
+    }
+
+    unsafe extern "C" fn get_current_key(iter: *mut zend_object_iterator, key: *mut Zval) {
+        let zv = match Self::this(iter).map(|this| this.key()) {
+            Some(Ok(Some(k))) => k.shallow_clone(),
+            Some(Err(e)) => {
+                throw(e);
+                Zval::new()
+            }
+            _ => Zval::new(),
+        };
+        // SAFETY: `key` is uninitialized, so the value is written without dropping the
+        // previous contents.
+        ptr::write(key, zv);
+    }
+
+    unsafe extern "C" fn move_forward(iter: *mut zend_object_iterator) {
+        if let Some(Err(e)) = Self::this(iter).map(|this| this.next()) {
+            throw(e);
+        }
+    }
+
+    unsafe extern "C" fn rewind(iter: *mut zend_object_iterator) {
+        if let Some(Err(e)) = Self::this(iter).map(|this| this.rewind()) {
+            throw(e);
+        }
+    }
+}
@@ -5,6 +5,7 @@
 #![allow(non_snake_case)]
 #![cfg_attr(docs, feature(doc_cfg))]
 #![cfg_attr(windows, feature(abi_vectorcall))]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
 
 pub mod alloc;
 pub mod args;
@@ -15,6 +16,8 @@ pub mod convert;
 pub mod error;
 pub mod exception;
 pub mod ffi;
+#[cfg(any(php81, php82))]
+pub mod fiber;
 pub mod flags;
 #[macro_use]
 pub mod macros;
@@ -27,10 +30,21 @@ pub mod constant;
 pub mod describe;
 #[cfg(feature = "embed")]
 pub mod embed;
+#[cfg(any(php81, php82))]
+pub mod enum_;
+pub mod interfaces;
 #[doc(hidden)]
 pub mod internal;
+#[cfg(any(docs, feature = "iterator"))]
+#[cfg_attr(docs, doc(cfg(feature = "iterator")))]
+pub mod iterator;
+pub mod persistent;
 pub mod props;
 pub mod rc;
+pub mod resource;
+#[cfg(feature = "serde")]
+#[cfg_attr(docs, doc(cfg(feature = "serde")))]
+pub mod serde;
 pub mod types;
 pub mod zend;
 pub mod hooks;
@@ -44,14 +58,21 @@ pub mod prelude {
     #[cfg_attr(docs, doc(cfg(feature = "closure")))]
     pub use crate::closure::Closure;
     pub use crate::exception::{PhpException, PhpResult};
+    #[cfg(any(docs, feature = "iterator"))]
+    #[cfg_attr(docs, doc(cfg(feature = "iterator")))]
+    pub use crate::iterator::RustIterator;
     pub use crate::php_class;
     pub use crate::php_const;
+    #[cfg(any(php81, php82))]
+    pub use crate::php_enum;
     pub use crate::php_extern;
     pub use crate::php_function;
     pub use crate::php_impl;
+    pub use crate::php_interface;
     pub use crate::php_module;
     pub use crate::php_print;
     pub use crate::php_println;
+    pub use crate::php_resource;
     pub use crate::php_startup;
     pub use crate::php_function_hook;
     pub use crate::types::ZendCallable;
@@ -460,14 +481,25 @@ pub use ext_php_rs_derive::php_module;
 /// * `#[extends(ce)]` - Sets the parent class of this new class. Can only be
 ///   used once, and `ce` may be any valid expression.
 /// * `#[implements(ce)]` - Implements an interface on the new class. Can be
-///   used multiple times, and `ce` may be any valid expression.
+///   used multiple times, and `ce` may be any valid expression, or the name of
+///   a trait annotated with [`macro@php_interface`].
+/// * `#[countable]`, `#[array_access]`, `#[iterable]` and `#[stringable]` -
+///   Implement the `Countable`, `ArrayAccess`, `IteratorAggregate` and
+///   `Stringable` interfaces natively, using the `len()` method, the
+///   [`Index`](std::ops::Index) and [`IndexMut`](std::ops::IndexMut)
+///   implementations, the [`IntoIterator`] implementation of `&T` and the
+///   [`Display`](std::fmt::Display) implementation of the struct
+///   respectively. See the [`interfaces`] module.
 ///
 /// This attribute (and its associated structs) must be defined *above* the
 /// startup function (which is annotated by the [`macro@php_startup`] macro, or
 /// automatically generated just above the [`macro@php_module`] function).
 ///
 /// Fields defined on the struct *are not* the same as PHP properties, and are
-/// only accessible from Rust.
+/// only accessible from Rust, unless they are annotated with `#[prop]`. Field
+/// properties are declared on the PHP class with a type derived from the Rust
+/// field type, and accept the `rename`, `flags` and `readonly` options, e.g.
+/// `#[prop(readonly, flags = PropertyFlags::Protected)]`.
 ///
 /// # Example
 ///
@@ -514,6 +546,182 @@ pub use ext_php_rs_derive::php_module;
 /// ```
 pub use ext_php_rs_derive::php_class;
 
+/// Annotates a Rust trait that will be exported to PHP as an interface.
+///
+/// The constants of the trait become interface constants and must have a
+/// value. The methods of the trait become abstract interface methods, with
+/// their signatures built from the Rust types in the same way as
+/// [`macro@php_impl`] methods. Methods taking `&self` become instance
+/// methods, the others become static methods.
+///
+/// This attribute takes a set of optional arguments:
+///
+/// * `name` - The name of the exported interface, if it is different from the
+///   Rust trait name.
+/// * `rename_methods` - The rule used to rename the methods, the same as for
+///   [`macro@php_impl`]. Defaults to `camelCase`.
+///
+/// Supertraits which are also annotated with this attribute become parent
+/// interfaces, and must be declared first.
+///
+/// A [`macro@php_class`] can implement the interface by giving the trait name to
+/// the `#[implements]` attribute. The methods required by the interface must
+/// then be defined in the [`macro@php_impl`] block of the class, otherwise the
+/// module fails to compile. The check is skipped for classes which extend
+/// another class, as the methods may be inherited.
+///
+/// # Example
+///
+/// ```
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// # use ext_php_rs::prelude::*;
+/// #[php_interface(name = "Shape")]
+/// pub trait Shape {
+///     const SIDES: i64 = 0;
+///
+///     fn area(&self) -> f64;
+/// }
+///
+/// #[php_class]
+/// #[implements(Shape)]
+/// pub struct Square {
+///     side: f64,
+/// }
+///
+/// #[php_impl]
+/// impl Square {
+///     pub fn area(&self) -> f64 {
+///         self.side * self.side
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+pub use ext_php_rs_derive::php_interface;
+
+/// Annotates a Rust enum that will be exported to PHP as a native enum.
+///
+/// Native enums are only available from PHP 8.1 onwards. Every variant of the
+/// Rust enum must be a unit variant, and becomes a case of the PHP enum.
+///
+/// This attribute takes a set of optional arguments:
+///
+/// * `name` - The name of the exported enum, if it is different from the Rust
+///   enum name. This can be useful for namespaced enums, as you cannot place
+///   backslashes in Rust enum names.
+///
+/// The enum is pure unless its cases have values. A case value is given either
+/// by an integer discriminant on the variant, or with the
+/// `#[case(value = ...)]` attribute, which accepts integer and string
+/// literals. Either all or none of the cases must have a value, and all values
+/// must have the same type. The `#[case(name = "...")]` attribute renames the
+/// PHP case.
+///
+/// The enum may also use `#[implements(ce)]` underneath this attribute to
+/// implement an interface. Methods and constants can be added with the
+/// [`macro@php_impl`] attribute; enum methods may take `&self` but not
+/// `&mut self`, as enum cases are immutable.
+///
+/// The enum implements [`FromZval`] and [`IntoZval`], converting between the
+/// Rust value and its PHP case object.
+///
+/// # Example
+///
+/// ```
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// # use ext_php_rs::prelude::*;
+/// #[php_enum]
+/// #[derive(Clone, Copy)]
+/// pub enum Suit {
+///     #[case(value = "H")]
+///     Hearts,
+///     #[case(value = "S")]
+///     Spades,
+/// }
+///
+/// #[php_impl]
+/// impl Suit {
+///     pub fn color(&self) -> &'static str {
+///         match self {
+///             Self::Hearts => "Red",
+///             Self::Spades => "Black",
+///         }
+///     }
+/// }
+///
+/// #[php_function]
+/// pub fn trump() -> Suit {
+///     Suit::Spades
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`FromZval`]: crate::convert::FromZval
+/// [`IntoZval`]: crate::convert::IntoZval
+#[cfg(any(php81, php82))]
+pub use ext_php_rs_derive::php_enum;
+
+/// Annotates a structure that will be exported to PHP as a resource type.
+///
+/// Resources are opaque handles to native values. New APIs should usually
+/// export a class with [`macro@php_class`] instead, but resources are still
+/// needed to mirror existing APIs which return them.
+///
+/// This attribute takes a set of optional arguments:
+///
+/// * `name` - The name of the resource type, as returned by
+///   `get_resource_type()`. Defaults to the name of the Rust struct.
+///
+/// The resource type is registered in the module startup function, with a
+/// destructor which drops the Rust value once the resource is destroyed. The
+/// struct implements [`RegisteredResource`], and the following conversions:
+///
+/// * `IntoZval for T`, which moves the value into a new resource.
+/// * `FromZval for &T` and `FromZvalMut for &mut T`, which borrow the value
+///   held by a resource after checking its type. Resources of other types and
+///   closed resources are rejected.
+///
+/// Persistent resources, which are kept across requests, are created with
+/// [`ZendResource::new_persistent`].
+///
+/// # Example
+///
+/// ```
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// # use ext_php_rs::prelude::*;
+/// #[php_resource(name = "counter")]
+/// pub struct Counter {
+///     count: i64,
+/// }
+///
+/// #[php_function]
+/// pub fn counter_open() -> Counter {
+///     Counter { count: 0 }
+/// }
+///
+/// #[php_function]
+/// pub fn counter_bump(counter: &mut Counter) -> i64 {
+///     counter.count += 1;
+///     counter.count
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`RegisteredResource`]: crate::resource::RegisteredResource
+/// [`ZendResource::new_persistent`]: crate::types::ZendResource::new_persistent
+pub use ext_php_rs_derive::php_resource;
+
 /// Annotates a function that will be called by PHP when the module starts up.
 /// Generally used to register classes and constants.
 ///
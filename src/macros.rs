@@ -316,6 +316,118 @@ macro_rules! class_derives {
     };
 }
 
+/// Implements a set of traits required to convert types that implement
+/// [`RegisteredEnum`] to and from [`ZendObject`]s and [`Zval`]s. Generally,
+/// this macro should not be called directly, as it is called on any type that
+/// uses the [`php_enum`] macro.
+///
+/// The following traits are implemented:
+///
+/// * `FromZendObject for T`
+/// * `FromZval for T`
+/// * `IntoZval for T`
+///
+/// Enum cases are singletons owned by PHP, so the conversions work on copies
+/// of the Rust value rather than references into the object.
+///
+/// [`RegisteredEnum`]: crate::enum_::RegisteredEnum
+/// [`ZendObject`]: crate::types::ZendObject
+/// [`Zval`]: crate::types::Zval
+/// [`php_enum`]: crate::php_enum
+#[macro_export]
+macro_rules! enum_derives {
+    ($type: ty) => {
+        impl<'a> $crate::convert::FromZendObject<'a> for $type {
+            #[inline]
+            fn from_zend_object(obj: &'a $crate::types::ZendObject) -> $crate::error::Result<Self> {
+                <$type as $crate::enum_::RegisteredEnum>::from_case_object(obj)
+                    .ok_or($crate::error::Error::InvalidScope)
+            }
+        }
+
+        impl<'a> $crate::convert::FromZval<'a> for $type {
+            const TYPE: $crate::flags::DataType = $crate::flags::DataType::Object(Some(
+                <$type as $crate::enum_::RegisteredEnum>::CLASS_NAME,
+            ));
+
+            #[inline]
+            fn from_zval(zval: &'a $crate::types::Zval) -> ::std::option::Option<Self> {
+                <$type as $crate::enum_::RegisteredEnum>::from_case_object(zval.object()?)
+            }
+        }
+
+        impl $crate::convert::IntoZval for $type {
+            const TYPE: $crate::flags::DataType = $crate::flags::DataType::Object(Some(
+                <$type as $crate::enum_::RegisteredEnum>::CLASS_NAME,
+            ));
+
+            #[inline]
+            fn set_zval(self, zv: &mut $crate::types::Zval, _: bool) -> $crate::error::Result<()> {
+                let obj = <$type as $crate::enum_::RegisteredEnum>::case_object(&self)?;
+                zv.set_object(obj);
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Implements a set of traits required to convert types that implement
+/// [`RegisteredResource`] to and from [`Zval`]s. Generally, this macro should
+/// not be called directly, as it is called on any type that uses the
+/// [`php_resource`] macro.
+///
+/// The following traits are implemented:
+///
+/// * `FromZval for &'a T`
+/// * `FromZvalMut for &'a mut T`
+/// * `IntoZval for T`
+///
+/// Converting from a zval fails if the zval is not a resource of type `T`, or
+/// if the resource has been closed.
+///
+/// [`RegisteredResource`]: crate::resource::RegisteredResource
+/// [`Zval`]: crate::types::Zval
+/// [`php_resource`]: crate::php_resource
+#[macro_export]
+macro_rules! resource_derives {
+    ($type: ty) => {
+        impl<'a> $crate::convert::FromZval<'a> for &'a $type {
+            const TYPE: $crate::flags::DataType = $crate::flags::DataType::Resource;
+
+            #[inline]
+            fn from_zval(zval: &'a $crate::types::Zval) -> ::std::option::Option<Self> {
+                <&$crate::types::ZendResource as $crate::convert::FromZval>::from_zval(zval)?
+                    .value::<$type>()
+            }
+        }
+
+        impl<'a> $crate::convert::FromZvalMut<'a> for &'a mut $type {
+            const TYPE: $crate::flags::DataType = $crate::flags::DataType::Resource;
+
+            #[inline]
+            fn from_zval_mut(zval: &'a mut $crate::types::Zval) -> ::std::option::Option<Self> {
+                <&mut $crate::types::ZendResource as $crate::convert::FromZvalMut>::from_zval_mut(
+                    zval,
+                )?
+                .value_mut::<$type>()
+            }
+        }
+
+        impl $crate::convert::IntoZval for $type {
+            const TYPE: $crate::flags::DataType = $crate::flags::DataType::Resource;
+
+            #[inline]
+            fn set_zval(
+                self,
+                zv: &mut $crate::types::Zval,
+                persistent: bool,
+            ) -> $crate::error::Result<()> {
+                $crate::types::ZendResource::new(self).set_zval(zv, persistent)
+            }
+        }
+    };
+}
+
 /// Derives `From<T> for Zval` and `IntoZval` for a given type.
 macro_rules! into_zval {
     ($type: ty, $fn: ident, $dt: ident) => {
@@ -341,8 +453,13 @@ macro_rules! into_zval {
 /// Derives `TryFrom<Zval> for T` and `FromZval for T` on a given type.
 macro_rules! try_from_zval {
     ($type: ty, $fn: ident, $dt: ident) => {
+        try_from_zval!($type, $fn, $dt, None);
+    };
+    ($type: ty, $fn: ident, $dt: ident, $range: expr) => {
         impl $crate::convert::FromZval<'_> for $type {
             const TYPE: $crate::flags::DataType = $crate::flags::DataType::$dt;
+            const RANGE: ::std::option::Option<($crate::types::ZendLong, $crate::types::ZendLong)> =
+                $range;
 
             fn from_zval(zval: &$crate::types::Zval) -> ::std::option::Option<Self> {
                 use ::std::convert::TryInto;
@@ -0,0 +1,189 @@
+//! Types used to share values between requests.
+//!
+//! Values are usually request-bound, and are freed at the end of the request
+//! which created them. A [`PersistentZval`] instead holds a value allocated
+//! with `pemalloc`, which is made immutable so that requests can read it
+//! without copying it or counting references to it, the same way opcache
+//! shares the arrays and strings of cached scripts.
+
+use std::{collections::HashMap, fmt::Debug, mem::ManuallyDrop};
+
+use crate::{
+    convert::IntoZval,
+    error::{Error, Result},
+    ffi::{ext_php_rs_persistent_freeze, ext_php_rs_persistent_zval_dtor},
+    flags::DataType,
+    types::Zval,
+};
+
+/// An immutable value which is shared by every request.
+///
+/// The value is converted into persistent memory when the [`PersistentZval`]
+/// is created, and may only contain scalars, strings and arrays. Strings and
+/// arrays are then made immutable: requests which receive the value read it
+/// in place, and PHP copies it into the request only if a request attempts to
+/// modify it.
+///
+/// Only values with a `'static` lifetime can be returned to PHP, which
+/// ensures the value outlives every request which may hold it. Values are
+/// usually stored in a static [`PersistentCache`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::persistent::PersistentZval;
+/// use std::collections::HashMap;
+///
+/// let mut limits = HashMap::new();
+/// limits.insert("max_connections", 100);
+/// limits.insert("timeout", 30);
+///
+/// let value = PersistentZval::new(limits).unwrap();
+/// assert!(value.value().is_array());
+/// ```
+pub struct PersistentZval(ManuallyDrop<Zval>);
+
+impl PersistentZval {
+    /// Converts the given value into a persistent, immutable zval.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The value to store.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the value could not be converted into a zval, or if
+    /// it contains a value which cannot be shared between requests, such as an
+    /// object or a request-bound string or array.
+    pub fn new(val: impl IntoZval) -> Result<Self> {
+        let mut zv = val.into_zval(true)?;
+        // SAFETY: The value is left untouched if it cannot be frozen, and is then
+        // dropped normally.
+        if unsafe { ext_php_rs_persistent_freeze(&mut zv) } {
+            Ok(Self(ManuallyDrop::new(zv)))
+        } else {
+            Err(Error::InvalidPersistentValue)
+        }
+    }
+
+    /// Returns the stored value.
+    pub fn value(&self) -> &Zval {
+        &self.0
+    }
+}
+
+impl Drop for PersistentZval {
+    fn drop(&mut self) {
+        // SAFETY: Frozen values are owned by the persistent zval, and are not freed by
+        // `zval_ptr_dtor` as they are not reference counted.
+        unsafe { ext_php_rs_persistent_zval_dtor(&mut *self.0) };
+    }
+}
+
+// SAFETY: The value is immutable, and the hashes of its strings are computed
+// when it is frozen, so it is never written to after it has been created.
+unsafe impl Send for PersistentZval {}
+unsafe impl Sync for PersistentZval {}
+
+impl Debug for PersistentZval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PersistentZval").field(self.value()).finish()
+    }
+}
+
+impl IntoZval for &'static PersistentZval {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        // Immutable values are not reference counted, so the zval is copied without
+        // touching the shared value.
+        *zv = self.value().shallow_clone();
+        Ok(())
+    }
+}
+
+/// A set of named [`PersistentZval`]s, such as configuration values loaded
+/// when the module starts up.
+///
+/// The cache is filled before it is shared, and is then stored in a static,
+/// such as a [`OnceCell`], so that its values can be returned to PHP.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::prelude::*;
+/// use ext_php_rs::persistent::{PersistentCache, PersistentZval};
+/// use once_cell::sync::OnceCell;
+///
+/// static CONFIG: OnceCell<PersistentCache> = OnceCell::new();
+///
+/// #[php_startup]
+/// pub fn startup() {
+///     let mut cache = PersistentCache::new();
+///     cache.insert("hosts", vec!["10.0.0.1", "10.0.0.2"]).unwrap();
+///     CONFIG.set(cache).unwrap();
+/// }
+///
+/// #[php_function]
+/// pub fn config_get(key: &str) -> Option<&'static PersistentZval> {
+///     CONFIG.get()?.get(key)
+/// }
+/// ```
+///
+/// [`OnceCell`]: once_cell::sync::OnceCell
+#[derive(Debug, Default)]
+pub struct PersistentCache {
+    entries: HashMap<String, PersistentZval>,
+}
+
+impl PersistentCache {
+    /// Creates a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts the given value into a [`PersistentZval`] and stores it under
+    /// the given key, replacing any previous value.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the value.
+    /// * `val` - The value to store.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the value could not be converted. See
+    /// [`PersistentZval::new`].
+    pub fn insert(&mut self, key: impl Into<String>, val: impl IntoZval) -> Result<()> {
+        self.entries.insert(key.into(), PersistentZval::new(val)?);
+        Ok(())
+    }
+
+    /// Returns the value stored under the given key, if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the value.
+    pub fn get(&self, key: &str) -> Option<&PersistentZval> {
+        self.entries.get(key)
+    }
+
+    /// Returns true if a value is stored under the given key.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the value.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Returns the number of values in the cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -70,7 +70,10 @@ pub type PropertySetter<'a, T> = Option<Box<dyn Fn(&mut T, &Zval) -> PhpResult +
 /// * Method properties, where getter and/or setter functions are provided,
 ///   which are used to get and set the value of the property.
 pub enum Property<'a, T> {
-    Field(Box<dyn (Fn(&mut T) -> &mut dyn Prop) + Send + Sync>),
+    Field {
+        get: Box<dyn (Fn(&T) -> &dyn Prop) + Send + Sync>,
+        get_mut: Box<dyn (Fn(&mut T) -> &mut dyn Prop) + Send + Sync>,
+    },
     Method {
         get: PropertyGetter<'a, T>,
         set: PropertySetter<'a, T>,
@@ -82,7 +85,10 @@ impl<'a, T: 'a> Property<'a, T> {
     ///
     /// # Parameters
     ///
-    /// * `f` - The function used to get a mutable reference to the property.
+    /// * `get` - The function used to get a reference to the property, used
+    ///   when the property is read.
+    /// * `get_mut` - The function used to get a mutable reference to the
+    ///   property, used when the property is written.
     ///
     /// # Examples
     ///
@@ -92,13 +98,18 @@ impl<'a, T: 'a> Property<'a, T> {
     ///     pub a: i32,
     /// }
     ///
-    /// let prop: Property<Test> = Property::field(|test: &mut Test| &mut test.a);
+    /// let prop: Property<Test> =
+    ///     Property::field(|test: &Test| &test.a, |test: &mut Test| &mut test.a);
     /// ```
-    pub fn field<F>(f: F) -> Self
+    pub fn field<F, G>(get: F, get_mut: G) -> Self
     where
-        F: (Fn(&mut T) -> &mut dyn Prop) + Send + Sync + 'static,
+        F: (Fn(&T) -> &dyn Prop) + Send + Sync + 'static,
+        G: (Fn(&mut T) -> &mut dyn Prop) + Send + Sync + 'static,
     {
-        Self::Field(Box::new(f) as Box<dyn (Fn(&mut T) -> &mut dyn Prop) + Send + Sync>)
+        Self::Field {
+            get: Box::new(get) as Box<dyn (Fn(&T) -> &dyn Prop) + Send + Sync>,
+            get_mut: Box::new(get_mut) as Box<dyn (Fn(&mut T) -> &mut dyn Prop) + Send + Sync>,
+        }
     }
 
     /// Creates a method property with getters and setters.
@@ -183,18 +194,18 @@ impl<'a, T: 'a> Property<'a, T> {
     ///     pub a: i32,
     /// }
     ///
-    /// let prop: Property<Test> = Property::field(|obj: &mut Test| &mut obj.a);
+    /// let prop: Property<Test> = Property::field(|obj: &Test| &obj.a, |obj: &mut Test| &mut obj.a);
     ///
-    /// let mut test = Test { a: 500 };
+    /// let test = Test { a: 500 };
     /// let mut zv = Zval::new();
-    /// prop.get(&mut test, &mut zv).unwrap();
+    /// prop.get(&test, &mut zv).unwrap();
     /// assert_eq!(zv.long(), Some(500));
     /// ```
     ///
     /// [`PhpException`]: crate::exception::PhpException
-    pub fn get(&self, self_: &'a mut T, retval: &mut Zval) -> PhpResult {
+    pub fn get(&self, self_: &'a T, retval: &mut Zval) -> PhpResult {
         match self {
-            Property::Field(field) => field(self_)
+            Property::Field { get, .. } => get(self_)
                 .get(retval)
                 .map_err(|e| format!("Failed to get property value: {e:?}").into()),
             Property::Method { get, set: _ } => match get {
@@ -231,7 +242,7 @@ impl<'a, T: 'a> Property<'a, T> {
     ///     pub a: i32,
     /// }
     ///
-    /// let prop: Property<Test> = Property::field(|obj: &mut Test| &mut obj.a);
+    /// let prop: Property<Test> = Property::field(|obj: &Test| &obj.a, |obj: &mut Test| &mut obj.a);
     ///
     /// let mut test = Test { a: 500 };
     /// let zv = 100.into_zval(false).unwrap();
@@ -242,7 +253,7 @@ impl<'a, T: 'a> Property<'a, T> {
     /// [`PhpException`]: crate::exception::PhpException
     pub fn set(&self, self_: &'a mut T, value: &Zval) -> PhpResult {
         match self {
-            Property::Field(field) => field(self_)
+            Property::Field { get_mut, .. } => get_mut(self_)
                 .set(value)
                 .map_err(|e| format!("Failed to set property value: {e:?}").into()),
             Property::Method { get: _, set } => match set {
@@ -1,10 +1,17 @@
 //! Traits and types for interacting with reference counted PHP types.
 
-use std::fmt::Debug;
+use std::{
+    borrow::Borrow,
+    fmt::Debug,
+    mem::ManuallyDrop,
+    ops::Deref,
+    ptr::{self, NonNull},
+};
 
 use crate::{
-    ffi::{zend_refcounted_h, zend_string},
-    types::ZendObject,
+    boxed::{ZBox, ZBoxable},
+    ffi::{zend_refcounted_h, zend_reference, zend_resource, zend_string, GC_IMMUTABLE},
+    types::{ZendHashTable, ZendObject},
 };
 
 /// Object used to store Zend reference counter.
@@ -40,6 +47,20 @@ pub trait PhpRc {
     fn dec_count(&mut self) {
         self.get_rc_mut().refcount -= 1;
     }
+
+    /// Returns true if the object is immutable, such as an interned string or
+    /// an array stored in opcache shared memory. Immutable objects must not be
+    /// modified, and their reference counter must not be changed.
+    fn is_immutable(&self) -> bool {
+        // SAFETY: `type_info` is the only field of the union.
+        unsafe { self.get_rc().u.type_info & GC_IMMUTABLE != 0 }
+    }
+
+    /// Returns true if the object is shared with another owner or is
+    /// immutable, in which case it must be duplicated before it is modified.
+    fn is_shared(&self) -> bool {
+        self.is_immutable() || self.get_count() > 1
+    }
 }
 
 macro_rules! rc {
@@ -58,4 +79,205 @@ macro_rules! rc {
     };
 }
 
-rc!(ZendObject, zend_string);
+rc!(
+    ZendObject,
+    zend_string,
+    ZendHashTable,
+    zend_reference,
+    zend_resource
+);
+
+/// A reference counted pointer to a PHP value, similar to
+/// [`Rc`](std::rc::Rc).
+///
+/// Where a [`ZBox`] is the only owner of its value, a [`ZRc`] holds one
+/// reference to a value which may also be held by PHP variables. Cloning the
+/// handle increments the reference counter of the value, and dropping the
+/// handle decrements it, releasing the value once the last reference is gone.
+/// Immutable values, such as interned strings, are never counted or released.
+///
+/// As the value may be shared, the handle only gives out immutable references.
+/// [`ZRc::make_mut`] gives a mutable reference, duplicating the value first if
+/// it is shared, following the copy-on-write semantics of PHP.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::prelude::*;
+/// use ext_php_rs::{rc::ZRc, types::ZendHashTable};
+///
+/// #[php_function]
+/// pub fn with_total(mut numbers: ZRc<ZendHashTable>) -> ZRc<ZendHashTable> {
+///     let total: i64 = numbers.values().filter_map(|v| v.long()).sum();
+///     // The array passed in by the caller is left untouched.
+///     ZRc::make_mut(&mut numbers).insert("total", total).unwrap();
+///     numbers
+/// }
+/// ```
+pub struct ZRc<T: PhpRc + ZBoxable>(NonNull<T>);
+
+impl<T: PhpRc + ZBoxable> ZRc<T> {
+    /// Creates a new handle from a given pointer, taking over one reference
+    /// to the value. The reference counter is not changed.
+    ///
+    /// # Parameters
+    ///
+    /// * `ptr` - A non-null, well-aligned pointer to a `T`.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that `ptr` is non-null, well-aligned, pointing to a
+    /// `T`, and that the caller owns a reference to the value which is given
+    /// to the handle.
+    pub unsafe fn from_raw(ptr: *mut T) -> Self {
+        Self(NonNull::new_unchecked(ptr))
+    }
+
+    /// Creates a new handle to a borrowed value, incrementing its reference
+    /// counter.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value to reference.
+    pub fn from_ref(value: &T) -> Self {
+        let ptr = value as *const T as *mut T;
+        if !value.is_immutable() {
+            // SAFETY: The reference counter is not covered by Rust's aliasing
+            // rules, PHP modifies it through shared pointers as well.
+            unsafe {
+                let rc = ptr::addr_of!(*value.get_rc()) as *mut ZendRefcount;
+                (*rc).refcount += 1;
+            }
+        }
+        // SAFETY: References are non-null and well-aligned.
+        unsafe { Self::from_raw(ptr) }
+    }
+
+    /// Returns the pointer contained by the handle, dropping the handle
+    /// without decrementing the reference counter.
+    ///
+    /// # Parameters
+    ///
+    /// * `this` - The handle to consume.
+    pub fn into_raw(this: Self) -> *mut T {
+        ManuallyDrop::new(this).0.as_ptr()
+    }
+
+    /// Returns the pointer contained by the handle.
+    ///
+    /// # Parameters
+    ///
+    /// * `this` - The handle.
+    pub fn as_ptr(this: &Self) -> *const T {
+        this.0.as_ptr()
+    }
+
+    /// Returns true if the two handles point to the same value.
+    ///
+    /// # Parameters
+    ///
+    /// * `this` - The first handle.
+    /// * `other` - The second handle.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.0 == other.0
+    }
+
+    /// Returns a mutable reference to the value if it is not shared with any
+    /// other owner and is not immutable.
+    ///
+    /// # Parameters
+    ///
+    /// * `this` - The handle.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if this.is_shared() {
+            None
+        } else {
+            // SAFETY: The handle is the only reference to the value.
+            Some(unsafe { this.0.as_mut() })
+        }
+    }
+
+    /// Returns a mutable reference to the value, first replacing it with a
+    /// duplicate if it is shared with another owner or is immutable. Other
+    /// owners of the value are not affected by changes made through the
+    /// returned reference.
+    ///
+    /// This is the equivalent of `SEPARATE_ARRAY` and `zend_string_separate`.
+    ///
+    /// # Parameters
+    ///
+    /// * `this` - The handle.
+    pub fn make_mut(this: &mut Self) -> &mut T
+    where
+        T: ToOwned<Owned = ZBox<T>>,
+    {
+        if this.is_shared() {
+            *this = (**this).to_owned().into();
+        }
+        // SAFETY: The handle is now the only reference to the value.
+        unsafe { this.0.as_mut() }
+    }
+}
+
+impl<T: PhpRc + ZBoxable> Drop for ZRc<T> {
+    fn drop(&mut self) {
+        // SAFETY: All constructors ensure the contained pointer is well-aligned and
+        // dereferenceable.
+        let value = unsafe { self.0.as_mut() };
+        if value.is_immutable() {
+            return;
+        }
+        if value.get_count() > 1 {
+            value.dec_count();
+        } else {
+            value.free();
+        }
+    }
+}
+
+impl<T: PhpRc + ZBoxable> Clone for ZRc<T> {
+    fn clone(&self) -> Self {
+        Self::from_ref(self)
+    }
+}
+
+impl<T: PhpRc + ZBoxable> Deref for ZRc<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: All constructors ensure the contained pointer is well-aligned and
+        // dereferenceable.
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<T: PhpRc + ZBoxable + Debug> Debug for ZRc<T> {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: PhpRc + ZBoxable> Borrow<T> for ZRc<T> {
+    #[inline]
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T: PhpRc + ZBoxable> AsRef<T> for ZRc<T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: PhpRc + ZBoxable> From<ZBox<T>> for ZRc<T> {
+    fn from(value: ZBox<T>) -> Self {
+        let mut value = ManuallyDrop::new(value);
+        // SAFETY: The box owns the only reference to the value, which is now given
+        // to the handle.
+        unsafe { Self::from_raw(&mut **value) }
+    }
+}
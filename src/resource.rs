@@ -0,0 +1,141 @@
+//! Types and traits used for registering resources with PHP.
+//!
+//! Resources are opaque handles to native values, and predate objects in PHP.
+//! New APIs should usually export a class instead, but resources are still
+//! needed to mirror APIs which return them, such as `fopen()` or `proc_open()`.
+
+use std::{
+    ffi::CString,
+    sync::atomic::{AtomicI32, Ordering},
+};
+
+use crate::{
+    error::{Error, Result},
+    ffi::{zend_register_list_destructors_ex, zend_resource},
+};
+
+/// Implemented on Rust types which are exported to PHP as resources.
+///
+/// The Rust value is moved onto the heap when the resource is created, and
+/// dropped when the resource is destroyed. For regular resources this happens
+/// once the last PHP variable holding the resource is released, or when the
+/// resource is closed. Persistent resources are dropped when the module is
+/// shut down.
+pub trait RegisteredResource: Sized + 'static {
+    /// Name of the resource type, as returned by `get_resource_type()`.
+    const RESOURCE_NAME: &'static str;
+
+    /// Returns a reference to the resource metadata, which stores the resource
+    /// type id.
+    ///
+    /// This must be statically allocated, and is usually done through the
+    /// [`macro@php_resource`] macro.
+    ///
+    /// [`macro@php_resource`]: crate::php_resource
+    fn get_metadata() -> &'static ResourceMetadata;
+}
+
+/// Stores the type id of a Rust resource type which has been registered with
+/// PHP. Usually allocated statically.
+pub struct ResourceMetadata {
+    id: AtomicI32,
+}
+
+impl ResourceMetadata {
+    /// Creates a new resource metadata instance.
+    pub const fn new() -> Self {
+        Self {
+            id: AtomicI32::new(-1),
+        }
+    }
+
+    /// Checks if the type id has been stored, returning a boolean.
+    pub fn has_id(&self) -> bool {
+        self.id.load(Ordering::SeqCst) >= 0
+    }
+
+    /// Retrieves the stored type id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no type id stored inside the resource metadata.
+    pub fn id(&self) -> i32 {
+        let id = self.id.load(Ordering::SeqCst);
+        assert!(
+            id >= 0,
+            "Attempted to retrieve resource type id before it has been stored."
+        );
+        id
+    }
+
+    /// Stores the type id inside the resource metadata.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The type id to store.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type id has already been set in the resource metadata.
+    /// This function should only be called once.
+    pub fn set_id(&self, id: i32) {
+        self.id
+            .compare_exchange(-1, id, Ordering::SeqCst, Ordering::Relaxed)
+            .expect("Resource type id has already been set");
+    }
+}
+
+impl Default for ResourceMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Registers the resource type `T` with PHP, storing its type id in the
+/// metadata of `T`. The same destructor is used for regular and persistent
+/// resources, and drops the Rust value.
+///
+/// This is called by the module startup function generated by the
+/// [`macro@php_module`] macro for every type annotated with
+/// [`macro@php_resource`].
+///
+/// # Parameters
+///
+/// * `module_number` - The module number passed to the startup function.
+///
+/// # Errors
+///
+/// Returns an error if the name of the resource type contains a NUL byte, or
+/// if PHP could not register the type.
+///
+/// [`macro@php_module`]: crate::php_module
+/// [`macro@php_resource`]: crate::php_resource
+pub fn register<T: RegisteredResource>(module_number: i32) -> Result<()> {
+    let name = CString::new(T::RESOURCE_NAME)?;
+    // SAFETY: PHP keeps a pointer to the name for as long as the type is
+    // registered, so it is leaked.
+    let id = unsafe {
+        zend_register_list_destructors_ex(
+            Some(resource_dtor::<T>),
+            Some(resource_dtor::<T>),
+            name.into_raw(),
+            module_number,
+        )
+    };
+    if id < 0 {
+        return Err(Error::ResourceRegistrationFailure);
+    }
+    T::get_metadata().set_id(id);
+    Ok(())
+}
+
+/// Destructor called by PHP when a resource of type `T` is destroyed.
+unsafe extern "C" fn resource_dtor<T: RegisteredResource>(res: *mut zend_resource) {
+    if let Some(res) = res.as_mut() {
+        let ptr = std::mem::replace(&mut res.ptr, std::ptr::null_mut());
+        if !ptr.is_null() {
+            // SAFETY: Resources of this type are always created from a boxed `T`.
+            drop(Box::from_raw(ptr.cast::<T>()));
+        }
+    }
+}
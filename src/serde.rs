@@ -0,0 +1,932 @@
+//! Conversions between [`Zval`]s and Rust types implementing serde's
+//! [`Serialize`] and [`Deserialize`] traits.
+//!
+//! Sequences, tuples and maps are converted into PHP arrays. Structs are
+//! converted into associative arrays by default, or `stdClass` objects when
+//! using [`Serializer::structs_as_objects`]. Enums are externally tagged: unit
+//! variants become a string containing the name of the variant, while all other
+//! variants become an array with a single element keyed by the variant name.
+//!
+//! When deserializing, arrays and objects can be used interchangeably for
+//! structs and maps, and arrays with sequential keys can be used for sequences.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::collections::HashMap;
+//!
+//! use ext_php_rs::serde::{from_zval, to_zval};
+//!
+//! let mut scores = HashMap::new();
+//! scores.insert("alice".to_string(), vec![10, 20]);
+//!
+//! let zval = to_zval(&scores).unwrap();
+//! assert!(zval.is_array());
+//!
+//! let scores: HashMap<String, Vec<i64>> = from_zval(&zval).unwrap();
+//! assert_eq!(scores["alice"], [10, 20]);
+//! ```
+
+use std::{
+    convert::TryInto,
+    fmt::{self, Display},
+};
+
+use serde::{
+    de::{
+        self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+        Visitor,
+    },
+    forward_to_deserialize_any,
+    ser::{self, Serialize},
+    Deserialize,
+};
+
+use crate::{
+    boxed::ZBox,
+    convert::IntoZval,
+    exception::PhpException,
+    flags::DataType,
+    types::{ArrayKey, ZendHashTable, ZendLong, ZendObject, ZendStr, Zval},
+};
+
+/// The result type returned from the serde conversion functions.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// An error that occurred while converting between a Rust value and a
+/// [`Zval`].
+///
+/// The error carries the path of the value that could not be converted, for
+/// example `users[2].name`.
+#[derive(Debug)]
+pub struct Error {
+    message: String,
+    /// Segments of the path, innermost first.
+    path: Vec<Segment>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+impl Error {
+    fn new(message: impl Display) -> Self {
+        Self {
+            message: message.to_string(),
+            path: vec![],
+        }
+    }
+
+    /// Adds a segment to the front of the path of the error.
+    fn at(mut self, segment: Segment) -> Self {
+        self.path.push(segment);
+        self
+    }
+
+    /// Returns the error message, without the path.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the path of the value that could not be converted, e.g.
+    /// `users[2].name`. The path is empty if the top-level value failed to
+    /// convert.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        for segment in self.path.iter().rev() {
+            match segment {
+                Segment::Key(key) if path.is_empty() => path.push_str(key),
+                Segment::Key(key) => {
+                    path.push('.');
+                    path.push_str(key);
+                }
+                Segment::Index(index) => path.push_str(&format!("[{index}]")),
+            }
+        }
+        path
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path(), self.message)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::new(msg)
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::new(msg)
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        Self::default(err.to_string())
+    }
+}
+
+/// Converts a Rust value into a [`Zval`], with structs being converted into
+/// associative arrays.
+///
+/// Use [`Serializer`] directly to convert structs into `stdClass` objects
+/// instead.
+///
+/// # Parameters
+///
+/// * `value` - The value to convert.
+pub fn to_zval<T: Serialize + ?Sized>(value: &T) -> Result<Zval> {
+    value.serialize(Serializer::new())
+}
+
+/// Converts a [`Zval`] into a Rust value.
+///
+/// # Parameters
+///
+/// * `zval` - The zval to convert. Strings may be borrowed from the zval.
+pub fn from_zval<'de, T: Deserialize<'de>>(zval: &'de Zval) -> Result<T> {
+    T::deserialize(Deserializer::new(zval))
+}
+
+/// A serializer which converts Rust values into [`Zval`]s.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::serde::Serializer;
+/// use serde::Serialize;
+///
+/// # fn serialize<T: Serialize>(value: &T) {
+/// let zval = value
+///     .serialize(Serializer::new().structs_as_objects(true))
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Serializer {
+    structs_as_objects: bool,
+}
+
+impl Serializer {
+    /// Creates a new serializer, which converts structs into associative
+    /// arrays.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether structs should be converted into `stdClass` objects rather
+    /// than associative arrays.
+    pub fn structs_as_objects(mut self, enabled: bool) -> Self {
+        self.structs_as_objects = enabled;
+        self
+    }
+
+    fn long<T: TryInto<ZendLong> + Display + Copy>(self, val: T) -> Result<Zval> {
+        let long = val
+            .try_into()
+            .map_err(|_| Error::new(format!("integer {val} is out of range")))?;
+        let mut zv = Zval::new();
+        zv.set_long(long);
+        Ok(zv)
+    }
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Zval;
+    type Error = Error;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeStruct;
+    type SerializeStructVariant = SerializeStruct;
+
+    fn serialize_bool(self, v: bool) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_bool(v);
+        Ok(zv)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Zval> {
+        self.long(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Zval> {
+        self.long(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Zval> {
+        self.long(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Zval> {
+        self.long(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Zval> {
+        self.long(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Zval> {
+        self.long(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Zval> {
+        self.long(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Zval> {
+        self.long(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Zval> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_double(v);
+        Ok(zv)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Zval> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Zval> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_zend_string(ZendStr::new(v, false));
+        Ok(zv)
+    }
+
+    fn serialize_none(self) -> Result<Zval> {
+        Ok(Zval::new())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Zval> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Zval> {
+        Ok(Zval::new())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Zval> {
+        Ok(Zval::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Zval> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Zval> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Zval> {
+        let value = value
+            .serialize(self)
+            .map_err(|e| e.at(Segment::Key(variant.into())))?;
+        tagged(variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray> {
+        Ok(SerializeArray {
+            ser: self,
+            ht: ZendHashTable::with_capacity(len.unwrap_or(0) as u32),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray> {
+        let mut seq = self.serialize_seq(Some(len))?;
+        seq.variant = Some(variant);
+        Ok(seq)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap> {
+        Ok(SerializeMap {
+            ser: self,
+            ht: ZendHashTable::with_capacity(len.unwrap_or(0) as u32),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<SerializeStruct> {
+        let fields = if self.structs_as_objects {
+            Fields::Object(ZendObject::new_stdclass())
+        } else {
+            Fields::Array(ZendHashTable::with_capacity(len as u32))
+        };
+        Ok(SerializeStruct {
+            ser: self,
+            fields,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStruct> {
+        let mut st = self.serialize_struct(name, len)?;
+        st.variant = Some(variant);
+        Ok(st)
+    }
+}
+
+/// Wraps the value of an enum variant in an array keyed by the variant name.
+fn tagged(variant: &str, value: Zval) -> Result<Zval> {
+    let mut ht = ZendHashTable::new();
+    ht.insert(variant, value).map_err(Error::new)?;
+    Ok(array(ht))
+}
+
+fn array(ht: ZBox<ZendHashTable>) -> Zval {
+    let mut zv = Zval::new();
+    zv.set_hashtable(ht);
+    zv
+}
+
+/// Adds the variant name, if any, to the path of an error.
+fn in_variant(err: Error, variant: Option<&'static str>) -> Error {
+    match variant {
+        Some(variant) => err.at(Segment::Key(variant.into())),
+        None => err,
+    }
+}
+
+/// Serializes sequences, tuples and tuple variants into packed arrays.
+///
+/// Returned from [`Serializer::serialize_seq`](ser::Serializer::serialize_seq).
+pub struct SerializeArray {
+    ser: Serializer,
+    ht: ZBox<ZendHashTable>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let index = self.ht.len();
+        let value = value
+            .serialize(self.ser)
+            .map_err(|e| in_variant(e.at(Segment::Index(index)), self.variant))?;
+        self.ht.push(value).map_err(Error::new)
+    }
+
+    fn finish(self) -> Result<Zval> {
+        match self.variant {
+            Some(variant) => tagged(variant, array(self.ht)),
+            None => Ok(array(self.ht)),
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+/// Serializes maps into associative arrays.
+///
+/// Returned from [`Serializer::serialize_map`](ser::Serializer::serialize_map).
+pub struct SerializeMap {
+    ser: Serializer,
+    ht: ZBox<ZendHashTable>,
+    key: Option<ArrayKey>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        let key = key.serialize(self.ser)?;
+        self.key = Some(array_key(&key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::new("map value serialized before its key"))?;
+        let value = value
+            .serialize(self.ser)
+            .map_err(|e| e.at(Segment::Key(key.to_string())))?;
+        match key {
+            ArrayKey::Long(index) => self.ht.insert_at_index(index as u64, value),
+            ArrayKey::String(key) => self.ht.insert(&key, value),
+        }
+        .map_err(Error::new)
+    }
+
+    fn end(self) -> Result<Zval> {
+        Ok(array(self.ht))
+    }
+}
+
+/// Converts a serialized map key into an array key. Integer-like strings are
+/// converted into integer keys, as PHP does for array literals.
+#[allow(clippy::useless_conversion)]
+fn array_key(key: &Zval) -> Result<ArrayKey> {
+    if let Some(key) = key.long() {
+        return Ok(ArrayKey::Long(key.into()));
+    }
+    if let Some(key) = key.bool() {
+        return Ok(ArrayKey::Long(key.into()));
+    }
+    if let Some(key) = key.str() {
+        return Ok(match key.parse::<i64>() {
+            Ok(index) if index.to_string() == key => ArrayKey::Long(index),
+            _ => ArrayKey::String(key.into()),
+        });
+    }
+    Err(Error::new(format!(
+        "map keys must be strings or integers, {} given",
+        key.get_type()
+    )))
+}
+
+/// Serializes structs and struct variants into associative arrays or
+/// `stdClass` objects.
+///
+/// Returned from [`Serializer::serialize_struct`](ser::Serializer::serialize_struct).
+pub struct SerializeStruct {
+    ser: Serializer,
+    fields: Fields,
+    variant: Option<&'static str>,
+}
+
+enum Fields {
+    Array(ZBox<ZendHashTable>),
+    Object(ZBox<ZendObject>),
+}
+
+impl SerializeStruct {
+    fn field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        let value = value
+            .serialize(self.ser)
+            .map_err(|e| in_variant(e.at(Segment::Key(key.into())), self.variant))?;
+        match &mut self.fields {
+            Fields::Array(ht) => ht.insert(key, value),
+            Fields::Object(obj) => obj.set_property(key, value),
+        }
+        .map_err(Error::new)
+    }
+
+    fn finish(self) -> Result<Zval> {
+        let value = match self.fields {
+            Fields::Array(ht) => array(ht),
+            Fields::Object(obj) => obj.into_zval(false).map_err(Error::new)?,
+        };
+        match self.variant {
+            Some(variant) => tagged(variant, value),
+            None => Ok(value),
+        }
+    }
+}
+
+impl ser::SerializeStruct for SerializeStruct {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeStruct {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+/// A deserializer which converts [`Zval`]s into Rust values.
+pub struct Deserializer<'de> {
+    zval: &'de Zval,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates a new deserializer reading from the given zval. References are
+    /// followed.
+    ///
+    /// # Parameters
+    ///
+    /// * `zval` - The zval to deserialize.
+    pub fn new(zval: &'de Zval) -> Self {
+        Self {
+            zval: zval.dereference().dereference(),
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self.zval.get_type(), DataType::Null | DataType::Undef)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    #[allow(clippy::useless_conversion)]
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let zval = self.zval;
+        if self.is_null() {
+            visitor.visit_unit()
+        } else if let Some(val) = zval.bool() {
+            visitor.visit_bool(val)
+        } else if let Some(val) = zval.long() {
+            visitor.visit_i64(val.into())
+        } else if let Some(val) = zval.double() {
+            visitor.visit_f64(val)
+        } else if let Some(val) = zval.zend_str() {
+            match val.as_str() {
+                Ok(val) => visitor.visit_borrowed_str(val),
+                Err(_) => visitor.visit_borrowed_bytes(val.as_bytes()),
+            }
+        } else if let Some(ht) = zval.array() {
+            if ht.has_sequential_keys() {
+                visitor.visit_seq(SeqDeserializer::new(ht.values()))
+            } else {
+                visitor.visit_map(MapDeserializer::new(ht.iter()))
+            }
+        } else if let Some(obj) = zval.object() {
+            let props = obj.get_properties().map_err(Error::new)?;
+            visitor.visit_map(MapDeserializer::new(props.iter()))
+        } else {
+            Err(Error::new(format!(
+                "cannot deserialize a value of type {}",
+                zval.get_type()
+            )))
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.zval.zend_str() {
+            Some(val) => visitor.visit_borrowed_bytes(val.as_bytes()),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.zval.array() {
+            Some(ht) => visitor.visit_seq(SeqDeserializer::new(ht.values())),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.zval.array() {
+            Some(ht) => visitor.visit_map(MapDeserializer::new(ht.iter())),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        if let Some(variant) = self.zval.str() {
+            return visitor.visit_enum(variant.into_deserializer());
+        }
+        if let Some(ht) = self.zval.array() {
+            if let (1, Some((ArrayKey::String(variant), value))) = (ht.len(), ht.iter().next()) {
+                return visitor.visit_enum(EnumDeserializer { variant, value });
+            }
+        }
+        Err(Error::new(format!(
+            "expected a string or an array with a single string key, {} given",
+            self.zval.get_type()
+        )))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct identifier
+    }
+}
+
+struct SeqDeserializer<I> {
+    values: I,
+    index: usize,
+}
+
+impl<I> SeqDeserializer<I> {
+    fn new(values: I) -> Self {
+        Self { values, index: 0 }
+    }
+}
+
+impl<'de, I: Iterator<Item = &'de Zval>> SeqAccess<'de> for SeqDeserializer<I> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        let value = match self.values.next() {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let index = self.index;
+        self.index += 1;
+        seed.deserialize(Deserializer::new(value))
+            .map(Some)
+            .map_err(|e| e.at(Segment::Index(index)))
+    }
+}
+
+struct MapDeserializer<'de, I> {
+    entries: I,
+    value: Option<(String, &'de Zval)>,
+}
+
+impl<'de, I> MapDeserializer<'de, I> {
+    fn new(entries: I) -> Self {
+        Self {
+            entries,
+            value: None,
+        }
+    }
+}
+
+impl<'de, I: Iterator<Item = (ArrayKey, &'de Zval)>> MapAccess<'de> for MapDeserializer<'de, I> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        for (key, value) in &mut self.entries {
+            // Skip uninitialized typed properties.
+            if value.dereference().get_type() == DataType::Undef {
+                continue;
+            }
+            // Private and protected property names are prefixed with the scope, separated
+            // by NUL bytes.
+            let key = match key {
+                ArrayKey::String(key) if key.starts_with('\0') => {
+                    ArrayKey::String(key.rsplit('\0').next().unwrap_or_default().into())
+                }
+                key => key,
+            };
+            let name = key.to_string();
+            let key = seed
+                .deserialize(KeyDeserializer(key))
+                .map_err(|e| e.at(Segment::Key(name.clone())))?;
+            self.value = Some((name, value));
+            return Ok(Some(key));
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let (key, value) = self
+            .value
+            .take()
+            .ok_or_else(|| Error::new("map value requested before its key"))?;
+        seed.deserialize(Deserializer::new(value))
+            .map_err(|e| e.at(Segment::Key(key)))
+    }
+}
+
+/// Deserializes array keys. Integer keys can be deserialized as strings, as
+/// PHP converts integer-like string keys into integers.
+struct KeyDeserializer(ArrayKey);
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            ArrayKey::Long(key) => visitor.visit_i64(key),
+            ArrayKey::String(key) => visitor.visit_string(key),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(self.0.to_string())
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char bytes byte_buf
+        option unit unit_struct seq tuple tuple_struct map struct enum ignored_any
+    }
+}
+
+struct EnumDeserializer<'de> {
+    variant: String,
+    value: &'de Zval,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let variant = seed.deserialize(self.variant.as_str().into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EnumDeserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Deserialize::deserialize(Deserializer::new(self.value))
+            .map_err(|e: Error| e.at(Segment::Key(self.variant)))
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(Deserializer::new(self.value))
+            .map_err(|e| e.at(Segment::Key(self.variant)))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(Deserializer::new(self.value), visitor)
+            .map_err(|e| e.at(Segment::Key(self.variant)))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(Deserializer::new(self.value), visitor)
+            .map_err(|e| e.at(Segment::Key(self.variant)))
+    }
+}
@@ -2,27 +2,39 @@
 //! they are represented by hash tables.
 
 use std::{
+    cell::Cell,
+    cmp::Ordering,
     collections::HashMap,
     convert::{TryFrom, TryInto},
-    ffi::CString,
+    ffi::{c_void, CString},
     fmt::{Debug, Display},
     iter::FromIterator,
+    os::raw::c_int,
+    ptr,
 };
 
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+#[cfg(feature = "indexmap")]
+use std::hash::Hash;
+
 use crate::{
     boxed::{ZBox, ZBoxable},
-    convert::{FromZval, IntoZval},
+    convert::{FromZval, FromZvalMut, IntoZval},
     error::{Error, Result},
     ffi::{
-        _zend_new_array, zend_array_count, zend_array_destroy, zend_array_dup, zend_hash_clean,
-        zend_hash_get_current_data_ex, zend_hash_get_current_key_type_ex,
+        _zend_new_array, ext_php_rs_persistent_array_free, ext_php_rs_zend_hash_renumber,
+        ext_php_rs_zend_new_persistent_array, zend_array_count, zend_array_destroy, zend_array_dup,
+        zend_hash_clean, zend_hash_get_current_data_ex, zend_hash_get_current_key_type_ex,
         zend_hash_get_current_key_zval_ex, zend_hash_index_del, zend_hash_index_find,
-        zend_hash_index_update, zend_hash_move_backwards_ex, zend_hash_move_forward_ex,
-        zend_hash_next_index_insert, zend_hash_str_del, zend_hash_str_find, zend_hash_str_update,
-        HashPosition, HT_MIN_SIZE,
+        zend_hash_index_update, zend_hash_internal_pointer_end_ex, zend_hash_move_backwards_ex,
+        zend_hash_move_forward_ex, zend_hash_next_index_insert, zend_hash_sort_ex,
+        zend_hash_str_del, zend_hash_str_find, zend_hash_str_update, zend_sort, Bucket,
+        HashPosition, GC_PERSISTENT, HT_MIN_SIZE,
     },
     flags::DataType,
-    types::Zval,
+    rc::ZRc,
+    types::{ZendStr, Zval},
 };
 
 /// A PHP hashtable.
@@ -102,6 +114,76 @@ impl ZendHashTable {
         }
     }
 
+    /// Creates a new, empty, persistent PHP hashtable, returned inside a
+    /// [`ZBox`].
+    ///
+    /// Persistent hashtables are allocated with `pemalloc` and live until they
+    /// are dropped, surviving the end of the request. Values inserted into
+    /// the hashtable are converted into persistent values, and may only be
+    /// scalars, strings and other persistent hashtables.
+    ///
+    /// Persistent hashtables are usually built during module startup and
+    /// shared with requests through a [`PersistentZval`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new_persistent();
+    /// ht.insert("name", "ext-php-rs").unwrap();
+    /// assert!(ht.is_persistent());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the hashtable could not be allocated.
+    ///
+    /// [`PersistentZval`]: crate::persistent::PersistentZval
+    pub fn new_persistent() -> ZBox<Self> {
+        Self::with_capacity_persistent(HT_MIN_SIZE)
+    }
+
+    /// Creates a new, empty, persistent PHP hashtable with an initial size,
+    /// returned inside a [`ZBox`]. See [`new_persistent`] for details.
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to initialize the array with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the hashtable could not be allocated.
+    ///
+    /// [`new_persistent`]: ZendHashTable::new_persistent
+    pub fn with_capacity_persistent(size: u32) -> ZBox<Self> {
+        unsafe {
+            let ptr = ext_php_rs_zend_new_persistent_array(size);
+            ZBox::from_raw(
+                ptr.as_mut()
+                    .expect("Failed to allocate memory for hashtable"),
+            )
+        }
+    }
+
+    /// Creates a new hashtable with an initial size, which is persistent if
+    /// requested.
+    fn with_capacity_ex(size: usize, persistent: bool) -> Result<ZBox<Self>> {
+        let size = size.try_into().map_err(|_| Error::IntegerOverflow)?;
+        Ok(if persistent {
+            Self::with_capacity_persistent(size)
+        } else {
+            Self::with_capacity(size)
+        })
+    }
+
+    /// Returns true if the hashtable was allocated as persistent, and
+    /// survives the end of the request.
+    pub fn is_persistent(&self) -> bool {
+        // SAFETY: `type_info` is the only field of the union.
+        unsafe { self.gc.u.type_info & GC_PERSISTENT != 0 }
+    }
+
     /// Returns the current number of elements in the array.
     ///
     /// # Example
@@ -265,6 +347,56 @@ impl ZendHashTable {
         unsafe { zend_hash_index_find(self, key).as_mut() }
     }
 
+    /// Attempts to retrieve a value from the hash table with an [`ArrayKey`],
+    /// the same way as PHP's `$array[$key]`.
+    ///
+    /// Unlike [`get`], string keys containing a canonical integer, such as
+    /// `"5"` or `"-1"` but not `"05"`, refer to integer keys.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to search for in the hash table.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&Zval)` - A reference to the zval at the position in the hash
+    ///   table.
+    /// * `None` - No value at the given position was found.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.insert_key(-1, "negative");
+    /// assert_eq!(ht.get_key("-1").and_then(|zv| zv.str()), Some("negative"));
+    /// ```
+    ///
+    /// [`get`]: ZendHashTable::get
+    pub fn get_key<'k>(&self, key: impl Into<ArrayKeyRef<'k>>) -> Option<&Zval> {
+        unsafe { self.find(key.into().raw()).as_ref() }
+    }
+
+    /// Attempts to retrieve a value from the hash table with an [`ArrayKey`],
+    /// the same way as PHP's `$array[$key]`. See [`get_key`] for details.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to search for in the hash table.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&mut Zval)` - A reference to the zval at the position in the
+    ///   hash table.
+    /// * `None` - No value at the given position was found.
+    ///
+    /// [`get_key`]: ZendHashTable::get_key
+    pub fn get_key_mut<'k>(&mut self, key: impl Into<ArrayKeyRef<'k>>) -> Option<&mut Zval> {
+        unsafe { self.find(key.into().raw()).as_mut() }
+    }
+
     /// Attempts to remove a value from the hash table with a string key.
     ///
     /// # Parameters
@@ -334,6 +466,40 @@ impl ZendHashTable {
         }
     }
 
+    /// Attempts to remove a value from the hash table with an [`ArrayKey`],
+    /// the same way as PHP's `unset($array[$key])`. See [`get_key`] for how
+    /// string keys are handled.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to remove from the hash table.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(())` - Key was successfully removed.
+    /// * `None` - No key was removed, did not exist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push("hello");
+    /// ht.remove_key("0");
+    /// assert_eq!(ht.len(), 0);
+    /// ```
+    ///
+    /// [`get_key`]: ZendHashTable::get_key
+    pub fn remove_key<'k>(&mut self, key: impl Into<ArrayKeyRef<'k>>) -> Option<()> {
+        if self.delete(key.into().raw()) {
+            Some(())
+        } else {
+            None
+        }
+    }
+
     /// Attempts to insert an item into the hash table, or update if the key
     /// already exists. Returns nothing in a result if successful.
     ///
@@ -364,12 +530,52 @@ impl ZendHashTable {
     where
         V: IntoZval,
     {
-        let mut val = val.into_zval(false)?;
+        let mut val = val.into_zval(self.is_persistent())?;
         unsafe { zend_hash_str_update(self, CString::new(key)?.as_ptr(), key.len(), &mut val) };
         val.release();
         Ok(())
     }
 
+    /// Attempts to insert an item into the hash table with an [`ArrayKey`], or
+    /// update if the key already exists, the same way as PHP's
+    /// `$array[$key] = $value`. Returns nothing in a result if successful.
+    ///
+    /// Unlike [`insert`], string keys containing a canonical integer, such as
+    /// `"5"` or `"-1"` but not `"05"`, are stored as integer keys.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to insert the value at in the hash table.
+    /// * `value` - The value to insert into the hash table.
+    ///
+    /// # Returns
+    ///
+    /// Returns nothing in a result on success. Returns an error if converting
+    /// the value into a [`Zval`] failed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.insert_key(-1, "A");
+    /// ht.insert_key("5", "B");
+    /// assert!(ht.has_numerical_keys());
+    /// ```
+    ///
+    /// [`insert`]: ZendHashTable::insert
+    pub fn insert_key<'k, K, V>(&mut self, key: K, val: V) -> Result<()>
+    where
+        K: Into<ArrayKeyRef<'k>>,
+        V: IntoZval,
+    {
+        let val = val.into_zval(self.is_persistent())?;
+        self.update(key.into().raw(), val);
+        Ok(())
+    }
+
     /// Inserts an item into the hash table at a specified index, or updates if
     /// the key already exists. Returns nothing in a result if successful.
     ///
@@ -399,7 +605,7 @@ impl ZendHashTable {
     where
         V: IntoZval,
     {
-        let mut val = val.into_zval(false)?;
+        let mut val = val.into_zval(self.is_persistent())?;
         unsafe { zend_hash_index_update(self, key, &mut val) };
         val.release();
         Ok(())
@@ -433,13 +639,274 @@ impl ZendHashTable {
     where
         V: IntoZval,
     {
-        let mut val = val.into_zval(false)?;
+        let mut val = val.into_zval(self.is_persistent())?;
         unsafe { zend_hash_next_index_insert(self, &mut val) };
         val.release();
 
         Ok(())
     }
 
+    /// Returns true if the hash table contains a value for the given key. See
+    /// [`get_key`] for how string keys are handled.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to search for in the hash table.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.insert("a", "A");
+    /// assert!(ht.contains_key("a"));
+    /// assert!(!ht.contains_key(0));
+    /// ```
+    ///
+    /// [`get_key`]: ZendHashTable::get_key
+    pub fn contains_key<'k>(&self, key: impl Into<ArrayKeyRef<'k>>) -> bool {
+        !self.find(key.into().raw()).is_null()
+    }
+
+    /// Returns an entry for the given key, which can be used to insert a value
+    /// if the key is not present. See [`get_key`] for how string keys are
+    /// handled.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the entry.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// let hits = ht.entry("hits").or_insert(0).unwrap();
+    /// hits.set_long(hits.long().unwrap_or_default() + 1);
+    /// assert_eq!(ht.get("hits").and_then(|zv| zv.long()), Some(1));
+    /// ```
+    ///
+    /// [`get_key`]: ZendHashTable::get_key
+    pub fn entry<'k>(&mut self, key: impl Into<ArrayKeyRef<'k>>) -> Entry<'_, 'k> {
+        Entry {
+            ht: self,
+            key: key.into(),
+        }
+    }
+
+    /// Removes all elements for which the given function returns false. The
+    /// function is called once for each element, in order.
+    ///
+    /// # Parameters
+    ///
+    /// * `f` - Function called with the key and value of each element.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(1);
+    /// ht.push(2);
+    /// ht.push(3);
+    /// ht.retain(|_, val| val.long() != Some(2));
+    /// assert_eq!(ht.len(), 2);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&ArrayKey, &mut Zval) -> bool,
+    {
+        let mut removed = vec![];
+        let mut iter = self.iter();
+        while let Some((key, _)) = iter.next_zval() {
+            // SAFETY: Elements are only modified in place, the hashtable itself is not
+            // modified while iterating.
+            let val = unsafe { self.find(RawKey::from_engine(&key)).as_mut() };
+            let retained = match (ArrayKey::from_engine(&key), val) {
+                (Some(array_key), Some(val)) => f(&array_key, val),
+                _ => true,
+            };
+            if !retained {
+                removed.push(key);
+            }
+        }
+        for key in removed {
+            self.delete(RawKey::from_engine(&key));
+        }
+    }
+
+    /// Inserts all key-value pairs of an iterator into the hash table,
+    /// updating the values of existing keys. See [`insert_key`] for how
+    /// string keys are handled.
+    ///
+    /// # Parameters
+    ///
+    /// * `iter` - The key-value pairs to insert.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if converting one of the values into a [`Zval`]
+    /// failed. Values before the failing value are still inserted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.extend([("a", 1), ("b", 2)]);
+    /// assert_eq!(ht.len(), 2);
+    /// ```
+    ///
+    /// [`insert_key`]: ZendHashTable::insert_key
+    pub fn extend<I, K, V>(&mut self, iter: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<ArrayKey>,
+        V: IntoZval,
+    {
+        for (key, val) in iter {
+            self.insert_key(&key.into(), val)?;
+        }
+        Ok(())
+    }
+
+    /// Appends the elements of another hash table, the same way as PHP's
+    /// `array_merge()`: elements with integer keys are pushed onto the end of
+    /// the hash table, while elements with string keys overwrite existing
+    /// values with the same key.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The hash table to append.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.push("a");
+    ///
+    /// let mut other = ZendHashTable::new();
+    /// other.push("b");
+    ///
+    /// ht.append(&other);
+    /// assert_eq!(ht.get_index(1).and_then(|zv| zv.str()), Some("b"));
+    /// ```
+    pub fn append(&mut self, other: &ZendHashTable) {
+        let mut iter = other.iter();
+        while let Some((key, val)) = iter.next_zval() {
+            let val = val.shallow_clone();
+            match RawKey::from_engine(&key) {
+                // Pushing a zval cannot fail.
+                RawKey::Index(_) => {
+                    let _ = self.push(val);
+                }
+                key => self.update(key, val),
+            }
+        }
+    }
+
+    /// Removes the last element of the hash table and returns it, the same way
+    /// as PHP's `array_pop()`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(1);
+    /// ht.push(2);
+    /// assert_eq!(ht.pop().and_then(|zv| zv.long()), Some(2));
+    /// assert_eq!(ht.len(), 1);
+    /// ```
+    pub fn pop(&mut self) -> Option<Zval> {
+        let mut pos: HashPosition = 0;
+        unsafe { zend_hash_internal_pointer_end_ex(self, &mut pos) };
+        let (key, val) = Iter {
+            ht: self,
+            current_num: 0,
+            pos,
+        }
+        .next_zval()?;
+        let val = val.shallow_clone();
+        self.delete(RawKey::from_engine(&key));
+        // Like `array_pop()`, allow the index of the removed element to be reused.
+        if let Some(index) = key.long() {
+            if index == self.nNextFreeElement.wrapping_sub(1) {
+                self.nNextFreeElement = index;
+            }
+        }
+        Some(val)
+    }
+
+    /// Removes the first element of the hash table and returns it, the same
+    /// way as PHP's `array_shift()`. Integer keys are renumbered starting from
+    /// zero, while string keys are left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(1);
+    /// ht.push(2);
+    /// assert_eq!(ht.shift().and_then(|zv| zv.long()), Some(1));
+    /// assert_eq!(ht.get_index(0).and_then(|zv| zv.long()), Some(2));
+    /// ```
+    pub fn shift(&mut self) -> Option<Zval> {
+        let (key, val) = self
+            .iter()
+            .next_zval()
+            .map(|(key, val)| (key, val.shallow_clone()))?;
+        self.delete(RawKey::from_engine(&key));
+        unsafe { ext_php_rs_zend_hash_renumber(self) };
+        Some(val)
+    }
+
+    /// Sorts the elements of the hash table in place with a comparison
+    /// function, preserving the key of each element, the same way as PHP's
+    /// `uasort()`. The sort is stable.
+    ///
+    /// # Parameters
+    ///
+    /// * `compare` - Function used to compare two values.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(2);
+    /// ht.push(1);
+    /// ht.sort_by(|a, b| a.long().cmp(&b.long()));
+    /// assert_eq!(ht.values().next().and_then(|zv| zv.long()), Some(1));
+    /// ```
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Zval, &Zval) -> Ordering,
+    {
+        let mut compare: &mut dyn FnMut(&Zval, &Zval) -> Ordering = &mut compare;
+        let previous =
+            SORT_COMPARE.with(|cell| cell.replace(&mut compare as *mut _ as *mut c_void));
+        unsafe { zend_hash_sort_ex(self, Some(zend_sort), Some(compare_buckets), false) };
+        SORT_COMPARE.with(|cell| cell.set(previous));
+    }
+
     /// Checks if the hashtable only contains numerical keys.
     ///
     /// # Returns
@@ -535,12 +1002,130 @@ impl ZendHashTable {
     pub fn iter(&self) -> Iter {
         self.into_iter()
     }
+
+    fn find(&self, key: RawKey) -> *mut Zval {
+        unsafe {
+            match key {
+                RawKey::Index(index) => zend_hash_index_find(self, index),
+                RawKey::Str(key) => zend_hash_str_find(self, key.as_ptr().cast(), key.len() as _),
+            }
+        }
+    }
+
+    fn update(&mut self, key: RawKey, mut val: Zval) {
+        unsafe {
+            match key {
+                RawKey::Index(index) => zend_hash_index_update(self, index, &mut val),
+                RawKey::Str(key) => {
+                    zend_hash_str_update(self, key.as_ptr().cast(), key.len() as _, &mut val)
+                }
+            };
+        }
+        val.release();
+    }
+
+    fn delete(&mut self, key: RawKey) -> bool {
+        let result = unsafe {
+            match key {
+                RawKey::Index(index) => zend_hash_index_del(self, index),
+                RawKey::Str(key) => zend_hash_str_del(self, key.as_ptr().cast(), key.len() as _),
+            }
+        };
+        result >= 0
+    }
+}
+
+thread_local! {
+    /// The comparison function of the innermost running
+    /// [`ZendHashTable::sort_by`] call.
+    static SORT_COMPARE: Cell<*mut c_void> = const { Cell::new(ptr::null_mut()) };
+}
+
+/// Compares two buckets with the comparison function of the running sort,
+/// falling back to the original order of the elements to keep the sort
+/// stable.
+unsafe extern "C" fn compare_buckets(a: *mut Bucket, b: *mut Bucket) -> c_int {
+    let compare = SORT_COMPARE.with(Cell::get) as *mut &mut dyn FnMut(&Zval, &Zval) -> Ordering;
+    let (a, b) = (&(*a).val, &(*b).val);
+    // `zend_hash_sort_ex()` stores the original position of each element in the
+    // `extra` field of its zval.
+    (*compare)(a, b).then_with(|| a.u2.extra.cmp(&b.u2.extra)) as c_int
+}
+
+/// An entry in a [`ZendHashTable`], returned from [`ZendHashTable::entry`].
+pub struct Entry<'a, 'k> {
+    ht: &'a mut ZendHashTable,
+    key: ArrayKeyRef<'k>,
+}
+
+impl<'a, 'k> Entry<'a, 'k> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &ArrayKeyRef<'k> {
+        &self.key
+    }
+
+    /// Returns the value of the entry, inserting the given value first if the
+    /// key is not present.
+    ///
+    /// # Parameters
+    ///
+    /// * `default` - The value to insert if the key is not present.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if converting the value into a [`Zval`] failed.
+    pub fn or_insert<V: IntoZval>(self, default: V) -> Result<&'a mut Zval> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns the value of the entry, inserting the value returned by the
+    /// given function first if the key is not present.
+    ///
+    /// # Parameters
+    ///
+    /// * `default` - Function returning the value to insert, only called if
+    ///   the key is not present.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if converting the value into a [`Zval`] failed.
+    pub fn or_insert_with<V, F>(self, default: F) -> Result<&'a mut Zval>
+    where
+        V: IntoZval,
+        F: FnOnce() -> V,
+    {
+        let key = self.key.raw();
+        if self.ht.find(key).is_null() {
+            let val = default().into_zval(self.ht.is_persistent())?;
+            self.ht.update(key, val);
+        }
+        unsafe { self.ht.find(key).as_mut() }.ok_or(Error::InvalidPointer)
+    }
+
+    /// Calls the given function with the value of the entry, if the key is
+    /// present.
+    ///
+    /// # Parameters
+    ///
+    /// * `f` - Function called with the value of the entry.
+    pub fn and_modify<F: FnOnce(&mut Zval)>(self, f: F) -> Self {
+        if let Some(val) = unsafe { self.ht.find(self.key.raw()).as_mut() } {
+            f(val);
+        }
+        self
+    }
 }
 
 unsafe impl ZBoxable for ZendHashTable {
     fn free(&mut self) {
         // SAFETY: ZBox has immutable access to `self`.
-        unsafe { zend_array_destroy(self) }
+        unsafe {
+            if self.is_persistent() {
+                ext_php_rs_persistent_array_free(self)
+            } else {
+                zend_array_destroy(self)
+            }
+        }
     }
 }
 
@@ -576,7 +1161,13 @@ pub struct Iter<'a> {
     pos: HashPosition,
 }
 
-#[derive(Debug, PartialEq)]
+/// The key of an element in a PHP array, which can be either an integer or a
+/// string.
+///
+/// String keys which are not valid UTF-8 are converted lossily when iterating
+/// over a hashtable. Use [`ArrayKeyRef`] to access elements with binary-safe
+/// keys.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ArrayKey {
     Long(i64),
     String(String),
@@ -595,6 +1186,17 @@ impl ArrayKey {
             ArrayKey::String(_) => false,
         }
     }
+
+    /// Converts a key returned by the Zend engine into an [`ArrayKey`].
+    fn from_engine(key: &Zval) -> Option<Self> {
+        if let Some(key) = key.long() {
+            return Some(ArrayKey::Long(key));
+        }
+        let key = key.zend_str()?;
+        Some(ArrayKey::String(
+            String::from_utf8_lossy(key.as_bytes()).into_owned(),
+        ))
+    }
 }
 
 impl Display for ArrayKey {
@@ -606,6 +1208,44 @@ impl Display for ArrayKey {
     }
 }
 
+impl From<i64> for ArrayKey {
+    fn from(key: i64) -> Self {
+        ArrayKey::Long(key)
+    }
+}
+
+impl From<String> for ArrayKey {
+    fn from(key: String) -> Self {
+        ArrayKey::String(key)
+    }
+}
+
+impl From<&str> for ArrayKey {
+    fn from(key: &str) -> Self {
+        ArrayKey::String(key.to_owned())
+    }
+}
+
+impl From<ArrayKey> for String {
+    fn from(key: ArrayKey) -> Self {
+        match key {
+            ArrayKey::Long(key) => key.to_string(),
+            ArrayKey::String(key) => key,
+        }
+    }
+}
+
+impl TryFrom<ArrayKey> for i64 {
+    type Error = Error;
+
+    fn try_from(key: ArrayKey) -> Result<Self> {
+        match key {
+            ArrayKey::Long(key) => Ok(key),
+            ArrayKey::String(_) => Err(Error::ZvalConversion(DataType::String)),
+        }
+    }
+}
+
 impl<'a> FromZval<'a> for ArrayKey {
     const TYPE: DataType = DataType::String;
 
@@ -620,6 +1260,104 @@ impl<'a> FromZval<'a> for ArrayKey {
     }
 }
 
+/// A borrowed key used to access the elements of a PHP array with PHP's own
+/// array access rules, as taken by [`ZendHashTable::get_key`] and related
+/// methods.
+///
+/// String keys containing a canonical integer, such as `"5"` or `"-1"` but not
+/// `"05"`, refer to integer keys. String keys are binary-safe, and do not need
+/// to be valid UTF-8.
+#[derive(Debug, Clone, Copy)]
+pub enum ArrayKeyRef<'a> {
+    /// An integer key.
+    Long(i64),
+    /// A string key.
+    Str(&'a [u8]),
+}
+
+impl<'a> ArrayKeyRef<'a> {
+    /// Returns the key the way PHP stores it, converting integer-like strings
+    /// into integers.
+    fn raw(&self) -> RawKey<'a> {
+        match *self {
+            ArrayKeyRef::Long(key) => RawKey::Index(key as u64),
+            ArrayKeyRef::Str(key) => match numeric_key(key) {
+                Some(index) => RawKey::Index(index as u64),
+                None => RawKey::Str(key),
+            },
+        }
+    }
+}
+
+impl From<i64> for ArrayKeyRef<'_> {
+    fn from(key: i64) -> Self {
+        ArrayKeyRef::Long(key)
+    }
+}
+
+impl<'a> From<&'a str> for ArrayKeyRef<'a> {
+    fn from(key: &'a str) -> Self {
+        ArrayKeyRef::Str(key.as_bytes())
+    }
+}
+
+impl<'a> From<&'a String> for ArrayKeyRef<'a> {
+    fn from(key: &'a String) -> Self {
+        ArrayKeyRef::Str(key.as_bytes())
+    }
+}
+
+impl<'a> From<&'a [u8]> for ArrayKeyRef<'a> {
+    fn from(key: &'a [u8]) -> Self {
+        ArrayKeyRef::Str(key)
+    }
+}
+
+impl<'a> From<&'a ZendStr> for ArrayKeyRef<'a> {
+    fn from(key: &'a ZendStr) -> Self {
+        ArrayKeyRef::Str(key.as_bytes())
+    }
+}
+
+impl<'a> From<&'a ArrayKey> for ArrayKeyRef<'a> {
+    fn from(key: &'a ArrayKey) -> Self {
+        match key {
+            ArrayKey::Long(key) => ArrayKeyRef::Long(*key),
+            ArrayKey::String(key) => ArrayKeyRef::Str(key.as_bytes()),
+        }
+    }
+}
+
+/// Returns the integer represented by a string key, if PHP would store it as
+/// an integer key.
+fn numeric_key(key: &[u8]) -> Option<i64> {
+    let digits = key.strip_prefix(b"-").unwrap_or(key);
+    if digits.is_empty()
+        || !digits.iter().all(u8::is_ascii_digit)
+        || (digits[0] == b'0' && key.len() > 1)
+    {
+        return None;
+    }
+    std::str::from_utf8(key).ok()?.parse().ok()
+}
+
+/// A key in the form it is stored in a hashtable.
+#[derive(Clone, Copy)]
+enum RawKey<'a> {
+    Index(u64),
+    Str(&'a [u8]),
+}
+
+impl<'a> RawKey<'a> {
+    /// Returns the exact key returned by the Zend engine.
+    fn from_engine(key: &'a Zval) -> Self {
+        match key.zend_str() {
+            Some(key) => RawKey::Str(key.as_bytes()),
+            None => RawKey::Index(key.long().unwrap_or_default() as u64),
+        }
+    }
+}
+
 impl<'a> Iter<'a> {
     /// Creates a new iterator over a hashtable.
     ///
@@ -667,7 +1405,7 @@ impl<'a> Iterator for Iter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next_zval()
-            .map(|(k, v)| (ArrayKey::from_zval(&k).expect("Invalid array key!"), v))
+            .map(|(k, v)| (ArrayKey::from_engine(&k).expect("Invalid array key!"), v))
     }
 
     fn count(self) -> usize
@@ -713,7 +1451,7 @@ impl DoubleEndedIterator for Iter<'_> {
             )
         };
 
-        let key = match ArrayKey::from_zval(&key) {
+        let key = match ArrayKey::from_engine(&key) {
             Some(key) => key,
             None => ArrayKey::Long(self.current_num),
         };
@@ -846,6 +1584,31 @@ impl<'a> FromZval<'a> for &'a ZendHashTable {
     }
 }
 
+impl<'a> FromZvalMut<'a> for &'a mut ZendHashTable {
+    const TYPE: DataType = DataType::Array;
+
+    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
+        zval.array_mut()
+    }
+}
+
+impl IntoZval for ZRc<ZendHashTable> {
+    const TYPE: DataType = DataType::Array;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        zv.set_shared_hashtable(self);
+        Ok(())
+    }
+}
+
+impl FromZval<'_> for ZRc<ZendHashTable> {
+    const TYPE: DataType = DataType::Array;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.array().map(ZRc::from_ref)
+    }
+}
+
 ///////////////////////////////////////////
 // HashMap
 ///////////////////////////////////////////
@@ -878,15 +1641,7 @@ where
     type Error = Error;
 
     fn try_from(value: HashMap<K, V>) -> Result<Self> {
-        let mut ht = ZendHashTable::with_capacity(
-            value.len().try_into().map_err(|_| Error::IntegerOverflow)?,
-        );
-
-        for (k, v) in value.into_iter() {
-            ht.insert(k.as_ref(), v)?;
-        }
-
-        Ok(ht)
+        from_pairs(value.len(), value, false)
     }
 }
 
@@ -897,8 +1652,8 @@ where
 {
     const TYPE: DataType = DataType::Array;
 
-    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
-        let arr = self.try_into()?;
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        let arr = from_pairs(self.len(), self, persistent)?;
         zv.set_hashtable(arr);
         Ok(())
     }
@@ -915,6 +1670,106 @@ where
     }
 }
 
+/// Builds a hashtable from string keys and values, which is persistent if
+/// requested.
+fn from_pairs<K, V>(
+    len: usize,
+    pairs: impl IntoIterator<Item = (K, V)>,
+    persistent: bool,
+) -> Result<ZBox<ZendHashTable>>
+where
+    K: AsRef<str>,
+    V: IntoZval,
+{
+    let mut ht = ZendHashTable::with_capacity_ex(len, persistent)?;
+    for (k, v) in pairs {
+        ht.insert(k.as_ref(), v)?;
+    }
+    Ok(ht)
+}
+
+///////////////////////////////////////////
+// IndexMap
+///////////////////////////////////////////
+
+#[cfg(feature = "indexmap")]
+impl<'a, K, V> TryFrom<&'a ZendHashTable> for IndexMap<K, V>
+where
+    K: From<ArrayKey> + Hash + Eq,
+    V: FromZval<'a>,
+{
+    type Error = Error;
+
+    fn try_from(value: &'a ZendHashTable) -> Result<Self> {
+        let mut map = IndexMap::with_capacity(value.len());
+
+        for (key, val) in value {
+            map.insert(
+                key.into(),
+                V::from_zval(val).ok_or_else(|| Error::ZvalConversion(val.get_type()))?,
+            );
+        }
+
+        Ok(map)
+    }
+}
+
+/// Builds a hashtable from ordered keys and values, which is persistent if
+/// requested. Integer-like string keys are stored as integer keys.
+#[cfg(feature = "indexmap")]
+fn from_ordered_pairs<K, V>(map: IndexMap<K, V>, persistent: bool) -> Result<ZBox<ZendHashTable>>
+where
+    K: Into<ArrayKey>,
+    V: IntoZval,
+{
+    let mut ht = ZendHashTable::with_capacity_ex(map.len(), persistent)?;
+    for (k, v) in map {
+        ht.insert_key(&k.into(), v)?;
+    }
+    Ok(ht)
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, V> TryFrom<IndexMap<K, V>> for ZBox<ZendHashTable>
+where
+    K: Into<ArrayKey>,
+    V: IntoZval,
+{
+    type Error = Error;
+
+    fn try_from(value: IndexMap<K, V>) -> Result<Self> {
+        from_ordered_pairs(value, false)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, V> IntoZval for IndexMap<K, V>
+where
+    K: Into<ArrayKey>,
+    V: IntoZval,
+{
+    const TYPE: DataType = DataType::Array;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        let arr = from_ordered_pairs(self, persistent)?;
+        zv.set_hashtable(arr);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<'a, K, T> FromZval<'a> for IndexMap<K, T>
+where
+    K: From<ArrayKey> + Hash + Eq,
+    T: FromZval<'a>,
+{
+    const TYPE: DataType = DataType::Array;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.array().and_then(|arr| arr.try_into().ok())
+    }
+}
+
 ///////////////////////////////////////////
 // Vec
 ///////////////////////////////////////////
@@ -936,6 +1791,15 @@ where
     }
 }
 
+/// Builds a list from the given values, which is persistent if requested.
+fn from_values<T: IntoZval>(values: Vec<T>, persistent: bool) -> Result<ZBox<ZendHashTable>> {
+    let mut ht = ZendHashTable::with_capacity_ex(values.len(), persistent)?;
+    for val in values {
+        ht.push(val)?;
+    }
+    Ok(ht)
+}
+
 impl<T> TryFrom<Vec<T>> for ZBox<ZendHashTable>
 where
     T: IntoZval,
@@ -943,26 +1807,22 @@ where
     type Error = Error;
 
     fn try_from(value: Vec<T>) -> Result<Self> {
-        let mut ht = ZendHashTable::with_capacity(
-            value.len().try_into().map_err(|_| Error::IntegerOverflow)?,
-        );
-
-        for val in value.into_iter() {
-            ht.push(val)?;
-        }
-
-        Ok(ht)
+        from_values(value, false)
     }
 }
 
+/// Converts a vector into a PHP list. A `Vec<u8>` therefore becomes an array of
+/// integers, not a string; use [`Binary<u8>`] for byte strings.
+///
+/// [`Binary<u8>`]: crate::binary::Binary
 impl<T> IntoZval for Vec<T>
 where
     T: IntoZval,
 {
     const TYPE: DataType = DataType::Array;
 
-    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
-        let arr = self.try_into()?;
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        let arr = from_values(self, persistent)?;
         zv.set_hashtable(arr);
         Ok(())
     }
@@ -1003,6 +1863,18 @@ impl FromIterator<(u64, Zval)> for ZBox<ZendHashTable> {
     }
 }
 
+impl FromIterator<(ArrayKey, Zval)> for ZBox<ZendHashTable> {
+    fn from_iter<T: IntoIterator<Item = (ArrayKey, Zval)>>(iter: T) -> Self {
+        let mut ht = ZendHashTable::new();
+        for (key, val) in iter.into_iter() {
+            // Inserting a zval cannot fail, as `push` only returns `Err` if converting
+            // `val` to a zval fails.
+            let _ = ht.insert_key(&key, val);
+        }
+        ht
+    }
+}
+
 impl<'a> FromIterator<(&'a str, Zval)> for ZBox<ZendHashTable> {
     fn from_iter<T: IntoIterator<Item = (&'a str, Zval)>>(iter: T) -> Self {
         let mut ht = ZendHashTable::new();
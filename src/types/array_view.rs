@@ -0,0 +1,308 @@
+//! Borrowed, typed views of PHP arrays which convert elements lazily.
+
+use std::{convert::TryFrom, marker::PhantomData};
+
+use super::array::{Iter, Values};
+use crate::{
+    convert::FromZval,
+    error::{Error, Result},
+    flags::DataType,
+    types::{ArrayKey, ZendHashTable, Zval},
+};
+
+/// A borrowed view of a PHP array as a list of `T`.
+///
+/// Unlike converting into a [`Vec<T>`], no elements are converted when the
+/// view is created. Each element is converted when it is accessed, so reading
+/// a few elements of a large array is cheap. The keys of the array are
+/// ignored when iterating.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::prelude::*;
+/// use ext_php_rs::types::ArrayView;
+///
+/// #[php_function]
+/// pub fn first_positive(numbers: ArrayView<i64>) -> PhpResult<Option<i64>> {
+///     for number in numbers {
+///         let number = number?;
+///         if number > 0 {
+///             return Ok(Some(number));
+///         }
+///     }
+///     Ok(None)
+/// }
+/// ```
+pub struct ArrayView<'a, T> {
+    ht: &'a ZendHashTable,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> ArrayView<'a, T>
+where
+    T: FromZval<'a>,
+{
+    /// Creates a new view of the given hashtable.
+    ///
+    /// # Parameters
+    ///
+    /// * `ht` - The hashtable to view.
+    pub fn new(ht: &'a ZendHashTable) -> Self {
+        Self {
+            ht,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements in the array.
+    pub fn len(&self) -> usize {
+        self.ht.len()
+    }
+
+    /// Returns true if the array is empty.
+    pub fn is_empty(&self) -> bool {
+        self.ht.is_empty()
+    }
+
+    /// Converts and returns the element at the given index.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the element.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if there is no element at the given index, or an error
+    /// if the element could not be converted into `T`.
+    pub fn get(&self, index: u64) -> Result<Option<T>> {
+        self.ht.get_index(index).map(convert).transpose()
+    }
+
+    /// Returns an iterator over the elements of the array, converting each
+    /// element as it is reached.
+    pub fn iter(&self) -> ArrayViewIter<'a, T> {
+        ArrayViewIter {
+            values: self.ht.values(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the underlying hashtable.
+    pub fn hashtable(&self) -> &'a ZendHashTable {
+        self.ht
+    }
+}
+
+impl<T> Clone for ArrayView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArrayView<'_, T> {}
+
+impl<'a, T> IntoIterator for ArrayView<'a, T>
+where
+    T: FromZval<'a>,
+{
+    type Item = Result<T>;
+    type IntoIter = ArrayViewIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> FromZval<'a> for ArrayView<'a, T>
+where
+    T: FromZval<'a>,
+{
+    const TYPE: DataType = DataType::Array;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.array().map(Self::new)
+    }
+}
+
+/// Iterator over the elements of an [`ArrayView`].
+pub struct ArrayViewIter<'a, T> {
+    values: Values<'a>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> Iterator for ArrayViewIter<'a, T>
+where
+    T: FromZval<'a>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.values.next().map(convert)
+    }
+}
+
+/// A borrowed view of a PHP array as a map from `K` to `V`.
+///
+/// Unlike converting into a [`HashMap`](std::collections::HashMap), no keys or
+/// values are converted when the view is created. Values are looked up in the
+/// underlying hashtable and converted when they are accessed, so reading a
+/// few keys of a large array is cheap.
+///
+/// The key type `K` is used when iterating, and can be any type which can be
+/// converted from an [`ArrayKey`]: [`ArrayKey`] itself to handle both integer
+/// and string keys, [`String`] to convert integer keys into strings, or
+/// [`i64`] to only accept integer keys.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::prelude::*;
+/// use ext_php_rs::types::MapView;
+///
+/// #[php_function]
+/// pub fn connect(config: MapView<String, &str>) -> PhpResult<String> {
+///     let host = config.get("host")?.unwrap_or("localhost");
+///     let port = config.get("port")?.unwrap_or("80");
+///     Ok(format!("{}:{}", host, port))
+/// }
+/// ```
+pub struct MapView<'a, K, V> {
+    ht: &'a ZendHashTable,
+    _phantom: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'a, K, V> MapView<'a, K, V>
+where
+    K: TryFrom<ArrayKey>,
+    V: FromZval<'a>,
+{
+    /// Creates a new view of the given hashtable.
+    ///
+    /// # Parameters
+    ///
+    /// * `ht` - The hashtable to view.
+    pub fn new(ht: &'a ZendHashTable) -> Self {
+        Self {
+            ht,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements in the array.
+    pub fn len(&self) -> usize {
+        self.ht.len()
+    }
+
+    /// Returns true if the array is empty.
+    pub fn is_empty(&self) -> bool {
+        self.ht.is_empty()
+    }
+
+    /// Converts and returns the value with the given string key.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the value.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if the key does not exist, or an error if the value
+    /// could not be converted into `V`.
+    pub fn get(&self, key: &str) -> Result<Option<V>> {
+        self.ht.get(key).map(convert).transpose()
+    }
+
+    /// Converts and returns the value with the given integer key.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The key of the value.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if the key does not exist, or an error if the value
+    /// could not be converted into `V`.
+    pub fn get_index(&self, index: u64) -> Result<Option<V>> {
+        self.ht.get_index(index).map(convert).transpose()
+    }
+
+    /// Returns an iterator over the key-value pairs of the array, converting
+    /// each pair as it is reached.
+    pub fn iter(&self) -> MapViewIter<'a, K, V> {
+        MapViewIter {
+            iter: self.ht.iter(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the underlying hashtable.
+    pub fn hashtable(&self) -> &'a ZendHashTable {
+        self.ht
+    }
+}
+
+impl<K, V> Clone for MapView<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for MapView<'_, K, V> {}
+
+impl<'a, K, V> IntoIterator for MapView<'a, K, V>
+where
+    K: TryFrom<ArrayKey>,
+    V: FromZval<'a>,
+{
+    type Item = Result<(K, V)>;
+    type IntoIter = MapViewIter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> FromZval<'a> for MapView<'a, K, V>
+where
+    K: TryFrom<ArrayKey>,
+    V: FromZval<'a>,
+{
+    const TYPE: DataType = DataType::Array;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.array().map(Self::new)
+    }
+}
+
+/// Iterator over the key-value pairs of a [`MapView`].
+pub struct MapViewIter<'a, K, V> {
+    iter: Iter<'a>,
+    _phantom: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'a, K, V> Iterator for MapViewIter<'a, K, V>
+where
+    K: TryFrom<ArrayKey>,
+    V: FromZval<'a>,
+{
+    type Item = Result<(K, V)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(key, val)| {
+            let key_type = if key.is_long() {
+                DataType::Long
+            } else {
+                DataType::String
+            };
+            let key = K::try_from(key).map_err(|_| Error::ZvalConversion(key_type))?;
+            Ok((key, convert(val)?))
+        })
+    }
+}
+
+/// Converts an element of a viewed array, following references.
+fn convert<'a, T: FromZval<'a>>(zval: &'a Zval) -> Result<T> {
+    let zval = zval.dereference();
+    T::from_zval(zval).ok_or_else(|| Error::ZvalConversion(zval.get_type()))
+}
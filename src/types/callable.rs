@@ -1,23 +1,32 @@
 //! Types related to callables in PHP (anonymous functions, functions, etc).
 
-use std::{convert::TryFrom, ops::Deref};
+use std::{cell::Cell, convert::TryFrom, mem, ops::Deref, ptr};
 
 use crate::{
-    convert::{FromZval, IntoZvalDyn},
+    boxed::ZBox,
+    convert::{FromZval, IntoZval, IntoZvalDyn},
     error::{Error, Result},
-    ffi::_call_user_function_impl,
-    flags::DataType,
-    zend::ExecutorGlobals,
+    ffi::{
+        _call_user_function_impl, zend_call_known_function, zend_fcall_info_cache, zend_function,
+        zend_is_callable_ex,
+    },
+    flags::{DataType, MethodFlags},
+    zend::{ClassEntry, ExecutorGlobals},
 };
 
-use super::Zval;
+use super::{ArrayKey, ZendHashTable, ZendObject, ZendReference, Zval};
 
 /// Acts as a wrapper around a callable [`Zval`]. Allows the owner to call the
-/// [`Zval`] as if it was a PHP function through the [`try_call`] method.
+/// [`Zval`] as if it was a PHP function through the [`call`] and [`try_call`]
+/// methods.
 ///
+/// [`call`]: #method.call
 /// [`try_call`]: #method.try_call
 #[derive(Debug)]
-pub struct ZendCallable<'a>(OwnedZval<'a>);
+pub struct ZendCallable<'a> {
+    callable: OwnedZval<'a>,
+    cache: Cell<Option<zend_fcall_info_cache>>,
+}
 
 impl<'a> ZendCallable<'a> {
     /// Attempts to create a new [`ZendCallable`] from a zval.
@@ -31,7 +40,7 @@ impl<'a> ZendCallable<'a> {
     /// Returns an error if the [`Zval`] was not callable.
     pub fn new(callable: &'a Zval) -> Result<Self> {
         if callable.is_callable() {
-            Ok(Self(OwnedZval::Reference(callable)))
+            Ok(Self::from_zval_unchecked(OwnedZval::Reference(callable)))
         } else {
             Err(Error::Callable)
         }
@@ -45,7 +54,7 @@ impl<'a> ZendCallable<'a> {
     /// * `callable` - The underlying [`Zval`] that is callable.
     pub fn new_owned(callable: Zval) -> Result<Self> {
         if callable.is_callable() {
-            Ok(Self(OwnedZval::Owned(callable)))
+            Ok(Self::from_zval_unchecked(OwnedZval::Owned(callable)))
         } else {
             Err(Error::Callable)
         }
@@ -101,7 +110,7 @@ impl<'a> ZendCallable<'a> {
     /// ```
     #[inline(always)]
     pub fn try_call(&self, params: Vec<&dyn IntoZvalDyn>) -> Result<Zval> {
-        if !self.0.is_callable() {
+        if !self.callable.is_callable() {
             return Err(Error::Callable);
         }
 
@@ -116,7 +125,8 @@ impl<'a> ZendCallable<'a> {
         let result = unsafe {
             _call_user_function_impl(
                 std::ptr::null_mut(),
-                self.0.as_ref() as *const crate::ffi::_zval_struct as *mut crate::ffi::_zval_struct,
+                self.callable.as_ref() as *const crate::ffi::_zval_struct
+                    as *mut crate::ffi::_zval_struct,
                 &mut retval,
                 len as _,
                 packed.as_ptr() as *mut _,
@@ -132,6 +142,451 @@ impl<'a> ZendCallable<'a> {
             Ok(retval)
         }
     }
+
+    /// Calls the callable with a tuple of arguments, converting the return
+    /// value into `R`.
+    ///
+    /// Unlike [`try_call`](ZendCallable::try_call), the arguments are passed
+    /// without allocating. Arguments which implement [`IntoZval`] are passed
+    /// by value, while `&mut Zval` arguments are passed by reference and
+    /// hold the value assigned by the callable once the call returns. Named
+    /// arguments and unpacked arrays are passed with an [`ArgList`] instead
+    /// of a tuple.
+    ///
+    /// The function the callable points to is looked up on the first call and
+    /// reused by the following calls, which makes calling the same callable
+    /// repeatedly, for example as a comparison function, cheaper. Callables
+    /// which depend on the calling scope, such as `"static::method"` or
+    /// private methods, are looked up again on each call.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The arguments to call the function with, as a tuple.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted return value upon success. If calling the
+    /// callable fails, an exception is thrown or the return value could not
+    /// be converted into `R`, an [`Err`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ZendCallable, Zval};
+    ///
+    /// let strpos = ZendCallable::try_from_name("strpos").unwrap();
+    /// let pos: i64 = strpos.call(("hello", "e")).unwrap();
+    /// assert_eq!(pos, 1);
+    ///
+    /// let mut matches = Zval::new();
+    /// let preg_match = ZendCallable::try_from_name("preg_match").unwrap();
+    /// let found: i64 = preg_match.call(("/l+/", "hello", &mut matches)).unwrap();
+    /// assert_eq!(found, 1);
+    /// assert!(matches.is_array());
+    /// ```
+    pub fn call<A, R>(&self, args: A) -> Result<R>
+    where
+        A: CallArgs,
+        for<'b> R: FromZval<'b>,
+    {
+        let retval = self.call_zval(args)?;
+        R::from_zval(&retval).ok_or_else(|| Error::ZvalConversion(retval.get_type()))
+    }
+
+    /// Calls the callable with a tuple of arguments, returning the untyped
+    /// return value. See [`call`](ZendCallable::call).
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The arguments to call the function with, as a tuple.
+    pub fn call_zval<A: CallArgs>(&self, args: A) -> Result<Zval> {
+        let fcc = self.resolve()?;
+        // SAFETY: The function was resolved from the callable, which is kept alive by
+        // `self`.
+        unsafe { call_function(fcc.function_handler, fcc.object, fcc.called_scope, args) }
+    }
+
+    /// Returns the function cache of the callable, looking the function up if
+    /// it has not been cached yet.
+    fn resolve(&self) -> Result<zend_fcall_info_cache> {
+        if let Some(fcc) = self.cache.get() {
+            return Ok(fcc);
+        }
+
+        // SAFETY: The function cache is a plain C struct, filled in by PHP.
+        let mut fcc: zend_fcall_info_cache = unsafe { mem::zeroed() };
+        let callable = self.callable.as_ref() as *const Zval as *mut Zval;
+        let found = unsafe {
+            zend_is_callable_ex(
+                callable,
+                ptr::null_mut(),
+                0,
+                ptr::null_mut(),
+                &mut fcc,
+                ptr::null_mut(),
+            )
+        };
+        if !found || fcc.function_handler.is_null() {
+            return Err(Error::Callable);
+        }
+
+        // Trampolines used to call `__call()` are freed once called, and are
+        // therefore looked up again on each call. So are callables which are
+        // resolved relative to the calling scope, or which are only visible from
+        // it, as the next call may come from another scope.
+        let flags = unsafe { (*fcc.function_handler).common.fn_flags };
+        let uncached =
+            MethodFlags::CallViaTrampoline | MethodFlags::Private | MethodFlags::Protected;
+        if flags & uncached.bits() == 0 && !is_scope_relative(&self.callable) {
+            self.cache.set(Some(fcc));
+        }
+        Ok(fcc)
+    }
+
+    fn from_zval_unchecked(callable: OwnedZval<'a>) -> Self {
+        Self {
+            callable,
+            cache: Cell::new(None),
+        }
+    }
+}
+
+/// Returns true if the callable names its class relative to the calling scope,
+/// such as `"static::method"`, `["parent", "method"]` or
+/// `[$obj, "parent::method"]`.
+fn is_scope_relative(callable: &Zval) -> bool {
+    let is_relative = |class: &str| {
+        ["self", "parent", "static"]
+            .iter()
+            .any(|keyword| class.eq_ignore_ascii_case(keyword))
+    };
+    if let Some(name) = callable.str() {
+        return name
+            .split_once("::")
+            .is_some_and(|(class, _)| is_relative(class));
+    }
+    callable.array().is_some_and(|arr| {
+        arr.get_index(0)
+            .and_then(Zval::str)
+            .is_some_and(is_relative)
+            || arr
+                .get_index(1)
+                .and_then(Zval::str)
+                .is_some_and(|method| method.contains("::"))
+    })
+}
+
+/// A value which can be passed as an argument to [`ZendCallable::call`].
+///
+/// This trait is implemented on every type implementing [`IntoZval`], which
+/// are passed by value, and on `&mut Zval`, which is passed by reference.
+pub trait CallArg {
+    /// State kept for the duration of the call, used to write the value of
+    /// by-reference arguments back once the call returns.
+    type Guard;
+
+    /// Writes the argument into the zval passed to the function.
+    /// By-reference arguments are only moved into the zval by
+    /// [`bind`](CallArg::bind).
+    ///
+    /// # Parameters
+    ///
+    /// * `param` - The zval passed to the function.
+    fn set_param(self, param: &mut Zval) -> Result<Self::Guard>;
+
+    /// Called once every argument has been written, so that the value of a
+    /// by-reference argument is left in place if another argument fails to
+    /// convert.
+    ///
+    /// # Parameters
+    ///
+    /// * `guard` - The state returned by [`set_param`](CallArg::set_param).
+    /// * `param` - The zval passed to the function.
+    #[inline]
+    fn bind(guard: &mut Self::Guard, param: &mut Zval) {
+        let _ = (guard, param);
+    }
+
+    /// Called once the call has returned.
+    ///
+    /// # Parameters
+    ///
+    /// * `guard` - The state returned by [`set_param`](CallArg::set_param).
+    /// * `param` - The zval passed to the function.
+    fn write_back(guard: Self::Guard, param: &mut Zval);
+}
+
+impl<T: IntoZval> CallArg for T {
+    type Guard = ();
+
+    #[inline]
+    fn set_param(self, param: &mut Zval) -> Result<()> {
+        self.set_zval(param, false)
+    }
+
+    #[inline]
+    fn write_back(_: (), _: &mut Zval) {}
+}
+
+impl<'a> CallArg for &'a mut Zval {
+    type Guard = Option<&'a mut Zval>;
+
+    fn set_param(self, param: &mut Zval) -> Result<Self::Guard> {
+        // PHP references are shared with the function as-is.
+        if self.is_reference() {
+            *param = self.shallow_clone();
+            return Ok(None);
+        }
+
+        Ok(Some(self))
+    }
+
+    fn bind(guard: &mut Self::Guard, param: &mut Zval) {
+        if let Some(target) = guard {
+            param.set_reference(ZendReference::from_zval(mem::take(&mut **target)));
+        }
+    }
+
+    fn write_back(guard: Self::Guard, param: &mut Zval) {
+        if let Some(target) = guard {
+            *target = param.dereference().shallow_clone();
+        }
+    }
+}
+
+/// Arguments passed to [`ZendCallable::call`]. Implemented on tuples of up to
+/// 8 [`CallArg`]s, and on [`ArgList`].
+pub trait CallArgs {
+    /// The zvals passed to the function.
+    type Params: AsMut<[Zval]>;
+
+    /// The state kept by each argument for the duration of the call.
+    type Guards;
+
+    /// Converts the arguments into the zvals passed to the function.
+    fn into_params(self) -> Result<(Self::Params, Self::Guards)>;
+
+    /// Called once the call has returned, to write the value of by-reference
+    /// arguments back.
+    ///
+    /// # Parameters
+    ///
+    /// * `guards` - The state returned by
+    ///   [`into_params`](CallArgs::into_params).
+    /// * `params` - The zvals passed to the function.
+    fn write_back(guards: Self::Guards, params: &mut Self::Params);
+
+    /// Returns the named arguments, if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `guards` - The state returned by
+    ///   [`into_params`](CallArgs::into_params).
+    fn named_params(guards: &mut Self::Guards) -> Option<&mut ZendHashTable> {
+        let _ = guards;
+        None
+    }
+}
+
+impl CallArgs for () {
+    type Params = [Zval; 0];
+    type Guards = ();
+
+    #[inline]
+    fn into_params(self) -> Result<(Self::Params, Self::Guards)> {
+        Ok(([], ()))
+    }
+
+    #[inline]
+    fn write_back(_: (), _: &mut Self::Params) {}
+}
+
+macro_rules! call_args_impl {
+    ($n: literal; $($t: ident $i: tt),*) => {
+        impl<$($t: CallArg),*> CallArgs for ($($t,)*) {
+            type Params = [Zval; $n];
+            type Guards = ($($t::Guard,)*);
+
+            #[inline]
+            fn into_params(self) -> Result<(Self::Params, Self::Guards)> {
+                let mut params: [Zval; $n] = Default::default();
+                let mut guards = ($(self.$i.set_param(&mut params[$i])?,)*);
+                $($t::bind(&mut guards.$i, &mut params[$i]);)*
+                Ok((params, guards))
+            }
+
+            #[inline]
+            fn write_back(guards: Self::Guards, params: &mut Self::Params) {
+                $($t::write_back(guards.$i, &mut params[$i]);)*
+            }
+        }
+    };
+}
+
+call_args_impl!(1; A 0);
+call_args_impl!(2; A 0, B 1);
+call_args_impl!(3; A 0, B 1, C 2);
+call_args_impl!(4; A 0, B 1, C 2, D 3);
+call_args_impl!(5; A 0, B 1, C 2, D 3, E 4);
+call_args_impl!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+call_args_impl!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+call_args_impl!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// A list of arguments mixing positional arguments, named arguments and
+/// unpacked arrays, for calls from Rust into PHP. The list is built in the
+/// same order as the arguments of a PHP call such as `f(1, ...$rest, key:
+/// 2)`.
+///
+/// Errors are reported when the list is passed to a call: positional
+/// arguments cannot follow named arguments, and a name cannot be given twice.
+/// Names which do not match a parameter of the function cause PHP to throw an
+/// `Error`.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::types::{ArgList, ZendCallable};
+///
+/// let json_encode = ZendCallable::try_from_name("json_encode").unwrap();
+/// let json: String = json_encode
+///     .call(ArgList::new().arg(vec![1, 2]).named("flags", 128))
+///     .unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct ArgList {
+    positional: Vec<Zval>,
+    named: Option<ZBox<ZendHashTable>>,
+    error: Option<Error>,
+}
+
+impl ArgList {
+    /// Creates an empty argument list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a positional argument.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value of the argument.
+    pub fn arg(mut self, value: impl IntoZval) -> Self {
+        if self.named.is_some() {
+            self.fail(Error::PositionalAfterNamed);
+        }
+        match value.into_zval(false) {
+            Ok(zv) => self.positional.push(zv),
+            Err(e) => self.fail(e),
+        }
+        self
+    }
+
+    /// Adds a named argument.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the parameter, without the leading `$`.
+    /// * `value` - The value of the argument.
+    pub fn named(mut self, name: &str, value: impl IntoZval) -> Self {
+        match value.into_zval(false) {
+            Ok(zv) => self.insert_named(name, zv),
+            Err(e) => self.fail(e),
+        }
+        self
+    }
+
+    /// Unpacks an array into the arguments, as `...$array` does in PHP.
+    /// Values with integer keys are added as positional arguments, while
+    /// values with string keys are added as named arguments.
+    ///
+    /// # Parameters
+    ///
+    /// * `values` - The array to unpack.
+    pub fn spread(mut self, values: &ZendHashTable) -> Self {
+        for (key, value) in values.iter() {
+            match key {
+                ArrayKey::Long(_) => self = self.arg(value.shallow_clone()),
+                name => self.insert_named(&name.to_string(), value.shallow_clone()),
+            }
+        }
+        self
+    }
+
+    fn insert_named(&mut self, name: &str, value: Zval) {
+        let named = self.named.get_or_insert_with(ZendHashTable::new);
+        if named.get(name).is_some() {
+            self.fail(Error::DuplicateNamedArgument(name.to_string()));
+        } else if let Err(e) = named.insert(name, value) {
+            self.fail(e);
+        }
+    }
+
+    fn fail(&mut self, e: Error) {
+        self.error.get_or_insert(e);
+    }
+}
+
+impl CallArgs for ArgList {
+    type Params = Vec<Zval>;
+    type Guards = Option<ZBox<ZendHashTable>>;
+
+    fn into_params(self) -> Result<(Self::Params, Self::Guards)> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok((self.positional, self.named)),
+        }
+    }
+
+    #[inline]
+    fn write_back(_: Self::Guards, _: &mut Self::Params) {}
+
+    fn named_params(guards: &mut Self::Guards) -> Option<&mut ZendHashTable> {
+        guards.as_deref_mut()
+    }
+}
+
+/// Calls a function with the given arguments, returning the exception thrown
+/// by the function as an error.
+///
+/// # Parameters
+///
+/// * `func` - The function to call.
+/// * `object` - The object the method is called on, or a null pointer.
+/// * `called_scope` - The class the method is called on, or a null pointer.
+/// * `args` - The arguments to call the function with.
+///
+/// # Safety
+///
+/// The function, object and class must be valid for the duration of the call.
+pub(crate) unsafe fn call_function<A: CallArgs>(
+    func: *mut zend_function,
+    object: *mut ZendObject,
+    called_scope: *mut ClassEntry,
+    args: A,
+) -> Result<Zval> {
+    let (mut params, mut guards) = args.into_params()?;
+    let mut retval = Zval::new();
+
+    {
+        let named = A::named_params(&mut guards).map_or(ptr::null_mut(), |ht| ht as *mut _);
+        let params = params.as_mut();
+        zend_call_known_function(
+            func,
+            object,
+            called_scope,
+            &mut retval,
+            params.len() as _,
+            params.as_mut_ptr(),
+            named,
+        );
+    }
+    A::write_back(guards, &mut params);
+
+    if let Some(e) = ExecutorGlobals::take_exception() {
+        Err(Error::Exception(e))
+    } else {
+        Ok(retval)
+    }
 }
 
 impl<'a> FromZval<'a> for ZendCallable<'a> {
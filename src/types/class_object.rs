@@ -2,6 +2,7 @@
 //! by classes, allowing users to store Rust data inside a PHP object.
 
 use std::{
+    cell::Cell,
     fmt::Debug,
     mem,
     ops::{Deref, DerefMut},
@@ -14,13 +15,14 @@ use crate::{
     class::RegisteredClass,
     convert::{FromZendObject, FromZendObjectMut, FromZval, FromZvalMut, IntoZval},
     error::{Error, Result},
+    exception::{PhpException, PhpResult},
     ffi::{
         ext_php_rs_zend_object_alloc, ext_php_rs_zend_object_release, object_properties_init,
         zend_object, zend_object_std_init, zend_objects_clone_members,
     },
-    flags::DataType,
-    types::{ZendObject, Zval},
-    zend::ClassEntry,
+    flags::{ClassFlags, DataType},
+    types::{CallArgs, ZendObject, Zval},
+    zend::{ce, ClassEntry},
 };
 
 /// Representation of a Zend class object in memory.
@@ -28,6 +30,8 @@ use crate::{
 #[derive(Debug)]
 pub struct ZendClassObject<T> {
     pub obj: Option<T>,
+    /// Number of shared borrows of `obj`, or `-1` while it is mutably borrowed.
+    borrow: Cell<isize>,
     pub std: ZendObject,
 }
 
@@ -48,6 +52,38 @@ impl<T: RegisteredClass> ZendClassObject<T> {
         unsafe { Self::internal_new(Some(val), None) }
     }
 
+    /// Creates a new [`ZendClassObject`] of type `T` as an instance of the
+    /// class `ce`, storing the given value `val` inside the object. This can
+    /// be used to create an instance of a PHP class which extends `T`, such
+    /// as the class returned by [`ExecuteData::called_scope`] in a static
+    /// factory method.
+    ///
+    /// Returns [`None`] if `ce` is not the class of `T` or one of its
+    /// subclasses, or if it is abstract. The constructor of the class is not
+    /// called.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The value to store inside the object.
+    /// * `ce` - The class to create an instance of.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory was unable to be allocated for the new object.
+    ///
+    /// [`ExecuteData::called_scope`]: crate::zend::ExecuteData::called_scope
+    pub fn new_in(val: T, ce: &ClassEntry) -> Option<ZBox<Self>> {
+        if !ce.instance_of(T::get_metadata().ce())
+            || ce
+                .flags()
+                .intersects(ClassFlags::Abstract | ClassFlags::ImplicitAbstractClass)
+        {
+            return None;
+        }
+        // SAFETY: We are providing a value to initialize the object with.
+        Some(unsafe { Self::internal_new(Some(val), Some(ce)) })
+    }
+
     /// Creates a new [`ZendClassObject`] of type `T`, with an uninitialized
     /// internal object.
     ///
@@ -104,7 +140,7 @@ impl<T: RegisteredClass> ZendClassObject<T> {
     /// # Panics
     ///
     /// Panics if memory was unable to be allocated for the new object.
-    unsafe fn internal_new(val: Option<T>, ce: Option<&'static ClassEntry>) -> ZBox<Self> {
+    unsafe fn internal_new(val: Option<T>, ce: Option<&ClassEntry>) -> ZBox<Self> {
         let size = mem::size_of::<ZendClassObject<T>>();
         let meta = T::get_metadata();
         let ce = ce.unwrap_or_else(|| meta.ce()) as *const _ as *mut _;
@@ -120,6 +156,7 @@ impl<T: RegisteredClass> ZendClassObject<T> {
         // As the data in `obj.obj` is uninitialized, we don't want to drop
         // the data, but directly override it.
         ptr::write(&mut obj.obj, val);
+        ptr::write(&mut obj.borrow, Cell::new(0));
 
         obj.std.handlers = meta.handlers();
         ZBox::from_raw(obj)
@@ -139,6 +176,123 @@ impl<T: RegisteredClass> ZendClassObject<T> {
         self.obj.replace(val)
     }
 
+    /// Returns whether the object has been initialized with a value of `T`.
+    ///
+    /// Objects created from PHP are initialized by the constructor of the
+    /// class. Instances of a PHP class which extends the class of `T` remain
+    /// uninitialized until `parent::__construct()` is called.
+    pub fn is_initialized(&self) -> bool {
+        self.obj.is_some()
+    }
+
+    /// Returns the `Error` thrown when the value of an uninitialized object is
+    /// accessed from PHP.
+    #[doc(hidden)]
+    pub fn uninitialized_error(&self) -> PhpException {
+        PhpException::new(
+            format!(
+                "The {} object has not been correctly initialized by its constructor",
+                self.std.get_class_name().unwrap_or_default()
+            ),
+            0,
+            ce::error(),
+        )
+    }
+
+    /// Borrows the value of the object for as long as the returned [`ClassRef`]
+    /// is alive.
+    ///
+    /// Methods and object handlers of the class borrow the value while they
+    /// run, so that a method called re-entrantly, e.g. from PHP code called
+    /// through [`call_method`], cannot modify the value while it is in use.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Error` exception if the object has not been initialized or
+    /// if the value is mutably borrowed.
+    ///
+    /// [`call_method`]: #method.call_method
+    pub fn try_borrow(&self) -> PhpResult<ClassRef<'_, T>> {
+        let value = self
+            .obj
+            .as_ref()
+            .ok_or_else(|| self.uninitialized_error())?;
+        if self.borrow.get() < 0 {
+            return Err(self.borrow_error("being modified"));
+        }
+        self.borrow.set(self.borrow.get() + 1);
+        Ok(ClassRef {
+            value,
+            borrow: &self.borrow,
+        })
+    }
+
+    /// Mutably borrows the value of the object for as long as the returned
+    /// [`ClassRefMut`] is alive.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Error` exception if the object has not been initialized or
+    /// if the value is borrowed.
+    pub fn try_borrow_mut(&mut self) -> PhpResult<ClassRefMut<'_, T>> {
+        if self.obj.is_none() {
+            return Err(self.uninitialized_error());
+        }
+        if self.borrow.get() != 0 {
+            return Err(self.borrow_error("in use"));
+        }
+        let Self { obj, borrow, .. } = self;
+        borrow.set(-1);
+        Ok(ClassRefMut {
+            value: obj.as_mut().expect("Object was checked to be initialized"),
+            borrow,
+        })
+    }
+
+    fn borrow_error(&self, state: &str) -> PhpException {
+        PhpException::new(
+            format!(
+                "Cannot access the {} object while it is {}",
+                self.std.get_class_name().unwrap_or_default(),
+                state
+            ),
+            0,
+            ce::error(),
+        )
+    }
+
+    /// Calls a method of the object with a tuple of arguments or an
+    /// [`ArgList`](crate::types::ArgList).
+    ///
+    /// The method is resolved against the class of the object rather than the
+    /// class of `T`, so when the object is an instance of a PHP class which
+    /// extends the class of `T`, the method overridden by the PHP class is
+    /// called. This can be used to implement methods which PHP classes are
+    /// meant to override.
+    ///
+    /// The called method may call methods of the object in turn, which borrow
+    /// the value of the object through [`try_borrow`] and [`try_borrow_mut`].
+    /// A method which needs the value across the call should hold a borrow
+    /// obtained from these functions instead of dereferencing the object, so
+    /// that re-entrant calls which would modify the value throw an `Error`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    /// * `args` - The arguments to call the method with.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result wrapped in [`Ok`] upon success. If the method does
+    /// not exist, the arguments are invalid or an exception is thrown, an
+    /// [`Err`] is returned.
+    ///
+    /// [`try_borrow`]: #method.try_borrow
+    /// [`try_borrow_mut`]: #method.try_borrow_mut
+    pub fn call_method<A: CallArgs>(&self, name: &str, args: A) -> Result<Zval> {
+        self.std.try_call_method_with(name, args)
+    }
+
     /// Returns a mutable reference to the [`ZendClassObject`] of a given zend
     /// object `obj`. Returns [`None`] if the given object is not of the
     /// type `T`.
@@ -163,6 +317,14 @@ impl<T: RegisteredClass> ZendClassObject<T> {
     }
 
     fn _from_zend_obj(std: &zend_object) -> Option<&mut Self> {
+        // Objects of `T` are created with the handlers of `T`, including instances of
+        // PHP classes which extend the class of `T`. Objects of a Rust class which
+        // extends the class of `T` are an instance of the class, but have the layout
+        // of another Rust type.
+        if !ptr::eq(std.handlers, T::get_metadata().handlers()) {
+            return None;
+        }
+
         let std = std as *const zend_object as *const c_char;
         let ptr = unsafe {
             let ptr = std.offset(0 - Self::std_offset() as isize) as *const Self;
@@ -193,6 +355,54 @@ impl<T: RegisteredClass> ZendClassObject<T> {
     }
 }
 
+/// A shared borrow of the value of a [`ZendClassObject`], returned by
+/// [`ZendClassObject::try_borrow`].
+pub struct ClassRef<'a, T> {
+    value: &'a T,
+    borrow: &'a Cell<isize>,
+}
+
+impl<T> Deref for ClassRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> Drop for ClassRef<'_, T> {
+    fn drop(&mut self) {
+        self.borrow.set(self.borrow.get() - 1);
+    }
+}
+
+/// A mutable borrow of the value of a [`ZendClassObject`], returned by
+/// [`ZendClassObject::try_borrow_mut`].
+pub struct ClassRefMut<'a, T> {
+    value: &'a mut T,
+    borrow: &'a Cell<isize>,
+}
+
+impl<T> Deref for ClassRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> DerefMut for ClassRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value
+    }
+}
+
+impl<T> Drop for ClassRefMut<'_, T> {
+    fn drop(&mut self) {
+        self.borrow.set(0);
+    }
+}
+
 impl<'a, T: RegisteredClass> FromZval<'a> for &'a ZendClassObject<T> {
     const TYPE: DataType = DataType::Object(Some(T::CLASS_NAME));
 
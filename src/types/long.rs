@@ -58,15 +58,41 @@ try_into_zval_int!(u64);
 try_into_zval_int!(isize);
 try_into_zval_int!(usize);
 
-try_from_zval!(i8, long, Long);
-try_from_zval!(i16, long, Long);
-try_from_zval!(i32, long, Long);
-try_from_zval!(i64, long, Long);
-
-try_from_zval!(u8, long, Long);
-try_from_zval!(u16, long, Long);
-try_from_zval!(u32, long, Long);
-try_from_zval!(u64, long, Long);
-
-try_from_zval!(usize, long, Long);
-try_from_zval!(isize, long, Long);
+/// Returns the range of the given integer type which fits in a [`ZendLong`].
+const fn long_range(min: i128, max: i128) -> Option<(ZendLong, ZendLong)> {
+    let min = if min < ZendLong::MIN as i128 {
+        ZendLong::MIN
+    } else {
+        min as ZendLong
+    };
+    let max = if max > ZendLong::MAX as i128 {
+        ZendLong::MAX
+    } else {
+        max as ZendLong
+    };
+    Some((min, max))
+}
+
+macro_rules! try_from_zval_int {
+    ($type: ty) => {
+        try_from_zval!(
+            $type,
+            long,
+            Long,
+            long_range(<$type>::MIN as i128, <$type>::MAX as i128)
+        );
+    };
+}
+
+try_from_zval_int!(i8);
+try_from_zval_int!(i16);
+try_from_zval_int!(i32);
+try_from_zval_int!(i64);
+
+try_from_zval_int!(u8);
+try_from_zval_int!(u16);
+try_from_zval_int!(u32);
+try_from_zval_int!(u64);
+
+try_from_zval_int!(usize);
+try_from_zval_int!(isize);
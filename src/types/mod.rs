@@ -4,23 +4,34 @@
 //! these PHP types when required.
 
 mod array;
+mod array_view;
 mod callable;
 mod class_object;
 mod iterable;
 mod iterator;
 mod long;
 mod object;
+mod reference;
+mod resource;
+mod smart_str;
 mod string;
+mod weak;
 mod zval;
 
-pub use array::{ArrayKey, ZendHashTable};
-pub use callable::ZendCallable;
-pub use class_object::ZendClassObject;
+pub use array::{ArrayKey, ArrayKeyRef, Entry, ZendHashTable};
+pub use array_view::{ArrayView, ArrayViewIter, MapView, MapViewIter};
+pub(crate) use callable::call_function;
+pub use callable::{ArgList, CallArg, CallArgs, ZendCallable};
+pub use class_object::{ClassRef, ClassRefMut, ZendClassObject};
 pub use iterable::Iterable;
 pub use iterator::ZendIterator;
 pub use long::ZendLong;
 pub use object::{PropertyQuery, ZendObject};
+pub use reference::{Ref, ZendReference};
+pub use resource::ZendResource;
+pub use smart_str::SmartStr;
 pub use string::ZendStr;
+pub use weak::{WeakMap, WeakObject};
 pub use zval::Zval;
 
 use crate::{convert::FromZval, flags::DataType, macros::into_zval};
@@ -14,8 +14,8 @@ use crate::{
         ZEND_ISEMPTY, ZEND_PROPERTY_EXISTS, ZEND_PROPERTY_ISSET,
     },
     flags::DataType,
-    rc::PhpRc,
-    types::{ZendClassObject, ZendStr, Zval},
+    rc::{PhpRc, ZRc},
+    types::{call_function, CallArgs, ZendClassObject, ZendStr, Zval},
     zend::{ce, ClassEntry, ExecutorGlobals, ZendObjectHandlers},
 };
 
@@ -174,6 +174,37 @@ impl ZendObject {
 
         Ok(retval)
     }
+
+    /// Calls a method of the object with a tuple of arguments or an
+    /// [`ArgList`], which allows named arguments and unpacked arrays to be
+    /// passed.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    /// * `args` - The arguments to call the method with.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result wrapped in [`Ok`] upon success. If the method does
+    /// not exist, the arguments are invalid or an exception is thrown, an
+    /// [`Err`] is returned.
+    ///
+    /// [`ArgList`]: crate::types::ArgList
+    pub fn try_call_method_with<A: CallArgs>(&self, name: &str, args: A) -> Result<Zval> {
+        unsafe {
+            let func = zend_hash_str_find_ptr_lc(
+                &(*self.ce).function_table,
+                name.as_ptr() as *const c_char,
+                name.len(),
+            ) as *mut zend_function;
+            if func.is_null() {
+                return Err(Error::Callable);
+            }
+            call_function(func, self as *const _ as *mut _, self.ce, args)
+        }
+    }
+
     /// Attempts to read a property from the Object. Returns a result containing
     /// the value of the property if it exists and can be read, and an
     /// [`Error`] otherwise.
@@ -375,6 +406,27 @@ impl IntoZval for &mut ZendObject {
     }
 }
 
+impl IntoZval for ZRc<ZendObject> {
+    const TYPE: DataType = DataType::Object(None);
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        // The reference held by the handle is given to the zval, so the counter
+        // incremented by `set_object` is decremented again.
+        let obj = unsafe { &mut *ZRc::into_raw(self) };
+        zv.set_object(obj);
+        obj.dec_count();
+        Ok(())
+    }
+}
+
+impl FromZval<'_> for ZRc<ZendObject> {
+    const TYPE: DataType = DataType::Object(None);
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.object().map(ZRc::from_ref)
+    }
+}
+
 impl FromZendObject<'_> for String {
     fn from_zend_object(obj: &ZendObject) -> Result<Self> {
         let mut ret = Zval::new();
@@ -0,0 +1,250 @@
+//! Represents a PHP reference, which allows multiple variables to share the
+//! same value.
+
+use std::{fmt::Debug, marker::PhantomData};
+
+use crate::{
+    alloc::efree,
+    boxed::{ZBox, ZBoxable},
+    convert::{FromZval, FromZvalMut, IntoZval},
+    error::{Error, Result},
+    ffi::{
+        ext_php_rs_zend_new_reference, zend_reference, zend_try_assign_typed_ref, zval_ptr_dtor,
+        ZEND_RESULT_CODE_SUCCESS,
+    },
+    flags::DataType,
+    rc::PhpRc,
+    types::Zval,
+    zend::ExecutorGlobals,
+};
+
+/// A PHP reference.
+///
+/// A reference holds a value which is shared by every variable bound to it,
+/// so writing to one of the variables changes all of them. References are
+/// created in PHP with `&`, for example when a variable is passed to a
+/// by-reference parameter.
+///
+/// A reference can be bound to typed properties, in which case only values
+/// matching the types of the properties can be assigned to it.
+/// [`set`](ZendReference::set) checks the types the same way an assignment in
+/// PHP does.
+pub type ZendReference = zend_reference;
+
+impl ZendReference {
+    /// Creates a new reference holding the given value, returned inside a
+    /// [`ZBox`].
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The value of the reference.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the value could not be converted into a zval.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ZendReference, Zval};
+    ///
+    /// let mut zv = Zval::new();
+    /// zv.set_reference(ZendReference::new(5).unwrap());
+    /// assert_eq!(zv.dereference().long(), Some(5));
+    /// ```
+    pub fn new(val: impl IntoZval) -> Result<ZBox<Self>> {
+        Ok(Self::from_zval(val.into_zval(false)?))
+    }
+
+    /// Creates a new reference holding the given zval.
+    pub(crate) fn from_zval(mut zv: Zval) -> ZBox<Self> {
+        unsafe {
+            let ptr = ext_php_rs_zend_new_reference(&mut zv);
+            // The reference has taken ownership of the value.
+            zv.release();
+            ZBox::from_raw(ptr)
+        }
+    }
+
+    /// Returns the value held by the reference.
+    pub fn value(&self) -> &Zval {
+        &self.val
+    }
+
+    /// Returns a mutable reference to the value held by the reference.
+    ///
+    /// Values written through the returned zval are not checked against the
+    /// types of the typed properties the reference is bound to. Use
+    /// [`set`](ZendReference::set) to assign a new value instead.
+    pub fn value_mut(&mut self) -> &mut Zval {
+        &mut self.val
+    }
+
+    /// Returns true if the reference is bound to at least one typed property.
+    pub fn is_typed(&self) -> bool {
+        // SAFETY: Both variants of the union are null when there are no sources.
+        unsafe { !self.sources.ptr.is_null() }
+    }
+
+    /// Assigns a new value to the reference, which is seen by every variable
+    /// bound to it.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The new value of the reference.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the value could not be converted into a zval, or if
+    /// the reference is bound to a typed property and the value does not match
+    /// its type. In the latter case the `TypeError` thrown by PHP is returned
+    /// in [`Error::Exception`].
+    pub fn set(&mut self, val: impl IntoZval) -> Result<()> {
+        let mut zv = val.into_zval(false)?;
+        if !self.is_typed() {
+            self.val = zv;
+            return Ok(());
+        }
+
+        // SAFETY: The value is given to the reference on success, and released on
+        // failure.
+        let result = unsafe { zend_try_assign_typed_ref(self, &mut zv) };
+        zv.release();
+        if result == ZEND_RESULT_CODE_SUCCESS {
+            Ok(())
+        } else {
+            Err(ExecutorGlobals::take_exception()
+                .map(Error::Exception)
+                .unwrap_or(Error::ZvalConversion(DataType::Reference)))
+        }
+    }
+}
+
+unsafe impl ZBoxable for ZendReference {
+    fn free(&mut self) {
+        self.dec_count();
+        if self.get_count() == 0 {
+            unsafe {
+                zval_ptr_dtor(&mut self.val);
+                efree((self as *mut Self).cast());
+            }
+        }
+    }
+}
+
+impl Debug for ZendReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ZendReference").field(self.value()).finish()
+    }
+}
+
+impl IntoZval for ZBox<ZendReference> {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        zv.set_reference(self);
+        Ok(())
+    }
+}
+
+impl<'a> FromZvalMut<'a> for &'a mut ZendReference {
+    const TYPE: DataType = DataType::Mixed;
+    const DEREFERENCE: bool = false;
+
+    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
+        zval.zend_reference_mut()
+    }
+}
+
+/// A by-reference function parameter, declared as `&$name` in PHP, whose
+/// value is read and written as a `T`.
+///
+/// Writing a value with [`set`](Ref::set) changes the variable passed by the
+/// caller, which makes `Ref` suitable for out parameters. The value held by
+/// the reference can be of any type when the function is called, for example
+/// `null` when the caller passes a new variable.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::prelude::*;
+/// use ext_php_rs::types::Ref;
+///
+/// #[php_function]
+/// pub fn parse_int(input: &str, mut result: Ref<i64>) -> PhpResult<bool> {
+///     match input.parse() {
+///         Ok(value) => {
+///             result.set(value)?;
+///             Ok(true)
+///         }
+///         Err(_) => Ok(false),
+///     }
+/// }
+/// ```
+pub struct Ref<'a, T> {
+    reference: &'a mut ZendReference,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> Ref<'a, T> {
+    /// Creates a new typed handle to the given reference.
+    ///
+    /// # Parameters
+    ///
+    /// * `reference` - The reference to read and write.
+    pub fn new(reference: &'a mut ZendReference) -> Self {
+        Self {
+            reference,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the value held by the reference, or `None` if it could not be
+    /// converted into a `T`.
+    pub fn get<'b>(&'b self) -> Option<T>
+    where
+        T: FromZval<'b>,
+    {
+        T::from_zval(self.reference.value().dereference())
+    }
+
+    /// Assigns a new value to the reference, changing the variable passed by
+    /// the caller.
+    ///
+    /// See [`ZendReference::set`] for the errors which can be returned.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The new value.
+    pub fn set(&mut self, val: T) -> Result<()>
+    where
+        T: IntoZval,
+    {
+        self.reference.set(val)
+    }
+
+    /// Returns the zval held by the reference.
+    pub fn zval(&self) -> &Zval {
+        self.reference.value()
+    }
+
+    /// Returns the underlying reference.
+    pub fn reference(&mut self) -> &mut ZendReference {
+        self.reference
+    }
+}
+
+impl<T> Debug for Ref<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Ref").field(self.zval()).finish()
+    }
+}
+
+impl<'a, T> FromZvalMut<'a> for Ref<'a, T> {
+    const TYPE: DataType = DataType::Mixed;
+    const DEREFERENCE: bool = false;
+
+    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
+        zval.zend_reference_mut().map(Self::new)
+    }
+}
@@ -0,0 +1,189 @@
+//! Represents a PHP resource, an opaque handle to a native value.
+
+use std::fmt::Debug;
+
+use crate::{
+    boxed::{ZBox, ZBoxable},
+    convert::{FromZval, FromZvalMut, IntoZval},
+    error::Result,
+    ffi::{
+        zend_list_close, zend_list_delete, zend_register_persistent_resource,
+        zend_register_resource, zend_resource,
+    },
+    flags::DataType,
+    rc::PhpRc,
+    resource::RegisteredResource,
+    types::Zval,
+    zend::ExecutorGlobals,
+};
+
+/// A PHP resource.
+///
+/// Resources created from Rust hold a value of a type implementing
+/// [`RegisteredResource`], which can be retrieved with
+/// [`value`](ZendResource::value) after checking the type of the resource.
+pub type ZendResource = zend_resource;
+
+impl ZendResource {
+    /// Creates a new resource holding the given value, returned inside a
+    /// [`ZBox`]. The value is dropped when the resource is destroyed.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value held by the resource.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource type `T` has not been registered.
+    pub fn new<T: RegisteredResource>(value: T) -> ZBox<Self> {
+        let id = T::get_metadata().id();
+        let ptr = Box::into_raw(Box::new(value));
+        // SAFETY: `zend_register_resource` always returns a valid resource with a
+        // reference count of 1.
+        unsafe { ZBox::from_raw(zend_register_resource(ptr.cast(), id)) }
+    }
+
+    /// Creates a new persistent resource holding the given value, stored in
+    /// the persistent list under the given key. Persistent resources are kept
+    /// across requests, and can be retrieved in later requests with
+    /// [`find_persistent`](ZendResource::find_persistent).
+    ///
+    /// If a persistent resource already exists with the same key, it is
+    /// replaced and destroyed. The value is dropped when the resource is
+    /// replaced or when the module is shut down.
+    ///
+    /// As the resource can be retrieved any number of times, only shared
+    /// references to it are returned. Values which change over time should
+    /// use interior mutability, such as a [`Cell`](std::cell::Cell).
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the resource in the persistent list.
+    /// * `value` - The value held by the resource.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource type `T` has not been registered.
+    pub fn new_persistent<T: RegisteredResource>(key: &str, value: T) -> &'static Self {
+        let id = T::get_metadata().id();
+        let ptr = Box::into_raw(Box::new(value));
+        // SAFETY: The key is copied by PHP, and the returned resource is owned by the
+        // persistent list.
+        unsafe {
+            &*zend_register_persistent_resource(key.as_ptr().cast(), key.len(), ptr.cast(), id)
+        }
+    }
+
+    /// Returns the persistent resource stored under the given key, if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the resource in the persistent list.
+    pub fn find_persistent(key: &str) -> Option<&'static Self> {
+        let globals = ExecutorGlobals::get();
+        let res = globals.persistent_list.get(key)?.resource()?;
+        // SAFETY: Resources in the persistent list live until they are removed from
+        // the list.
+        unsafe { res.as_ref() }
+    }
+
+    /// Returns the handle of the resource, which is the number shown when the
+    /// resource is printed in PHP. Persistent resources have a handle of -1.
+    pub fn handle(&self) -> i64 {
+        self.handle
+    }
+
+    /// Returns the type id of the resource, or -1 if it has been closed.
+    pub fn type_id(&self) -> i32 {
+        self.type_
+    }
+
+    /// Returns true if the resource holds a value of type `T` and has not been
+    /// closed.
+    pub fn is<T: RegisteredResource>(&self) -> bool {
+        let meta = T::get_metadata();
+        meta.has_id() && self.type_ == meta.id() && !self.ptr.is_null()
+    }
+
+    /// Returns a reference to the value held by the resource if it is of type
+    /// `T`.
+    pub fn value<T: RegisteredResource>(&self) -> Option<&T> {
+        if self.is::<T>() {
+            // SAFETY: Resources of this type are always created from a boxed `T`.
+            unsafe { self.ptr.cast::<T>().as_ref() }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the value held by the resource if it is
+    /// of type `T`.
+    pub fn value_mut<T: RegisteredResource>(&mut self) -> Option<&mut T> {
+        if self.is::<T>() {
+            // SAFETY: Resources of this type are always created from a boxed `T`.
+            unsafe { self.ptr.cast::<T>().as_mut() }
+        } else {
+            None
+        }
+    }
+
+    /// Closes the resource, destroying the value it holds while PHP variables
+    /// may still refer to the resource, as `fclose()` does. The type of a
+    /// closed resource is reported as `Unknown` by PHP.
+    pub fn close(&mut self) {
+        // SAFETY: `zend_list_close` does nothing if the resource is already closed.
+        unsafe { zend_list_close(self) }
+    }
+}
+
+unsafe impl ZBoxable for ZendResource {
+    fn free(&mut self) {
+        unsafe { zend_list_delete(self) }
+    }
+}
+
+impl Debug for ZendResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZendResource")
+            .field("handle", &self.handle)
+            .field("type_id", &self.type_)
+            .finish()
+    }
+}
+
+impl IntoZval for ZBox<ZendResource> {
+    const TYPE: DataType = DataType::Resource;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        zv.set_resource(self.into_raw());
+        Ok(())
+    }
+}
+
+impl IntoZval for &mut ZendResource {
+    const TYPE: DataType = DataType::Resource;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        self.inc_count();
+        zv.set_resource(self);
+        Ok(())
+    }
+}
+
+impl<'a> FromZval<'a> for &'a ZendResource {
+    const TYPE: DataType = DataType::Resource;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        // SAFETY: Resource zvals always point to a valid resource.
+        unsafe { zval.resource()?.as_ref() }
+    }
+}
+
+impl<'a> FromZvalMut<'a> for &'a mut ZendResource {
+    const TYPE: DataType = DataType::Resource;
+
+    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
+        // SAFETY: Resource zvals always point to a valid resource.
+        unsafe { zval.resource()?.as_mut() }
+    }
+}
@@ -0,0 +1,204 @@
+//! A builder for Zend strings, which grows the string in place.
+
+use std::{fmt, io, ptr};
+
+use crate::{
+    boxed::ZBox,
+    convert::IntoZval,
+    error::Result,
+    ffi::{
+        ext_php_rs_smart_str_alloc, ext_php_rs_smart_str_append, ext_php_rs_smart_str_append_long,
+        ext_php_rs_smart_str_extract, ext_php_rs_smart_str_free, smart_str,
+    },
+    flags::DataType,
+    types::{ZendStr, Zval},
+};
+
+/// A builder for [`ZendStr`], wrapping the `smart_str` API of the Zend engine.
+///
+/// The contents are written directly into a Zend string, which is grown as
+/// needed and is handed over without being copied once the builder is
+/// finished. This avoids building a Rust [`String`] only to copy it into a
+/// Zend string afterwards.
+///
+/// The builder implements [`fmt::Write`] and [`io::Write`], so it can be used
+/// with the [`write!`] macro and with any API writing to a writer.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::types::SmartStr;
+/// use std::fmt::Write;
+///
+/// let mut buf = SmartStr::new(false);
+/// buf.append("id=");
+/// buf.append_long(42);
+/// write!(buf, ", ratio={:.2}", 0.5).unwrap();
+/// let s = buf.into_zend_str();
+/// assert_eq!(s.as_bytes(), b"id=42, ratio=0.50");
+/// ```
+pub struct SmartStr {
+    inner: smart_str,
+    persistent: bool,
+}
+
+impl SmartStr {
+    /// Creates a new, empty builder. No memory is allocated until the first
+    /// write.
+    ///
+    /// # Parameters
+    ///
+    /// * `persistent` - Whether the built string should persist through the
+    ///   request boundary.
+    pub fn new(persistent: bool) -> Self {
+        Self {
+            inner: smart_str {
+                s: ptr::null_mut(),
+                a: 0,
+            },
+            persistent,
+        }
+    }
+
+    /// Creates a new builder with room for at least `capacity` bytes.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity` - The number of bytes to allocate.
+    /// * `persistent` - Whether the built string should persist through the
+    ///   request boundary.
+    pub fn with_capacity(capacity: usize, persistent: bool) -> Self {
+        let mut buf = Self::new(persistent);
+        buf.reserve(capacity);
+        buf
+    }
+
+    /// Reserves room for at least `additional` more bytes.
+    ///
+    /// # Parameters
+    ///
+    /// * `additional` - The number of bytes to reserve.
+    pub fn reserve(&mut self, additional: usize) {
+        unsafe { ext_php_rs_smart_str_alloc(&mut self.inner, additional, self.persistent) };
+    }
+
+    /// Appends the given bytes to the string.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The bytes to append.
+    pub fn append(&mut self, val: impl AsRef<[u8]>) {
+        let val = val.as_ref();
+        unsafe {
+            ext_php_rs_smart_str_append(
+                &mut self.inner,
+                val.as_ptr().cast(),
+                val.len(),
+                self.persistent,
+            )
+        };
+    }
+
+    /// Appends the decimal representation of the given integer to the string.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The integer to append.
+    pub fn append_long(&mut self, val: i64) {
+        unsafe { ext_php_rs_smart_str_append_long(&mut self.inner, val as _, self.persistent) };
+    }
+
+    /// Returns the number of bytes written to the string.
+    pub fn len(&self) -> usize {
+        // SAFETY: The pointer is either null or points to the string being built.
+        unsafe { self.inner.s.as_ref() }.map_or(0, ZendStr::len)
+    }
+
+    /// Returns true if nothing has been written to the string.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bytes written to the string.
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: The pointer is either null or points to the string being built.
+        unsafe { self.inner.s.as_ref() }.map_or(&[], ZendStr::as_bytes)
+    }
+
+    /// Clears the contents of the string, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        // SAFETY: The pointer is either null or points to the string being built.
+        if let Some(s) = unsafe { self.inner.s.as_mut() } {
+            s.len = 0;
+        }
+    }
+
+    /// Finishes the string, returning it as a [`ZendStr`] without copying its
+    /// contents.
+    pub fn into_zend_str(mut self) -> ZBox<ZendStr> {
+        // SAFETY: The builder owns the string, which is handed over and not freed
+        // when the builder is dropped.
+        unsafe { ZBox::from_raw(ext_php_rs_smart_str_extract(&mut self.inner)) }
+    }
+}
+
+impl Default for SmartStr {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl Drop for SmartStr {
+    fn drop(&mut self) {
+        unsafe { ext_php_rs_smart_str_free(&mut self.inner, self.persistent) };
+    }
+}
+
+impl fmt::Debug for SmartStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SmartStr")
+            .field(&String::from_utf8_lossy(self.as_bytes()))
+            .finish()
+    }
+}
+
+impl fmt::Write for SmartStr {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.append(s);
+        Ok(())
+    }
+}
+
+impl io::Write for SmartStr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.append(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]>> Extend<T> for SmartStr {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for val in iter {
+            self.append(val);
+        }
+    }
+}
+
+impl From<SmartStr> for ZBox<ZendStr> {
+    fn from(value: SmartStr) -> Self {
+        value.into_zend_str()
+    }
+}
+
+impl IntoZval for SmartStr {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        zv.set_zend_string(self.into_zend_str());
+        Ok(())
+    }
+}
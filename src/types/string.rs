@@ -6,7 +6,8 @@ use std::{
     convert::TryFrom,
     ffi::{CStr, CString},
     fmt::Debug,
-    slice,
+    ops::RangeBounds,
+    ptr, slice,
 };
 
 use parking_lot::{const_mutex, Mutex};
@@ -17,11 +18,13 @@ use crate::{
     error::{Error, Result},
     ffi::{
         ext_php_rs_is_known_valid_utf8, ext_php_rs_set_known_valid_utf8,
-        ext_php_rs_zend_string_init, ext_php_rs_zend_string_release, zend_string,
-        zend_string_init_interned,
+        ext_php_rs_zend_string_alloc, ext_php_rs_zend_string_forget_hash_val,
+        ext_php_rs_zend_string_init, ext_php_rs_zend_string_release, zend_new_interned_string,
+        zend_string, zend_string_init_interned,
     },
     flags::DataType,
     macros::try_from_zval,
+    rc::{PhpRc, ZRc},
     types::Zval,
 };
 
@@ -240,6 +243,44 @@ impl ZendStr {
         }
     }
 
+    /// Interns the given Zend string, returning the interned copy. If an equal
+    /// string has already been interned, the given string is released and the
+    /// existing copy is returned. Otherwise the given string becomes the
+    /// interned string, without being copied.
+    ///
+    /// Strings created during a request are interned for the rest of the
+    /// request only.
+    ///
+    /// # Parameters
+    ///
+    /// * `str` - The string to intern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function used to intern strings has not been set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::intern(ZendStr::new("PHP", false));
+    /// assert!(s.is_interned());
+    /// ```
+    pub fn intern(str: ZBox<Self>) -> ZBox<Self> {
+        let _lock = INTERNED_LOCK.lock();
+        unsafe {
+            let intern = zend_new_interned_string.expect("`zend_new_interned_string` not ready");
+            ZBox::from_raw(intern(str.into_raw()))
+        }
+    }
+
+    /// Returns true if the string is interned. Interned strings are immutable,
+    /// and are never released while they are in use.
+    pub fn is_interned(&self) -> bool {
+        self.is_immutable()
+    }
+
     /// Returns the length of the string.
     ///
     /// # Example
@@ -308,6 +349,119 @@ impl ZendStr {
         unsafe { slice::from_raw_parts(self.val.as_ptr().cast(), self.len()) }
     }
 
+    /// Returns a mutable reference to the underlying bytes inside the Zend
+    /// string. The cached hash of the string is cleared, as it is no longer
+    /// valid once the bytes are modified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string is interned, as interned strings are shared and
+    /// must never be modified. Use [`Zval::zend_str_mut`] or
+    /// [`ZRc::make_mut`] to get a copy which can be modified.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        assert!(
+            !self.is_immutable(),
+            "Interned strings must not be modified"
+        );
+        unsafe {
+            ext_php_rs_zend_string_forget_hash_val(self);
+            slice::from_raw_parts_mut(self.val.as_mut_ptr().cast(), self.len())
+        }
+    }
+
+    /// Creates a new Zend string holding this string followed by the given
+    /// bytes. The contents are copied once, directly into the new string.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The bytes to append.
+    /// * `persistent` - Whether the string should persist through the request
+    ///   boundary.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::new("hello", false);
+    /// assert_eq!(s.concat(", world!", false).as_bytes(), b"hello, world!");
+    /// ```
+    pub fn concat(&self, other: impl AsRef<[u8]>, persistent: bool) -> ZBox<Self> {
+        Self::from_parts(&[self.as_bytes(), other.as_ref()], persistent)
+    }
+
+    /// Creates a new Zend string holding the bytes of this string within the
+    /// given range.
+    ///
+    /// Returns [`None`] if the range is out of bounds. Unlike slicing a
+    /// [`str`], the range does not have to fall on UTF-8 character boundaries.
+    ///
+    /// # Parameters
+    ///
+    /// * `range` - The range of bytes to copy.
+    /// * `persistent` - Whether the string should persist through the request
+    ///   boundary.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::new("hello, world!", false);
+    /// assert_eq!(s.substr(7..12, false).unwrap().as_bytes(), b"world");
+    /// assert!(s.substr(7..20, false).is_none());
+    /// ```
+    pub fn substr(&self, range: impl RangeBounds<usize>, persistent: bool) -> Option<ZBox<Self>> {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        let bytes = self.as_bytes().get(range)?;
+        Some(Self::from_parts(&[bytes], persistent))
+    }
+
+    /// Creates a new Zend string where every ASCII letter of this string is
+    /// converted to lowercase, like `strtolower()`. Other bytes are copied
+    /// unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `persistent` - Whether the string should persist through the request
+    ///   boundary.
+    pub fn to_ascii_lowercase(&self, persistent: bool) -> ZBox<Self> {
+        let mut s = Self::from_parts(&[self.as_bytes()], persistent);
+        s.as_bytes_mut().make_ascii_lowercase();
+        s
+    }
+
+    /// Creates a new Zend string where every ASCII letter of this string is
+    /// converted to uppercase, like `strtoupper()`. Other bytes are copied
+    /// unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `persistent` - Whether the string should persist through the request
+    ///   boundary.
+    pub fn to_ascii_uppercase(&self, persistent: bool) -> ZBox<Self> {
+        let mut s = Self::from_parts(&[self.as_bytes()], persistent);
+        s.as_bytes_mut().make_ascii_uppercase();
+        s
+    }
+
+    /// Allocates a new Zend string and copies the given parts into it, one
+    /// after another.
+    fn from_parts(parts: &[&[u8]], persistent: bool) -> ZBox<Self> {
+        let len = parts.iter().map(|part| part.len()).sum();
+        unsafe {
+            let ptr = ext_php_rs_zend_string_alloc(len, persistent)
+                .as_mut()
+                .expect("Failed to allocate memory for new Zend string");
+            let mut dest = ptr.val.as_mut_ptr().cast::<u8>();
+            for part in parts {
+                ptr::copy_nonoverlapping(part.as_ptr(), dest, part.len());
+                dest = dest.add(part.len());
+            }
+            ZBox::from_raw(ptr)
+        }
+    }
+
     /// Returns a raw pointer to this object
     pub fn as_ptr(&self) -> *const ZendStr {
         self as *const _
@@ -446,6 +600,23 @@ try_into_zval_str!(String);
 try_into_zval_str!(&str);
 try_from_zval!(String, string, String);
 
+impl IntoZval for ZRc<ZendStr> {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        zv.set_shared_zend_string(self);
+        Ok(())
+    }
+}
+
+impl FromZval<'_> for ZRc<ZendStr> {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.zend_str().map(ZRc::from_ref)
+    }
+}
+
 impl<'a> FromZval<'a> for &'a str {
     const TYPE: DataType = DataType::String;
 
@@ -454,6 +625,48 @@ impl<'a> FromZval<'a> for &'a str {
     }
 }
 
+impl IntoZval for ZBox<ZendStr> {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        zv.set_zend_string(self);
+        Ok(())
+    }
+}
+
+impl IntoZval for &[u8] {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        zv.set_zend_string(ZendStr::new(self, persistent));
+        Ok(())
+    }
+}
+
+impl IntoZval for Cow<'_, [u8]> {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        self.as_ref().set_zval(zv, persistent)
+    }
+}
+
+impl<'a> FromZval<'a> for &'a [u8] {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.bytes()
+    }
+}
+
+impl<'a> FromZval<'a> for Cow<'a, [u8]> {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.bytes().map(Cow::Borrowed)
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "embed")]
 mod tests {
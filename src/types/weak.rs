@@ -0,0 +1,234 @@
+//! Weak references to PHP objects, which do not keep the object alive.
+
+use std::{collections::HashMap, fmt::Debug, ptr};
+
+use crate::{
+    convert::IntoZval,
+    error::{Error, Result},
+    flags::DataType,
+    rc::ZRc,
+    types::{ZendObject, Zval},
+    zend::Function,
+};
+
+/// A weak reference to a PHP object, backed by a PHP `WeakReference`.
+///
+/// Holding a [`ZBox`] or a [`ZRc`] to an object keeps it alive for as long as
+/// the Rust value exists, which leaks the object when the Rust value is stored
+/// in a long-lived structure. A weak reference allows the object to be
+/// destroyed once PHP no longer uses it, at which point
+/// [`get`](WeakObject::get) returns [`None`].
+///
+/// Weak references are request-bound, like the objects they point to.
+///
+/// [`ZBox`]: crate::boxed::ZBox
+#[derive(Clone)]
+pub struct WeakObject {
+    weakref: ZRc<ZendObject>,
+}
+
+impl WeakObject {
+    /// Creates a new weak reference to the given object. Weak references to
+    /// the same object share the same `WeakReference` instance.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The object to reference.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the `WeakReference` could not be created.
+    pub fn new(obj: &ZendObject) -> Result<Self> {
+        let create = Function::try_from_method("WeakReference", "create").ok_or(Error::Callable)?;
+        let target = ZRc::from_ref(obj).into_zval(false)?;
+        let weakref = create.try_call(vec![&target])?;
+        let weakref = weakref.object().ok_or(Error::Callable)?;
+        Ok(Self {
+            weakref: ZRc::from_ref(weakref),
+        })
+    }
+
+    /// Returns a strong reference to the object, or [`None`] if the object
+    /// has been destroyed.
+    pub fn get(&self) -> Option<ZRc<ZendObject>> {
+        let obj = self.weakref.try_call_method("get", vec![]).ok()?;
+        obj.object().map(ZRc::from_ref)
+    }
+
+    /// Returns true if the object has not been destroyed.
+    pub fn is_alive(&self) -> bool {
+        self.get().is_some()
+    }
+
+    /// Returns true if the weak reference points to the given object.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The object to compare with.
+    pub fn points_to(&self, obj: &ZendObject) -> bool {
+        self.get().is_some_and(|target| ptr::eq(&*target, obj))
+    }
+}
+
+impl Debug for WeakObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WeakObject").field(&self.get()).finish()
+    }
+}
+
+impl IntoZval for WeakObject {
+    const TYPE: DataType = DataType::Object(Some("WeakReference"));
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        self.weakref.set_zval(zv, persistent)
+    }
+}
+
+/// A map keyed by PHP objects which does not keep its keys alive, similar to
+/// the PHP `WeakMap` class.
+///
+/// Entries are stored by object id, see [`ZendObject::get_id`]. As ids are
+/// reused once an object is destroyed, each entry also holds a
+/// [`WeakObject`] to its key, and entries whose key has been destroyed are
+/// never returned. They are removed when another object with the same id is
+/// inserted, or by calling [`purge`](WeakMap::purge).
+///
+/// The values are owned by the map, so a value referencing its own key keeps
+/// the key alive.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::types::{WeakMap, ZendObject};
+///
+/// let mut listeners: WeakMap<Vec<String>> = WeakMap::new();
+/// let obj = ZendObject::new_stdclass();
+/// listeners.insert(&obj, vec!["onSave".into()]).unwrap();
+/// assert_eq!(listeners.get(&obj).map(Vec::len), Some(1));
+/// ```
+pub struct WeakMap<V> {
+    entries: HashMap<u32, (WeakObject, V)>,
+}
+
+impl<V> WeakMap<V> {
+    /// Creates a new, empty map.
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Inserts a value into the map, returning the previous value associated
+    /// with the object.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The object to associate the value with.
+    /// * `value` - The value to insert.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the weak reference to the object could not be
+    /// created.
+    pub fn insert(&mut self, key: &ZendObject, value: V) -> Result<Option<V>> {
+        let prev = self.remove(key);
+        self.entries
+            .insert(key.get_id(), (WeakObject::new(key)?, value));
+        Ok(prev)
+    }
+
+    /// Returns a reference to the value associated with the object, if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The object to look up.
+    pub fn get(&self, key: &ZendObject) -> Option<&V> {
+        self.entries
+            .get(&key.get_id())
+            .filter(|(weak, _)| weak.points_to(key))
+            .map(|(_, value)| value)
+    }
+
+    /// Returns a mutable reference to the value associated with the object,
+    /// if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The object to look up.
+    pub fn get_mut(&mut self, key: &ZendObject) -> Option<&mut V> {
+        self.entries
+            .get_mut(&key.get_id())
+            .filter(|(weak, _)| weak.points_to(key))
+            .map(|(_, value)| value)
+    }
+
+    /// Returns true if a value is associated with the object.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The object to look up.
+    pub fn contains_key(&self, key: &ZendObject) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes the value associated with the object, returning it.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The object to remove.
+    pub fn remove(&mut self, key: &ZendObject) -> Option<V> {
+        let id = key.get_id();
+        // Entries left behind by a destroyed object with the same id are dropped as
+        // well.
+        let (weak, value) = self.entries.remove(&id)?;
+        if weak.points_to(key) {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Removes the entries whose object has been destroyed, returning the
+    /// number of entries removed.
+    pub fn purge(&mut self) -> usize {
+        let len = self.entries.len();
+        self.entries.retain(|_, (weak, _)| weak.is_alive());
+        len - self.entries.len()
+    }
+
+    /// Returns the number of entries in the map, including the entries whose
+    /// object has been destroyed but which have not been purged yet.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes every entry from the map.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns an iterator over the entries whose object is still alive,
+    /// yielding a strong reference to each object along with its value.
+    pub fn iter(&self) -> impl Iterator<Item = (ZRc<ZendObject>, &V)> {
+        self.entries
+            .values()
+            .filter_map(|(weak, value)| Some((weak.get()?, value)))
+    }
+}
+
+impl<V> Default for WeakMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Debug> Debug for WeakMap<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -13,13 +13,14 @@ use crate::{
     convert::{FromZval, FromZvalMut, IntoZval, IntoZvalDyn},
     error::{Error, Result},
     ffi::{
-        _zval_struct__bindgen_ty_1, _zval_struct__bindgen_ty_2, zend_is_callable,
-        zend_is_identical, zend_is_iterable, zend_resource, zend_value, zval, zval_ptr_dtor,
+        _zval_struct__bindgen_ty_1, _zval_struct__bindgen_ty_2, ext_php_rs_persistent_zval_dtor,
+        zend_is_callable, zend_is_identical, zend_is_iterable, zend_resource, zend_value, zval,
+        zval_ptr_dtor,
     },
     flags::DataType,
     flags::ZvalTypeFlags,
-    rc::PhpRc,
-    types::{ZendCallable, ZendHashTable, ZendLong, ZendObject, ZendStr},
+    rc::{PhpRc, ZRc},
+    types::{CallArgs, ZendCallable, ZendHashTable, ZendLong, ZendObject, ZendReference, ZendStr},
 };
 
 /// A zend value. This is the primary storage container used throughout the Zend
@@ -112,6 +113,31 @@ impl Zval {
         }
     }
 
+    /// Returns a mutable reference to the underlying Zend string if the zval
+    /// contains a string.
+    ///
+    /// If the string is shared with other variables or is interned, the zval
+    /// is first given its own copy of the string, so changes made through the
+    /// returned reference are not seen by the other variables. This is the
+    /// equivalent of `zend_string_separate`.
+    pub fn zend_str_mut(&mut self) -> Option<&mut ZendStr> {
+        if !self.is_string() {
+            return None;
+        }
+        // SAFETY: The zval contains a string.
+        let str = unsafe { &mut *self.value.str_ };
+        if str.is_shared() {
+            let dup = str.to_owned();
+            if !str.is_immutable() {
+                // Can't reach zero, the string is shared.
+                str.dec_count();
+            }
+            self.u1.type_info = ZvalTypeFlags::StringEx.bits();
+            self.value.str_ = dup.into_raw();
+        }
+        unsafe { self.value.str_.as_mut() }
+    }
+
     /// Returns the value of the zval if it is a string.
     ///
     /// [`str()`]: #method.str
@@ -129,6 +155,13 @@ impl Zval {
         self.zend_str().and_then(|zs| zs.as_str().ok())
     }
 
+    /// Returns the bytes of the zval if it is a string. Unlike
+    /// [`str()`](#method.str), the string does not have to be valid UTF-8, and
+    /// the contents are not copied.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        self.zend_str().map(ZendStr::as_bytes)
+    }
+
     /// Returns the value of the zval if it is a string and can be unpacked into
     /// a vector of a given type. Similar to the [`unpack`] function in PHP,
     /// except you can only unpack one type.
@@ -192,12 +225,27 @@ impl Zval {
 
     /// Returns a mutable reference to the underlying zval hashtable if the zval
     /// contains an array.
+    ///
+    /// If the array is shared with other variables or is immutable, the zval
+    /// is first given its own copy of the array, so changes made through the
+    /// returned reference are not seen by the other variables. This is the
+    /// equivalent of `SEPARATE_ARRAY`.
     pub fn array_mut(&mut self) -> Option<&mut ZendHashTable> {
-        if self.is_array() {
-            unsafe { self.value.arr.as_mut() }
-        } else {
-            None
+        if !self.is_array() {
+            return None;
         }
+        // SAFETY: The zval contains an array.
+        let arr = unsafe { &mut *self.value.arr };
+        if arr.is_shared() {
+            let dup = arr.to_owned();
+            if !arr.is_immutable() {
+                // Can't reach zero, the array is shared.
+                arr.dec_count();
+            }
+            self.u1.type_info = ZvalTypeFlags::ArrayEx.bits();
+            self.value.arr = dup.into_raw();
+        }
+        unsafe { self.value.arr.as_mut() }
     }
 
     /// Returns the value of the zval if it is an object.
@@ -226,6 +274,20 @@ impl Zval {
             .try_call_method(name, params)
     }
 
+    /// Calls a method of the object contained in the zval with a tuple of
+    /// arguments or an [`ArgList`](crate::types::ArgList). See
+    /// [`ZendObject::try_call_method_with`].
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    /// * `args` - The arguments to call the method with.
+    pub fn try_call_method_with<A: CallArgs>(&self, name: &str, args: A) -> Result<Zval> {
+        self.object()
+            .ok_or(Error::Object)?
+            .try_call_method_with(name, args)
+    }
+
     /// Returns the value of the zval if it is an internal indirect reference.
     pub fn indirect(&self) -> Option<&Zval> {
         if self.is_indirect() {
@@ -263,6 +325,25 @@ impl Zval {
         }
     }
 
+    /// Returns the underlying PHP reference if the zval is a reference.
+    pub fn zend_reference(&self) -> Option<&ZendReference> {
+        if self.is_reference() {
+            unsafe { self.value.ref_.as_ref() }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the underlying PHP reference if the zval
+    /// is a reference.
+    pub fn zend_reference_mut(&mut self) -> Option<&mut ZendReference> {
+        if self.is_reference() {
+            unsafe { self.value.ref_.as_mut() }
+        } else {
+            None
+        }
+    }
+
     /// Returns the value of the zval if it is callable.
     pub fn callable(&self) -> Option<ZendCallable> {
         // The Zval is checked if it is callable in the `new` function.
@@ -445,6 +526,21 @@ impl Zval {
         self.value.str_ = val.into_raw();
     }
 
+    /// Sets the value of the zval as a shared Zend string, taking over the
+    /// reference held by the handle.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - String content.
+    pub fn set_shared_zend_string(&mut self, val: ZRc<ZendStr>) {
+        self.change_type(if val.is_immutable() {
+            ZvalTypeFlags::InternedStringEx
+        } else {
+            ZvalTypeFlags::StringEx
+        });
+        self.value.str_ = ZRc::into_raw(val);
+    }
+
     /// Sets the value of the zval as a binary string, which is represented in
     /// Rust as a vector.
     ///
@@ -567,6 +663,31 @@ impl Zval {
         self.value.arr = val.into_raw();
     }
 
+    /// Sets the value of the zval as a shared array, taking over the reference
+    /// held by the handle.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The value to set the zval as.
+    pub fn set_shared_hashtable(&mut self, val: ZRc<ZendHashTable>) {
+        self.change_type(if val.is_immutable() {
+            ZvalTypeFlags::Array
+        } else {
+            ZvalTypeFlags::ArrayEx
+        });
+        self.value.arr = ZRc::into_raw(val);
+    }
+
+    /// Sets the value of the zval as a PHP reference.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The reference to set the zval as.
+    pub fn set_reference(&mut self, val: ZBox<ZendReference>) {
+        self.change_type(ZvalTypeFlags::ReferenceEx);
+        self.value.ref_ = val.into_raw();
+    }
+
     /// Sets the value of the zval as a pointer.
     ///
     /// # Parameters
@@ -596,8 +717,15 @@ impl Zval {
     /// * `ty` - The new type of the zval.
     fn change_type(&mut self, ty: ZvalTypeFlags) {
         // SAFETY: we have exclusive mutable access to this zval so can free the
-        // contents.
-        unsafe { zval_ptr_dtor(self) };
+        // contents. Persistent arrays cannot be freed by `zval_ptr_dtor`.
+        unsafe {
+            match self.array() {
+                Some(arr) if arr.is_persistent() && !arr.is_immutable() => {
+                    ext_php_rs_persistent_zval_dtor(self)
+                }
+                _ => zval_ptr_dtor(self),
+            }
+        };
         self.u1.type_info = ty.bits();
     }
 
@@ -18,6 +18,298 @@ void ext_php_rs_set_known_valid_utf8(zend_string *zs) {
   }
 }
 
+void ext_php_rs_zend_string_forget_hash_val(zend_string *zs) {
+  zend_string_forget_hash_val(zs);
+}
+
+zend_reference *ext_php_rs_zend_new_reference(zval *val) {
+  zval ref;
+  ZVAL_NEW_REF(&ref, val);
+  return Z_REF(ref);
+}
+
+zend_string *ext_php_rs_zend_string_alloc(size_t len, bool persistent) {
+  zend_string *zs = zend_string_alloc(len, persistent);
+  ZSTR_VAL(zs)[len] = '\0';
+  return zs;
+}
+
+size_t ext_php_rs_smart_str_alloc(smart_str *str, size_t len, bool persistent) {
+  return smart_str_alloc(str, len, persistent);
+}
+
+void ext_php_rs_smart_str_append(smart_str *str, const char *val, size_t len, bool persistent) {
+  smart_str_appendl_ex(str, val, len, persistent);
+}
+
+void ext_php_rs_smart_str_append_long(smart_str *str, zend_long num, bool persistent) {
+  smart_str_append_long_ex(str, num, persistent);
+}
+
+zend_string *ext_php_rs_smart_str_extract(smart_str *str) {
+  if (str->s == NULL) {
+    return ZSTR_EMPTY_ALLOC();
+  }
+  zend_string *res;
+  smart_str_0(str);
+  res = str->s;
+  str->s = NULL;
+  str->a = 0;
+  return res;
+}
+
+void ext_php_rs_smart_str_free(smart_str *str, bool persistent) {
+  smart_str_free_ex(str, persistent);
+}
+
+void *ext_php_rs_pemalloc(size_t size, bool persistent) {
+  return pemalloc(size, persistent);
+}
+
+void ext_php_rs_pefree(void *ptr, bool persistent) {
+  pefree(ptr, persistent);
+}
+
+HashTable *ext_php_rs_zend_new_persistent_array(uint32_t size) {
+  HashTable *ht = pemalloc(sizeof(HashTable), 1);
+  zend_hash_init(ht, size, NULL, ext_php_rs_persistent_zval_dtor, 1);
+  return ht;
+}
+
+// Strings frozen by `ext_php_rs_persistent_freeze` are flagged as interned so
+// requests do not count references to them, but are owned by their array.
+static bool ext_php_rs_is_frozen_string(const zend_string *str) {
+  return (GC_FLAGS(str) & (IS_STR_INTERNED | IS_STR_PERSISTENT | IS_STR_PERMANENT)) ==
+         (IS_STR_INTERNED | IS_STR_PERSISTENT);
+}
+
+static void ext_php_rs_persistent_string_free(zend_string *str) {
+  if (ext_php_rs_is_frozen_string(str)) {
+    pefree(str, 1);
+  } else {
+    zend_string_release(str);
+  }
+}
+
+void ext_php_rs_persistent_array_free(HashTable *ht) {
+  zend_string *key;
+  zval *val;
+  ZEND_HASH_FOREACH_STR_KEY_VAL(ht, key, val) {
+    ext_php_rs_persistent_zval_dtor(val);
+    if (key) {
+      ext_php_rs_persistent_string_free(key);
+    }
+  } ZEND_HASH_FOREACH_END();
+  if (!(HT_FLAGS(ht) & HASH_FLAG_UNINITIALIZED)) {
+    pefree(HT_GET_DATA_ADDR(ht), 1);
+  }
+  pefree(ht, 1);
+}
+
+void ext_php_rs_persistent_zval_dtor(zval *zv) {
+  switch (Z_TYPE_P(zv)) {
+    case IS_STRING:
+      ext_php_rs_persistent_string_free(Z_STR_P(zv));
+      break;
+    case IS_ARRAY:
+      if (!(GC_FLAGS(Z_ARR_P(zv)) & IS_ARRAY_PERSISTENT)) {
+        zval_ptr_dtor(zv);
+      } else if ((GC_FLAGS(Z_ARR_P(zv)) & GC_IMMUTABLE) || GC_DELREF(Z_ARR_P(zv)) == 0) {
+        ext_php_rs_persistent_array_free(Z_ARR_P(zv));
+      }
+      break;
+    default:
+      zval_ptr_dtor(zv);
+  }
+}
+
+// Checks that a value only holds scalars and persistent strings and arrays,
+// each with a single owner, so it can be frozen and shared by requests.
+static bool ext_php_rs_persistent_check(zval *zv) {
+  switch (Z_TYPE_P(zv)) {
+    case IS_UNDEF:
+    case IS_NULL:
+    case IS_FALSE:
+    case IS_TRUE:
+    case IS_LONG:
+    case IS_DOUBLE:
+      return true;
+    case IS_STRING: {
+      zend_string *str = Z_STR_P(zv);
+      if (ZSTR_IS_INTERNED(str)) {
+        return GC_FLAGS(str) & IS_STR_PERMANENT;
+      }
+      return (GC_FLAGS(str) & IS_STR_PERSISTENT) && GC_REFCOUNT(str) == 1;
+    }
+    case IS_ARRAY: {
+      HashTable *ht = Z_ARR_P(zv);
+      zend_string *key;
+      zval *val;
+      if (GC_FLAGS(ht) & GC_IMMUTABLE) {
+        return false;
+      }
+      if (!(GC_FLAGS(ht) & IS_ARRAY_PERSISTENT) || GC_REFCOUNT(ht) != 1) {
+        return false;
+      }
+      ZEND_HASH_FOREACH_STR_KEY_VAL(ht, key, val) {
+        if (key && !ZSTR_IS_INTERNED(key) && GC_REFCOUNT(key) != 1) {
+          return false;
+        }
+        if (!ext_php_rs_persistent_check(val)) {
+          return false;
+        }
+      } ZEND_HASH_FOREACH_END();
+      return true;
+    }
+    default:
+      return false;
+  }
+}
+
+static void ext_php_rs_persistent_string_freeze(zend_string *str) {
+  if (!ZSTR_IS_INTERNED(str)) {
+    zend_string_hash_val(str);
+    GC_TYPE_INFO(str) = GC_STRING |
+        ((IS_STR_INTERNED | IS_STR_PERSISTENT | (GC_FLAGS(str) & IS_STR_VALID_UTF8))
+         << GC_FLAGS_SHIFT);
+  }
+}
+
+static void ext_php_rs_persistent_zval_freeze(zval *zv) {
+  if (Z_TYPE_P(zv) == IS_STRING) {
+    ext_php_rs_persistent_string_freeze(Z_STR_P(zv));
+    Z_TYPE_INFO_P(zv) = IS_INTERNED_STRING_EX;
+  } else if (Z_TYPE_P(zv) == IS_ARRAY) {
+    HashTable *ht = Z_ARR_P(zv);
+    zend_string *key;
+    zval *val;
+    ZEND_HASH_FOREACH_STR_KEY_VAL(ht, key, val) {
+      if (key) {
+        ext_php_rs_persistent_string_freeze(key);
+      }
+      ext_php_rs_persistent_zval_freeze(val);
+    } ZEND_HASH_FOREACH_END();
+    HT_FLAGS(ht) |= HASH_FLAG_STATIC_KEYS;
+    GC_SET_REFCOUNT(ht, 2);
+    GC_TYPE_INFO(ht) =
+        GC_ARRAY | ((IS_ARRAY_IMMUTABLE | IS_ARRAY_PERSISTENT | GC_NOT_COLLECTABLE) << GC_FLAGS_SHIFT);
+    Z_TYPE_INFO_P(zv) = IS_ARRAY;
+  }
+}
+
+bool ext_php_rs_persistent_freeze(zval *zv) {
+  if (!ext_php_rs_persistent_check(zv)) {
+    return false;
+  }
+  ext_php_rs_persistent_zval_freeze(zv);
+  return true;
+}
+
+// Renumbers the integer keys of a hashtable starting from zero, keeping string
+// keys and the order of the elements, the same way as `array_shift()`.
+void ext_php_rs_zend_hash_renumber(HashTable *ht) {
+  Bucket *p;
+  zend_long k = 0;
+  bool rehash = false;
+
+  if (HT_IS_PACKED(ht)) {
+    zend_hash_packed_to_hash(ht);
+  }
+  ZEND_HASH_FOREACH_BUCKET(ht, p) {
+    if (p->key == NULL) {
+      if (p->h != (zend_ulong) k) {
+        p->h = k;
+        rehash = true;
+      }
+      k++;
+    }
+  } ZEND_HASH_FOREACH_END();
+  ht->nNextFreeElement = k;
+  if (rehash) {
+    zend_hash_rehash(ht);
+  }
+}
+
+void *ext_php_rs_zend_mm_heap(bool during_request) {
+#ifdef ZTS
+  // Threads which were not started by PHP have no globals.
+  void *ls = tsrm_get_ls_cache();
+  if (ls == NULL) {
+    return NULL;
+  }
+  if (during_request &&
+      !((zend_executor_globals *) ((char *) ls + executor_globals_offset))->active) {
+    return NULL;
+  }
+#else
+  if (during_request && !executor_globals.active) {
+    return NULL;
+  }
+#endif
+  return zend_mm_get_heap();
+}
+
+#if PHP_VERSION_ID >= 80100
+#define EXT_PHP_RS_ARG_NUM(num) , num
+#else
+#define EXT_PHP_RS_ARG_NUM(num)
+#endif
+
+// Converts an argument to a scalar type in place, the same way as the
+// `Z_PARAM_*` macros do when the argument is not of the expected type: only
+// `int` to `float` is allowed under `strict_types`, otherwise the argument is
+// coerced using the usual rules.
+bool ext_php_rs_zend_parse_arg_scalar(zval *arg, uint32_t type, uint32_t arg_num) {
+  switch (type) {
+    case IS_LONG: {
+      zend_long dest;
+      if (!zend_parse_arg_long_slow(arg, &dest EXT_PHP_RS_ARG_NUM(arg_num))) {
+        return false;
+      }
+      zval_ptr_dtor(arg);
+      ZVAL_LONG(arg, dest);
+      return true;
+    }
+    case IS_DOUBLE: {
+      double dest;
+      if (!zend_parse_arg_double_slow(arg, &dest EXT_PHP_RS_ARG_NUM(arg_num))) {
+        return false;
+      }
+      zval_ptr_dtor(arg);
+      ZVAL_DOUBLE(arg, dest);
+      return true;
+    }
+    case _IS_BOOL: {
+      zend_bool dest;
+      if (!zend_parse_arg_bool_slow(arg, &dest EXT_PHP_RS_ARG_NUM(arg_num))) {
+        return false;
+      }
+      zval_ptr_dtor(arg);
+      ZVAL_BOOL(arg, dest);
+      return true;
+    }
+    case IS_STRING: {
+      // The argument is converted into a string in place.
+      zend_string *dest;
+      return zend_parse_arg_str_slow(arg, &dest EXT_PHP_RS_ARG_NUM(arg_num));
+    }
+    default:
+      return false;
+  }
+}
+
+void ext_php_rs_gc_buffer_add_zval(zend_get_gc_buffer *buf, zval *zv) {
+  zend_get_gc_buffer_add_zval(buf, zv);
+}
+
+void ext_php_rs_gc_buffer_add_obj(zend_get_gc_buffer *buf, zend_object *obj) {
+  zend_get_gc_buffer_add_obj(buf, obj);
+}
+
+void ext_php_rs_gc_buffer_use(zend_get_gc_buffer *buf, zval **table, int *n) {
+  zend_get_gc_buffer_use(buf, table, n);
+}
+
 const char *ext_php_rs_php_build_id() { return ZEND_MODULE_BUILD_ID; }
 
 void *ext_php_rs_zend_object_alloc(size_t obj_size, zend_class_entry *ce) {
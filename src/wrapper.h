@@ -19,18 +19,45 @@
 #include "ext/standard/info.h"
 #include "ext/standard/php_var.h"
 #include "ext/standard/file.h"
+#include "zend_closures.h"
 #include "zend_exceptions.h"
 #include "zend_inheritance.h"
 #include "zend_interfaces.h"
 #include "php_variables.h"
 #include "zend_ini.h"
+#include "zend_smart_str.h"
+#include "zend_weakrefs.h"
 #include "main/SAPI.h"
+#if PHP_VERSION_ID >= 80100
+#include "zend_enum.h"
+#include "zend_fibers.h"
+#endif
 
 zend_string *ext_php_rs_zend_string_init(const char *str, size_t len, bool persistent);
 void ext_php_rs_zend_string_release(zend_string *zs);
 bool ext_php_rs_is_known_valid_utf8(const zend_string *zs);
 void ext_php_rs_set_known_valid_utf8(zend_string *zs);
+void ext_php_rs_zend_string_forget_hash_val(zend_string *zs);
+zend_reference *ext_php_rs_zend_new_reference(zval *val);
+zend_string *ext_php_rs_zend_string_alloc(size_t len, bool persistent);
+size_t ext_php_rs_smart_str_alloc(smart_str *str, size_t len, bool persistent);
+void ext_php_rs_smart_str_append(smart_str *str, const char *val, size_t len, bool persistent);
+void ext_php_rs_smart_str_append_long(smart_str *str, zend_long num, bool persistent);
+zend_string *ext_php_rs_smart_str_extract(smart_str *str);
+void ext_php_rs_smart_str_free(smart_str *str, bool persistent);
+void *ext_php_rs_pemalloc(size_t size, bool persistent);
+void ext_php_rs_pefree(void *ptr, bool persistent);
+HashTable *ext_php_rs_zend_new_persistent_array(uint32_t size);
+void ext_php_rs_persistent_array_free(HashTable *ht);
+void ext_php_rs_persistent_zval_dtor(zval *zv);
+bool ext_php_rs_persistent_freeze(zval *zv);
+void ext_php_rs_zend_hash_renumber(HashTable *ht);
+void *ext_php_rs_zend_mm_heap(bool during_request);
+bool ext_php_rs_zend_parse_arg_scalar(zval *arg, uint32_t type, uint32_t arg_num);
 
+void ext_php_rs_gc_buffer_add_zval(zend_get_gc_buffer *buf, zval *zv);
+void ext_php_rs_gc_buffer_add_obj(zend_get_gc_buffer *buf, zend_object *obj);
+void ext_php_rs_gc_buffer_use(zend_get_gc_buffer *buf, zval **table, int *n);
 const char *ext_php_rs_php_build_id();
 void *ext_php_rs_zend_object_alloc(size_t obj_size, zend_class_entry *ce);
 void ext_php_rs_zend_object_release(zend_object *obj);
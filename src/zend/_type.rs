@@ -6,6 +6,7 @@ use crate::{
         _ZEND_SEND_MODE_SHIFT, _ZEND_TYPE_NULLABLE_BIT,
     },
     flags::DataType,
+    types::ZendStr,
 };
 
 /// Internal Zend type.
@@ -122,6 +123,84 @@ impl ZendType {
         }
     }
 
+    /// Attempts to create a zend type for a property declaration. Returns
+    /// [`None`] if PHP does not allow properties of the given data type, in
+    /// which case the property should be declared without a type.
+    ///
+    /// Unlike argument types, class names in property types are stored as
+    /// interned Zend strings.
+    ///
+    /// # Parameters
+    ///
+    /// * `type_` - Data type to create zend type for.
+    /// * `allow_null` - Whether the property should allow null values.
+    pub(crate) fn property_type(type_: DataType, allow_null: bool) -> Option<Self> {
+        match type_ {
+            DataType::Object(Some(class)) => {
+                let mut flags = crate::ffi::_ZEND_TYPE_NAME_BIT;
+                if allow_null {
+                    flags |= _ZEND_TYPE_NULLABLE_BIT
+                }
+
+                Some(Self {
+                    ptr: ZendStr::new_interned(class, true).into_raw() as *mut ZendStr
+                        as *mut c_void,
+                    type_mask: flags,
+                })
+            }
+            // `mixed` already includes null, and cannot be marked as nullable.
+            DataType::Mixed => Some(Self::empty_from_primitive_type(type_, false, false, false)),
+            DataType::Long
+            | DataType::Double
+            | DataType::String
+            | DataType::Bool
+            | DataType::Array
+            | DataType::Iterable
+            | DataType::Object(None) => Some(Self::empty_from_primitive_type(
+                type_, false, false, allow_null,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Creates a zend type for the argument of a function which is created at
+    /// runtime rather than registered with PHP, such as a closure. PHP only
+    /// converts the class names of registered functions into Zend strings, so
+    /// the class names are stored as interned Zend strings up front.
+    ///
+    /// # Parameters
+    ///
+    /// * `type_` - Data type to create zend type for.
+    /// * `pass_by_ref` - Whether the type should be passed by reference.
+    /// * `is_variadic` - Whether the type is for a variadic argument.
+    /// * `allow_null` - Whether the type should allow null to be passed in
+    ///   place.
+    pub(crate) fn runtime_arg_type(
+        type_: DataType,
+        pass_by_ref: bool,
+        is_variadic: bool,
+        allow_null: bool,
+    ) -> Self {
+        // `mixed` and `null` already include null, and cannot be marked as nullable.
+        let allow_null = allow_null && !matches!(type_, DataType::Mixed | DataType::Null);
+        match type_ {
+            DataType::Object(Some(class)) => {
+                let mut flags = Self::arg_info_flags(pass_by_ref, is_variadic)
+                    | crate::ffi::_ZEND_TYPE_NAME_BIT;
+                if allow_null {
+                    flags |= _ZEND_TYPE_NULLABLE_BIT
+                }
+
+                Self {
+                    ptr: ZendStr::new_interned(class, false).into_raw() as *mut ZendStr
+                        as *mut c_void,
+                    type_mask: flags,
+                }
+            }
+            type_ => Self::empty_from_primitive_type(type_, pass_by_ref, is_variadic, allow_null),
+        }
+    }
+
     /// Calculates the internal flags of the type.
     /// Translation of of the `_ZEND_ARG_INFO_FLAGS` macro from zend_API.h:110.
     ///
@@ -4,11 +4,11 @@
 
 use crate::ffi::{
     zend_ce_aggregate, zend_ce_argument_count_error, zend_ce_arithmetic_error, zend_ce_arrayaccess,
-    zend_ce_compile_error, zend_ce_countable, zend_ce_division_by_zero_error,
-    zend_ce_error_exception, zend_ce_exception, zend_ce_iterator, zend_ce_parse_error,
-    zend_ce_serializable, zend_ce_stringable, zend_ce_throwable, zend_ce_traversable,
-    zend_ce_type_error, zend_ce_unhandled_match_error, zend_ce_value_error,
-    zend_standard_class_def,
+    zend_ce_closure, zend_ce_compile_error, zend_ce_countable, zend_ce_division_by_zero_error,
+    zend_ce_error, zend_ce_error_exception, zend_ce_exception, zend_ce_iterator,
+    zend_ce_parse_error, zend_ce_serializable, zend_ce_stringable, zend_ce_throwable,
+    zend_ce_traversable, zend_ce_type_error, zend_ce_unhandled_match_error, zend_ce_value_error,
+    zend_ce_weakref, zend_standard_class_def,
 };
 
 use super::ClassEntry;
@@ -28,6 +28,11 @@ pub fn exception() -> &'static ClassEntry {
     unsafe { zend_ce_exception.as_ref() }.unwrap()
 }
 
+/// Returns the base [`Error`](https://www.php.net/manual/en/class.error.php) class.
+pub fn error() -> &'static ClassEntry {
+    unsafe { zend_ce_error.as_ref() }.unwrap()
+}
+
 /// Returns the base [`ErrorException`](https://www.php.net/manual/en/class.errorexception.php) class.
 pub fn error_exception() -> &'static ClassEntry {
     unsafe { zend_ce_error_exception.as_ref() }.unwrap()
@@ -107,3 +112,19 @@ pub fn countable() -> &'static ClassEntry {
 pub fn stringable() -> &'static ClassEntry {
     unsafe { zend_ce_stringable.as_ref() }.unwrap()
 }
+
+/// Returns the [`Closure`](https://www.php.net/manual/en/class.closure.php) class.
+pub fn closure() -> &'static ClassEntry {
+    unsafe { zend_ce_closure.as_ref() }.unwrap()
+}
+
+/// Returns the [`WeakReference`](https://www.php.net/manual/en/class.weakreference.php) class.
+pub fn weak_reference() -> &'static ClassEntry {
+    unsafe { zend_ce_weakref.as_ref() }.unwrap()
+}
+
+/// Returns the [`Fiber`](https://www.php.net/manual/en/class.fiber.php) class.
+#[cfg(any(php81, php82))]
+pub fn fiber() -> &'static ClassEntry {
+    unsafe { crate::ffi::zend_ce_fiber.as_ref() }.unwrap()
+}
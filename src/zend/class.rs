@@ -4,8 +4,13 @@ use crate::ffi::instanceof_function_slow;
 use crate::types::{ZendIterator, Zval};
 use crate::{
     boxed::ZBox,
-    ffi::zend_class_entry,
-    flags::ClassFlags,
+    convert::IntoZval,
+    error::{Error, Result},
+    ffi::{
+        zend_class_entry, zend_property_info, zend_read_static_property_ex,
+        zend_update_static_property_ex, ZEND_RESULT_CODE_SUCCESS,
+    },
+    flags::{ClassFlags, PropertyFlags},
     types::{ZendObject, ZendStr},
     zend::ExecutorGlobals,
 };
@@ -114,6 +119,102 @@ impl ClassEntry {
     pub fn name(&self) -> Option<&str> {
         unsafe { self.name.as_ref().and_then(|s| s.as_str().ok()) }
     }
+
+    /// Returns the information about a property declared on the class,
+    /// including properties inherited from parent classes.
+    ///
+    /// Returns [`None`] if no property with the given name has been declared.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    pub fn property_info(&self, name: &str) -> Option<&PropertyInfo> {
+        let info = self.properties_info.get(name)?;
+        unsafe { (info.value.ptr as *const PropertyInfo).as_ref() }
+    }
+
+    /// Returns a reference to the value of a static property of the class.
+    ///
+    /// Returns [`None`] if the class does not have a static property with the
+    /// given name.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the static property.
+    pub fn static_property(&self, name: &str) -> Option<&Zval> {
+        let mut name = ZendStr::new(name, false);
+
+        unsafe {
+            zend_read_static_property_ex(self as *const Self as *mut Self, name.deref_mut(), true)
+                .as_ref()
+        }
+    }
+
+    /// Sets the value of a static property of the class.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the static property.
+    /// * `value` - The new value of the static property.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value could not be converted into a zval, or the
+    /// class does not have a static property with the given name. In the
+    /// latter case, a PHP exception may also have been thrown.
+    pub fn set_static_property(&self, name: &str, value: impl IntoZval) -> Result<()> {
+        let mut name = ZendStr::new(name, false);
+        let mut value = value.into_zval(false)?;
+
+        let result = unsafe {
+            zend_update_static_property_ex(
+                self as *const Self as *mut Self,
+                name.deref_mut(),
+                &mut value,
+            )
+        };
+
+        if result == ZEND_RESULT_CODE_SUCCESS {
+            Ok(())
+        } else {
+            Err(Error::InvalidProperty)
+        }
+    }
+}
+
+/// Information about a property declared on a PHP class.
+pub type PropertyInfo = zend_property_info;
+
+impl PropertyInfo {
+    /// Returns the flags of the property.
+    pub fn flags(&self) -> PropertyFlags {
+        PropertyFlags::from_bits_truncate(self.flags)
+    }
+
+    /// Returns the class which declared the property.
+    pub fn class(&self) -> Option<&ClassEntry> {
+        unsafe { self.ce.as_ref() }
+    }
+
+    /// Checks if the property can be accessed from the given class scope,
+    /// following the PHP visibility rules. A `scope` of [`None`] represents
+    /// code running outside of any class.
+    ///
+    /// # Parameters
+    ///
+    /// * `scope` - The class scope the property is accessed from.
+    pub fn is_accessible_from(&self, scope: Option<&ClassEntry>) -> bool {
+        let flags = self.flags();
+        if !flags.intersects(PropertyFlags::Private | PropertyFlags::Protected) {
+            return true;
+        }
+
+        match (scope, self.class()) {
+            (Some(scope), Some(ce)) if flags.contains(PropertyFlags::Private) => scope == ce,
+            (Some(scope), Some(ce)) => scope.instance_of(ce) || ce.instance_of(scope),
+            _ => false,
+        }
+    }
 }
 
 impl PartialEq for ClassEntry {
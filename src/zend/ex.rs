@@ -1,4 +1,6 @@
-use crate::ffi::{zend_execute_data, ZEND_MM_ALIGNMENT, ZEND_MM_ALIGNMENT_MASK};
+use crate::ffi::{
+    zend_execute_data, ZEND_CALL_HAS_EXTRA_NAMED_PARAMS, ZEND_MM_ALIGNMENT, ZEND_MM_ALIGNMENT_MASK,
+};
 
 use crate::{
     args::ArgParser,
@@ -6,7 +8,7 @@ use crate::{
     types::{ZendClassObject, ZendObject, Zval},
 };
 
-use super::function::Function;
+use super::{function::Function, ClassEntry};
 
 /// Execute data passed when a function is called from PHP.
 ///
@@ -87,9 +89,24 @@ impl ExecuteData {
             args.push(arg);
         }
 
+        let mut parser = ArgParser::new(args);
+
+        // SAFETY: All fields of the `u1` union are the same size, and the named
+        // arguments which could not be matched to a parameter outlive the call.
+        if unsafe { self.This.u1.type_info } & ZEND_CALL_HAS_EXTRA_NAMED_PARAMS != 0 {
+            if let Some(named) = unsafe { self.extra_named_params.as_ref() } {
+                parser = parser.named(named);
+            }
+        }
+
+        // SAFETY: The function being called outlives the call.
+        if let Some(func) = unsafe { self.func.as_ref() } {
+            parser = parser.function(func);
+        }
+
         let obj = self.This.object_mut();
 
-        (ArgParser::new(args), obj)
+        (parser, obj)
     }
 
     /// Returns an [`ArgParser`] pre-loaded with the arguments contained inside
@@ -196,6 +213,34 @@ impl ExecuteData {
         self.This.object_mut()
     }
 
+    /// Returns the `$this` object of the call, or [`None`] if the function is
+    /// not called on an object, such as in static methods.
+    pub fn this(&self) -> Option<&ZendObject> {
+        self.This.object()
+    }
+
+    /// Returns the class the called function is declared in, the equivalent
+    /// of `self::class` in PHP. Returns [`None`] if the function is not a
+    /// method.
+    pub fn scope(&self) -> Option<&'static ClassEntry> {
+        // SAFETY: Class entries live for at least as long as the request.
+        unsafe { self.function()?.common.scope.as_ref() }
+    }
+
+    /// Returns the class the function was called on, the equivalent of
+    /// `static::class` in PHP. This is the class of `$this` for methods called
+    /// on an object, and the class named in the call for static methods,
+    /// which may be a subclass of [`scope`](Self::scope) when late static
+    /// binding is used. Returns [`None`] if the function is not a method.
+    pub fn called_scope(&self) -> Option<&'static ClassEntry> {
+        match self.This.object() {
+            Some(obj) => Some(obj.get_class_entry()),
+            // SAFETY: When `$this` is not an object, the value of the zval holds the called
+            // scope, which is null for functions which are not methods.
+            None => unsafe { self.This.value.ce.as_ref() },
+        }
+    }
+
     /// Attempt to retrieve the function that is being called.
     pub fn function(&self) -> Option<&Function> {
         unsafe { self.func.as_ref() }
@@ -215,7 +260,7 @@ impl ExecuteData {
     /// Since this is a private method it's up to the caller to ensure the
     /// lifetime isn't exceeded.
     #[doc(hidden)]
-    unsafe fn zend_call_arg<'a>(&self, n: usize) -> Option<&'a mut Zval> {
+    pub(crate) unsafe fn zend_call_arg<'a>(&self, n: usize) -> Option<&'a mut Zval> {
         let ptr = self.zend_call_var_num(n as isize);
         ptr.as_mut()
     }
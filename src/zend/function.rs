@@ -10,7 +10,7 @@ use crate::{
         zend_hash_str_find_ptr_lc,
     },
     flags::FunctionType,
-    types::Zval,
+    types::{call_function, CallArgs, Zval},
 };
 
 use super::ClassEntry;
@@ -133,4 +133,38 @@ impl Function {
 
         Ok(retval)
     }
+
+    /// Calls the function with a tuple of arguments or an [`ArgList`], which
+    /// allows named arguments and unpacked arrays to be passed.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The arguments to call the function with.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result wrapped in [`Ok`] upon success. If the arguments
+    /// are invalid or an exception is thrown, an [`Err`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{types::ArgList, zend::Function};
+    ///
+    /// let str_pad = Function::try_from_function("str_pad").unwrap();
+    /// let args = ArgList::new().arg("5").arg(3).named("pad_type", 0).named("pad_string", "0");
+    /// let result = str_pad.try_call_with(args).unwrap();
+    /// assert_eq!(result.string().as_deref(), Some("005"));
+    /// ```
+    pub fn try_call_with<A: CallArgs>(&self, args: A) -> Result<Zval> {
+        // SAFETY: Functions are not freed while a request is running.
+        unsafe {
+            call_function(
+                self as *const _ as *mut _,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                args,
+            )
+        }
+    }
 }
@@ -0,0 +1,51 @@
+use crate::{
+    ffi::{
+        ext_php_rs_gc_buffer_add_obj, ext_php_rs_gc_buffer_add_zval, ext_php_rs_gc_buffer_use,
+        zend_get_gc_buffer, zend_get_gc_buffer_create,
+    },
+    types::{ZendObject, Zval},
+};
+use std::os::raw::c_int;
+
+/// A buffer collecting the values held by an object, used by the cycle
+/// collector to find reference cycles.
+///
+/// PHP cannot see the values stored inside Rust structs. A class holding PHP
+/// values in its Rust struct should add them to the buffer in the
+/// [`get_gc`](crate::class::RegisteredClass::get_gc) handler, otherwise cycles
+/// going through the struct are never collected.
+pub type GcBuffer = zend_get_gc_buffer;
+
+impl GcBuffer {
+    /// Returns the buffer shared by the engine, emptied before being returned.
+    pub(crate) fn get() -> &'static mut Self {
+        // SAFETY: The engine always returns a valid buffer.
+        unsafe { &mut *zend_get_gc_buffer_create() }
+    }
+
+    /// Adds a value to the buffer. Values which are not reference counted are
+    /// ignored by the collector.
+    ///
+    /// # Parameters
+    ///
+    /// * `zv` - The value to add.
+    pub fn add_zval(&mut self, zv: &Zval) {
+        // SAFETY: The value is copied into the buffer without being modified.
+        unsafe { ext_php_rs_gc_buffer_add_zval(self, zv as *const Zval as *mut Zval) }
+    }
+
+    /// Adds an object to the buffer.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The object to add.
+    pub fn add_object(&mut self, obj: &ZendObject) {
+        // SAFETY: The pointer is copied into the buffer without being modified.
+        unsafe { ext_php_rs_gc_buffer_add_obj(self, obj as *const ZendObject as *mut ZendObject) }
+    }
+
+    /// Hands the contents of the buffer over to the collector.
+    pub(crate) unsafe fn use_buffer(&mut self, table: *mut *mut Zval, n: *mut c_int) {
+        ext_php_rs_gc_buffer_use(self, table, n)
+    }
+}
@@ -1,15 +1,23 @@
-use std::{ffi::c_void, mem::MaybeUninit, os::raw::c_int, ptr};
+use std::{
+    ffi::c_void,
+    mem::MaybeUninit,
+    os::raw::{c_char, c_int},
+    ptr, slice,
+};
 
 use crate::{
     class::RegisteredClass,
-    exception::PhpResult,
+    exception::{PhpException, PhpResult},
     ffi::{
-        std_object_handlers, zend_is_true, zend_object_handlers, zend_object_std_dtor,
-        zend_std_get_properties, zend_std_has_property, zend_std_read_property,
-        zend_std_write_property,
+        std_object_handlers, zend_get_executed_scope, zend_hash_str_update, zend_is_true,
+        zend_long, zend_object_handlers, zend_object_std_dtor, zend_objects_clone_members,
+        zend_result, zend_std_get_properties, zend_std_has_property, zend_std_read_property,
+        zend_std_write_property, _IS_BOOL, IS_DOUBLE, IS_LONG, IS_STRING, ZEND_RESULT_CODE_FAILURE,
+        ZEND_RESULT_CODE_SUCCESS,
     },
-    flags::ZvalTypeFlags,
+    flags::{DataType, Operator, PropertyFlags, ZvalTypeFlags},
     types::{ZendClassObject, ZendHashTable, ZendObject, ZendStr, Zval},
+    zend::{ce, GcBuffer, PropertyInfo},
 };
 
 /// A set of functions associated with a PHP class.
@@ -49,6 +57,23 @@ impl ZendObjectHandlers {
         (*ptr).write_property = Some(Self::write_property::<T>);
         (*ptr).get_properties = Some(Self::get_properties::<T>);
         (*ptr).has_property = Some(Self::has_property::<T>);
+        (*ptr).clone_obj = if T::CLONEABLE {
+            Some(Self::clone_obj::<T>)
+        } else {
+            None
+        };
+        (*ptr).compare = Some(Self::compare::<T>);
+        (*ptr).cast_object = Some(Self::cast_object::<T>);
+        (*ptr).read_dimension = Some(Self::read_dimension::<T>);
+        (*ptr).write_dimension = Some(Self::write_dimension::<T>);
+        (*ptr).has_dimension = Some(Self::has_dimension::<T>);
+        (*ptr).unset_dimension = Some(Self::unset_dimension::<T>);
+        (*ptr).count_elements = Some(Self::count_elements::<T>);
+        (*ptr).get_debug_info = Some(Self::get_debug_info::<T>);
+        (*ptr).do_operation = Some(Self::do_operation::<T>);
+        if T::HAS_GC {
+            (*ptr).get_gc = Some(Self::get_gc::<T>);
+        }
     }
 
     unsafe extern "C" fn free_obj<T: RegisteredClass>(object: *mut ZendObject) {
@@ -85,7 +110,6 @@ impl ZendObjectHandlers {
             let prop_name = member
                 .as_ref()
                 .ok_or("Invalid property name pointer given")?;
-            let self_ = &mut **obj;
             let props = T::get_metadata().get_properties();
             let prop = props.get(prop_name.as_str()?);
 
@@ -95,7 +119,8 @@ impl ZendObjectHandlers {
 
             Ok(match prop {
                 Some(prop) => {
-                    prop.get(self_, rv_mut)?;
+                    check_property_access::<T>(&*object, prop_name.as_str()?)?;
+                    prop.get(&*obj.try_borrow()?, rv_mut)?;
                     rv
                 }
                 None => zend_std_read_property(object, member, type_, cache_slot, rv),
@@ -132,14 +157,30 @@ impl ZendObjectHandlers {
             let prop_name = member
                 .as_ref()
                 .ok_or("Invalid property name pointer given")?;
-            let self_ = &mut **obj;
             let props = T::get_metadata().get_properties();
             let prop = props.get(prop_name.as_str()?);
             let value_mut = value.as_mut().ok_or("Invalid return zval given")?;
 
             Ok(match prop {
                 Some(prop) => {
-                    prop.set(self_, value_mut)?;
+                    let info = check_property_access::<T>(&*object, prop_name.as_str()?)?;
+                    #[cfg(any(php81, php82))]
+                    if let Some(info) =
+                        info.filter(|info| info.flags().contains(PropertyFlags::Readonly))
+                    {
+                        return Err(PhpException::new(
+                            format!(
+                                "Cannot modify readonly property {}::${}",
+                                declaring_class::<T>(info),
+                                prop_name.as_str()?
+                            ),
+                            0,
+                            ce::error(),
+                        ));
+                    }
+                    #[cfg(not(any(php81, php82)))]
+                    let _ = info;
+                    prop.set(&mut *obj.try_borrow_mut()?, value_mut)?;
                     value
                 }
                 None => zend_std_write_property(object, member, value, cache_slot),
@@ -167,17 +208,37 @@ impl ZendObjectHandlers {
                 .as_mut()
                 .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
                 .ok_or("Invalid object pointer given")?;
-            let self_ = &mut **obj;
+            let ce = obj.std.ce.as_ref();
+            // The Rust properties of uninitialized objects are left out, as they have no
+            // value yet.
+            if !obj.is_initialized() {
+                return Ok(());
+            }
+            let self_ = obj.try_borrow()?;
             let struct_props = T::get_metadata().get_properties();
 
             for (name, val) in struct_props {
                 let mut zv = Zval::new();
-                if val.get(self_, &mut zv).is_err() {
+                if val.get(&self_, &mut zv).is_err() {
                     continue;
                 }
-                props.insert(name, zv).map_err(|e| {
-                    format!("Failed to insert value into properties hashtable: {e:?}")
-                })?;
+                // Declared properties are stored under their mangled name, which
+                // includes the visibility of the property.
+                match ce.and_then(|ce| ce.property_info(name)) {
+                    Some(info) => {
+                        let key = info.name.as_ref().ok_or("Invalid property name pointer")?;
+                        zend_hash_str_update(
+                            props,
+                            key.as_bytes().as_ptr() as *const c_char,
+                            key.len(),
+                            &mut zv,
+                        );
+                        zv.release();
+                    }
+                    None => props.insert(name, zv).map_err(|e| {
+                        format!("Failed to insert value into properties hashtable: {e:?}")
+                    })?,
+                }
             }
 
             Ok(())
@@ -217,7 +278,27 @@ impl ZendObjectHandlers {
                 .ok_or("Invalid property name pointer given")?;
             let props = T::get_metadata().get_properties();
             let prop = props.get(prop_name.as_str()?);
-            let self_ = &mut **obj;
+
+            // Inaccessible properties are treated as if they do not exist.
+            if prop.is_some() && check_property_access::<T>(&*object, prop_name.as_str()?).is_err()
+            {
+                return Ok(0);
+            }
+
+            // The Rust properties of uninitialized objects are treated as if they are not
+            // set.
+            let self_ = match obj.obj {
+                Some(_) => obj.try_borrow()?,
+                None if prop.is_some() => return Ok((has_set_exists == 2) as c_int),
+                None => {
+                    return Ok(zend_std_has_property(
+                        object,
+                        member,
+                        has_set_exists,
+                        cache_slot,
+                    ))
+                }
+            };
 
             match has_set_exists {
                 //
@@ -225,7 +306,7 @@ impl ZendObjectHandlers {
                 0 => {
                     if let Some(val) = prop {
                         let mut zv = Zval::new();
-                        val.get(self_, &mut zv)?;
+                        val.get(&self_, &mut zv)?;
                         if !zv.is_null() {
                             return Ok(1);
                         }
@@ -236,7 +317,7 @@ impl ZendObjectHandlers {
                 1 => {
                     if let Some(val) = prop {
                         let mut zv = Zval::new();
-                        val.get(self_, &mut zv)?;
+                        val.get(&self_, &mut zv)?;
 
                         cfg_if::cfg_if! {
                             if #[cfg(php84)] {
@@ -282,4 +363,462 @@ impl ZendObjectHandlers {
             }
         }
     }
+
+    unsafe extern "C" fn clone_obj<T: RegisteredClass>(object: *mut ZendObject) -> *mut ZendObject {
+        let old = object
+            .as_mut()
+            .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
+            .expect("Invalid object pointer given for `clone_obj`");
+        // The object may be an instance of a subclass.
+        let ce = old.std.ce.as_ref();
+        let mut new = ZendClassObject::<T>::new_uninit(ce);
+
+        // The engine does not expect a null pointer, so on failure the uninitialized
+        // object is returned and discarded along with the exception.
+        match old.try_borrow().map(|val| T::clone_obj(&val)) {
+            Ok(Some(val)) => {
+                new.initialize(val);
+                zend_objects_clone_members(&mut new.std, &mut old.std);
+            }
+            Ok(None) => {
+                let _ = PhpException::new(
+                    format!(
+                        "Trying to clone an uncloneable object of class {}",
+                        ce.and_then(|ce| ce.name()).unwrap_or(T::CLASS_NAME)
+                    ),
+                    0,
+                    ce::error(),
+                )
+                .throw();
+            }
+            Err(e) => {
+                let _ = e.throw();
+            }
+        }
+
+        new.into_raw().get_mut_zend_obj()
+    }
+
+    unsafe extern "C" fn compare<T: RegisteredClass>(op1: *mut Zval, op2: *mut Zval) -> c_int {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            op1: *mut Zval,
+            op2: *mut Zval,
+        ) -> PhpResult<Option<c_int>> {
+            let op1 = op1.as_ref().ok_or("Invalid zval pointer given")?;
+            let op2 = op2.as_ref().ok_or("Invalid zval pointer given")?;
+
+            // The object may be on either side of the comparison.
+            let (self_, other, reversed) = match zval_class_obj::<T>(op1) {
+                Some(self_) => (self_, op2, false),
+                None => match zval_class_obj::<T>(op2) {
+                    Some(self_) => (self_, op1, true),
+                    None => return Ok(None),
+                },
+            };
+
+            Ok(self_.try_borrow()?.compare(other)?.map(|ord| {
+                if reversed {
+                    ord.reverse() as c_int
+                } else {
+                    ord as c_int
+                }
+            }))
+        }
+
+        match internal::<T>(op1, op2) {
+            Ok(Some(rv)) => rv,
+            Ok(None) => std_object_handlers
+                .compare
+                .map_or(ZEND_UNCOMPARABLE, |compare| compare(op1, op2)),
+            Err(e) => {
+                let _ = e.throw();
+                ZEND_UNCOMPARABLE
+            }
+        }
+    }
+
+    unsafe extern "C" fn cast_object<T: RegisteredClass>(
+        readobj: *mut ZendObject,
+        retval: *mut Zval,
+        type_: c_int,
+    ) -> zend_result {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            readobj: *mut ZendObject,
+            retval: *mut Zval,
+            type_: c_int,
+        ) -> PhpResult<bool> {
+            let self_ = match class_obj::<T>(readobj) {
+                Some(self_) => self_,
+                None => return Ok(false),
+            };
+            let ty = match type_ as u32 {
+                IS_STRING => DataType::String,
+                IS_LONG => DataType::Long,
+                IS_DOUBLE => DataType::Double,
+                _IS_BOOL => DataType::Bool,
+                _ => return Ok(false),
+            };
+            let val = match self_.try_borrow()?.cast(ty)? {
+                Some(val) => val,
+                None => return Ok(false),
+            };
+            let valid = match ty {
+                DataType::String => val.is_string(),
+                DataType::Long => val.is_long(),
+                DataType::Double => val.is_double(),
+                _ => val.is_bool(),
+            };
+            if !valid {
+                return Err(format!(
+                    "{}::cast() must return a value of type {}, {} returned",
+                    T::CLASS_NAME,
+                    ty,
+                    val.get_type()
+                )
+                .into());
+            }
+
+            // SAFETY: `retval` is uninitialized, so the value is written without
+            // dropping the previous contents.
+            ptr::write(retval, val);
+            Ok(true)
+        }
+
+        match internal::<T>(readobj, retval, type_) {
+            Ok(true) => ZEND_RESULT_CODE_SUCCESS,
+            Ok(false) => std_object_handlers
+                .cast_object
+                .map_or(ZEND_RESULT_CODE_FAILURE, |cast| {
+                    cast(readobj, retval, type_)
+                }),
+            Err(e) => {
+                let _ = e.throw();
+                ZEND_RESULT_CODE_FAILURE
+            }
+        }
+    }
+
+    unsafe extern "C" fn read_dimension<T: RegisteredClass>(
+        object: *mut ZendObject,
+        offset: *mut Zval,
+        type_: c_int,
+        rv: *mut Zval,
+    ) -> *mut Zval {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            offset: *mut Zval,
+            rv: *mut Zval,
+        ) -> PhpResult<bool> {
+            let (self_, offset) = match (class_obj::<T>(object), offset.as_ref()) {
+                (Some(self_), Some(offset)) => (self_, offset),
+                _ => return Ok(false),
+            };
+
+            match self_.try_borrow()?.read_dimension(offset)? {
+                Some(val) => {
+                    // SAFETY: `rv` is uninitialized, so the value is written without
+                    // dropping the previous contents.
+                    ptr::write(rv, val);
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+
+        match internal::<T>(object, offset, rv) {
+            Ok(true) => rv,
+            Ok(false) => std_object_handlers
+                .read_dimension
+                .map_or(ptr::null_mut(), |read| read(object, offset, type_, rv)),
+            Err(e) => {
+                let _ = e.throw();
+                (*rv).set_null();
+                rv
+            }
+        }
+    }
+
+    unsafe extern "C" fn write_dimension<T: RegisteredClass>(
+        object: *mut ZendObject,
+        offset: *mut Zval,
+        value: *mut Zval,
+    ) {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            offset: *mut Zval,
+            value: *mut Zval,
+        ) -> PhpResult<bool> {
+            let self_ = match class_obj::<T>(object) {
+                Some(self_) => self_,
+                None => return Ok(false),
+            };
+            let value = value.as_ref().ok_or("Invalid value zval given")?;
+
+            self_
+                .try_borrow_mut()?
+                .write_dimension(offset.as_ref(), value)
+        }
+
+        match internal::<T>(object, offset, value) {
+            Ok(true) => {}
+            Ok(false) => {
+                if let Some(write) = std_object_handlers.write_dimension {
+                    write(object, offset, value);
+                }
+            }
+            Err(e) => {
+                let _ = e.throw();
+            }
+        }
+    }
+
+    unsafe extern "C" fn has_dimension<T: RegisteredClass>(
+        object: *mut ZendObject,
+        member: *mut Zval,
+        check_empty: c_int,
+    ) -> c_int {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            member: *mut Zval,
+            check_empty: c_int,
+        ) -> PhpResult<Option<bool>> {
+            match (class_obj::<T>(object), member.as_ref()) {
+                (Some(self_), Some(member)) => {
+                    self_.try_borrow()?.has_dimension(member, check_empty != 0)
+                }
+                _ => Ok(None),
+            }
+        }
+
+        match internal::<T>(object, member, check_empty) {
+            Ok(Some(rv)) => rv as c_int,
+            Ok(None) => std_object_handlers
+                .has_dimension
+                .map_or(0, |has| has(object, member, check_empty)),
+            Err(e) => {
+                let _ = e.throw();
+                0
+            }
+        }
+    }
+
+    unsafe extern "C" fn unset_dimension<T: RegisteredClass>(
+        object: *mut ZendObject,
+        offset: *mut Zval,
+    ) {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            offset: *mut Zval,
+        ) -> PhpResult<bool> {
+            match (class_obj::<T>(object), offset.as_ref()) {
+                (Some(self_), Some(offset)) => self_.try_borrow_mut()?.unset_dimension(offset),
+                _ => Ok(false),
+            }
+        }
+
+        match internal::<T>(object, offset) {
+            Ok(true) => {}
+            Ok(false) => {
+                if let Some(unset) = std_object_handlers.unset_dimension {
+                    unset(object, offset);
+                }
+            }
+            Err(e) => {
+                let _ = e.throw();
+            }
+        }
+    }
+
+    unsafe extern "C" fn count_elements<T: RegisteredClass>(
+        object: *mut ZendObject,
+        count: *mut zend_long,
+    ) -> zend_result {
+        let self_ = match class_obj::<T>(object) {
+            Some(self_) => self_,
+            None => return ZEND_RESULT_CODE_FAILURE,
+        };
+
+        // Returning failure without an exception lets the engine fall back to
+        // `Countable::count()`.
+        match self_.try_borrow().and_then(|self_| self_.count_elements()) {
+            Ok(Some(rv)) => {
+                *count = rv as zend_long;
+                ZEND_RESULT_CODE_SUCCESS
+            }
+            Ok(None) => ZEND_RESULT_CODE_FAILURE,
+            Err(e) => {
+                let _ = e.throw();
+                ZEND_RESULT_CODE_FAILURE
+            }
+        }
+    }
+
+    unsafe extern "C" fn get_debug_info<T: RegisteredClass>(
+        object: *mut ZendObject,
+        is_temp: *mut c_int,
+    ) -> *mut ZendHashTable {
+        let info = class_obj::<T>(object)
+            .map(|self_| self_.try_borrow().map(|self_| self_.debug_info()))
+            .transpose();
+        match info {
+            Ok(Some(Some(info))) => {
+                // The engine takes ownership of temporary tables.
+                *is_temp = 1;
+                info.into_raw()
+            }
+            Err(e) => {
+                let _ = e.throw();
+                ptr::null_mut()
+            }
+            Ok(_) => std_object_handlers
+                .get_debug_info
+                .map_or(ptr::null_mut(), |info| info(object, is_temp)),
+        }
+    }
+
+    unsafe extern "C" fn do_operation<T: RegisteredClass>(
+        opcode: u8,
+        result: *mut Zval,
+        op1: *mut Zval,
+        op2: *mut Zval,
+    ) -> zend_result {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            opcode: u8,
+            result: *mut Zval,
+            op1: *mut Zval,
+            op2: *mut Zval,
+        ) -> PhpResult<bool> {
+            let op = match Operator::from_opcode(opcode) {
+                Some(op) => op,
+                None => return Ok(false),
+            };
+            let lhs = op1.as_ref().ok_or("Invalid zval pointer given")?;
+            let val = match T::do_operation(op, lhs, op2.as_ref())? {
+                Some(val) => val,
+                None => return Ok(false),
+            };
+
+            if result == op1 {
+                // Compound assignments such as `$a += $b` write the result into the
+                // left-hand operand, which must be released.
+                *result = val;
+            } else {
+                // SAFETY: `result` is uninitialized, so the value is written without
+                // dropping the previous contents.
+                ptr::write(result, val);
+            }
+            Ok(true)
+        }
+
+        // Returning failure without an exception lets the engine fall back to the
+        // default behaviour of the operator.
+        match internal::<T>(opcode, result, op1, op2) {
+            Ok(true) => ZEND_RESULT_CODE_SUCCESS,
+            Ok(false) => ZEND_RESULT_CODE_FAILURE,
+            Err(e) => {
+                let _ = e.throw();
+                ZEND_RESULT_CODE_FAILURE
+            }
+        }
+    }
+
+    unsafe extern "C" fn get_gc<T: RegisteredClass>(
+        object: *mut ZendObject,
+        table: *mut *mut Zval,
+        n: *mut c_int,
+    ) -> *mut ZendHashTable {
+        let gc = GcBuffer::get();
+        // Like `zend_std_get_gc()`, visit the declared properties directly unless the
+        // properties hashtable has been built, in which case it refers to them
+        // through indirect zvals and is visited instead, so that they are not
+        // visited twice.
+        let obj = &*object;
+        if obj.properties.is_null() {
+            let count = (*obj.ce).default_properties_count.max(0) as usize;
+            for prop in slice::from_raw_parts(obj.properties_table.as_ptr(), count) {
+                gc.add_zval(prop);
+            }
+        }
+        // The value is left out while it is being modified, in which case the values
+        // it holds are treated as referenced from outside of the cycle.
+        if let Some(Ok(self_)) = class_obj::<T>(object).map(|self_| self_.try_borrow()) {
+            self_.get_gc(gc);
+        }
+        gc.use_buffer(table, n);
+        obj.properties
+    }
+}
+
+/// Value returned by the `compare` handler when the operands cannot be
+/// compared.
+const ZEND_UNCOMPARABLE: c_int = 1;
+
+/// Returns an object of class `T`, or [`None`] if the object is not an instance
+/// of the class or has not been initialized.
+unsafe fn class_obj<'a, T: RegisteredClass>(
+    object: *mut ZendObject,
+) -> Option<&'a mut ZendClassObject<T>> {
+    object
+        .as_mut()
+        .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
+        .filter(|obj| obj.is_initialized())
+}
+
+/// Returns an object of class `T` contained in a zval, or [`None`] if the
+/// object is not an instance of the class or has not been initialized.
+fn zval_class_obj<T: RegisteredClass>(zv: &Zval) -> Option<&ZendClassObject<T>> {
+    zv.object()
+        .and_then(|obj| ZendClassObject::<T>::from_zend_obj(obj))
+        .filter(|obj| obj.is_initialized())
+}
+
+/// Checks that a property declared on the class of `object` can be accessed
+/// from the currently executing scope, returning the information about the
+/// declared property.
+///
+/// Returns [`None`] if the property has not been declared on the class, in
+/// which case it is treated as public.
+fn check_property_access<'a, T: RegisteredClass>(
+    object: &'a ZendObject,
+    name: &str,
+) -> PhpResult<Option<&'a PropertyInfo>> {
+    let info = match unsafe { object.ce.as_ref() }.and_then(|ce| ce.property_info(name)) {
+        Some(info) => info,
+        None => return Ok(None),
+    };
+    let scope = unsafe { zend_get_executed_scope().as_ref() };
+
+    if info.is_accessible_from(scope) {
+        Ok(Some(info))
+    } else {
+        let visibility = if info.flags().contains(PropertyFlags::Private) {
+            "private"
+        } else {
+            "protected"
+        };
+        Err(PhpException::new(
+            format!(
+                "Cannot access {} property {}::${}",
+                visibility,
+                declaring_class::<T>(info),
+                name
+            ),
+            0,
+            ce::error(),
+        ))
+    }
+}
+
+/// Returns the name of the class which declared a property.
+fn declaring_class<T: RegisteredClass>(info: &PropertyInfo) -> &str {
+    info.class()
+        .and_then(|ce| ce.name())
+        .unwrap_or(T::CLASS_NAME)
 }
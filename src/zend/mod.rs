@@ -5,6 +5,7 @@ pub mod ce;
 mod class;
 mod ex;
 mod function;
+mod gc;
 mod globals;
 mod handlers;
 mod ini_entry_def;
@@ -20,10 +21,11 @@ use crate::{
 use std::ffi::CString;
 
 pub use _type::ZendType;
-pub use class::ClassEntry;
+pub use class::{ClassEntry, PropertyInfo};
 pub use ex::ExecuteData;
 pub use function::Function;
 pub use function::FunctionEntry;
+pub use gc::GcBuffer;
 pub use globals::ExecutorGlobals;
 pub use globals::FileGlobals;
 pub use globals::ProcessGlobals;
@@ -0,0 +1,21 @@
+use std::{env, process::Command};
+
+/// Sets the `php81_plus` cfg flag when the tests are built against PHP 8.1 or
+/// later, which is required by some of the exported types.
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(php81_plus)");
+    println!("cargo:rerun-if-env-changed=PHP");
+
+    let php = env::var("PHP").unwrap_or_else(|_| "php".into());
+    let version = Command::new(php)
+        .args(["-r", "echo PHP_VERSION_ID;"])
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|version| version.trim().parse::<u32>().ok())
+        .unwrap_or(0);
+
+    if version >= 80100 {
+        println!("cargo:rustc-cfg=php81_plus");
+    }
+}
@@ -9,3 +9,15 @@ function assert_exception_thrown(callable $callback): void
     }
     throw new Exception("Exception was not thrown", 255);
 }
+
+function assert_error_message(callable $callback, string $class, string $message): void
+{
+    try {
+        $callback();
+    } catch (\Throwable $th) {
+        assert($th instanceof $class);
+        assert($th->getMessage() === $message);
+        return;
+    }
+    throw new Exception("Exception was not thrown", 255);
+}
@@ -0,0 +1,7 @@
+<?php
+
+require('_utils.php');
+
+// Memory allocated during a request is tracked by the Zend memory manager.
+assert(test_zend_allocator(1 << 20));
+assert(test_zend_allocator_realloc(1000));
@@ -0,0 +1,4 @@
+#[test]
+fn allocator_works() {
+    assert!(crate::integration::run_php("allocator.php"));
+}
@@ -24,3 +24,37 @@ assert(array_key_exists('c', $assoc));
 assert(in_array('1', $assoc));
 assert(in_array('2', $assoc));
 assert(in_array('3', $assoc));
+
+// Tests lazy array views
+$list = [1, 2, 3, 'a'];
+assert(test_array_view($list, 1) === 2);
+assert(test_array_view($list, 10) === null);
+assert_exception_thrown(fn () => test_array_view($list, 3));
+assert(test_array_view_sum([1, 2, 3]) === 6);
+assert_exception_thrown(fn () => test_array_view_sum($list));
+
+$map = ['host' => 'localhost', 'port' => 80];
+assert(test_map_view($map, 'host') === 'localhost');
+assert(test_map_view($map, 'user') === null);
+assert_exception_thrown(fn () => test_map_view($map, 'port'));
+assert(test_map_view_keys(['a' => 1, 5 => 2, -1 => 3]) === ['string:a', 'int:5', 'int:-1']);
+
+// Tests mixed keys
+$keys = test_array_keys(['remove' => true, 'keep' => true]);
+assert($keys === ['keep' => true, -1 => 'negative', 10 => 'numeric', 'count' => 1]);
+
+[$popped, $shifted, $sorted] = test_array_ops([5 => 3, 'a' => 1], [2, 'b' => 4, 9]);
+assert($popped === 9);
+assert($shifted === 3);
+assert($sorted === ['a' => 1, 0 => 2, 'b' => 4]);
+
+// Tests copy-on-write separation
+$shared = [1, 2];
+$copy = $shared;
+assert(test_array_push($shared) === 3);
+assert($shared === [1, 2, 'pushed']);
+assert($copy === [1, 2]);
+
+$counted = test_array_rc($copy);
+assert($copy === [1, 2]);
+assert($counted === [1, 2, 'total' => 2]);
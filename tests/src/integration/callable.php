@@ -3,3 +3,33 @@
 require('_utils.php');
 
 assert(test_callable(fn (string $a) => $a, 'test') === 'test');
+
+// Typed calls
+assert(test_callable_typed(fn (int $a, int $b) => $a * $b) === 9900);
+assert(test_callable_typed('max') === 4953);
+assert_exception_thrown(fn () => test_callable_typed(fn () => []));
+assert_exception_thrown(fn () => test_callable_typed(fn () => throw new Exception('test')));
+
+// By-reference arguments
+assert(test_callable_ref(function (string &$value) { $value .= '!'; }, 'test') === 'test!');
+assert(test_callable_ref(fn (string $value) => $value . '!', 'test') === 'test');
+assert(test_callable_ref_invalid(function (string &$value) { $value .= '!'; }, 'test') === 'test');
+
+// Named arguments and unpacking
+$join = fn ($a, $b = 0, $c = 0) => "$a-$b-$c";
+assert(test_call_named($join) === '1-2-3');
+assert_exception_thrown(fn () => test_call_named(fn ($a) => $a));
+assert(test_call_spread(fn (...$args) => $args, [1, 2, 'x' => 3]) === [0, 1, 2, 'x' => 3]);
+assert(test_call_spread($join, ['c' => 5]) === '0-0-5');
+assert_exception_thrown(fn () => test_call_spread($join, ['a' => 1]));
+assert_exception_thrown(fn () => test_call_positional_after_named($join));
+
+class Greeter
+{
+    public function greet(string $name, string $suffix = '.'): string
+    {
+        return "Hello $name$suffix";
+    }
+}
+
+assert(test_call_method_named(new Greeter(), 'World') === 'Hello World!');
@@ -15,7 +15,32 @@ assert($class->getNumber() === 2022);
 $class->setNumber(2023);
 assert($class->getNumber() === 2023);
 
+// Tests method arguments are parsed like the ones of functions
+$class->setNumber('2024');
+assert($class->getNumber() === 2024);
+assert_error_message(fn () => $class->setNumber('abc'), TypeError::class, 'TestClass::setNumber(): Argument #1 ($number) must be of type int, string given');
+assert_error_message(fn () => $class->setNumber(), ArgumentCountError::class, 'TestClass::setNumber() expects exactly 1 argument, 0 given');
+
 // Tests #prop decorator
 assert($class->boolean);
 $class->boolean = false;
 assert($class->boolean === false);
+
+// Tests constants computed at registration
+assert(TestFactory::DEFAULTS === [1, 2, 3]);
+assert(TestFactory::LABEL === 'factory-3');
+
+// Tests late static binding
+class TestFactoryChild extends TestFactory {}
+
+$obj = TestFactory::create(1);
+assert(get_class($obj) === TestFactory::class);
+assert($obj->value === 1);
+
+$child = TestFactoryChild::create(2);
+assert($child instanceof TestFactoryChild);
+assert($child->value === 2);
+
+assert(TestFactory::calledClass() === 'TestFactory');
+assert(TestFactoryChild::calledClass() === 'TestFactoryChild');
+assert($child->instanceClass() === 'TestFactoryChild');
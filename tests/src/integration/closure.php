@@ -3,6 +3,7 @@
 require('_utils.php');
 
 $v = test_closure();
+assert($v instanceof Closure);
 
 // Closure
 assert($v('works') === 'works');
@@ -18,5 +19,23 @@ function take(\stdClass $rs): void { }
 try {
     take($closure);
 } catch (\TypeError $e) {
-    assert(str_starts_with($e->getMessage(), 'take(): Argument #1 ($rs) must be of type stdClass, RustClosure given, called in '));
-}
\ No newline at end of file
+    assert(str_starts_with($e->getMessage(), 'take(): Argument #1 ($rs) must be of type stdClass, Closure given, called in '));
+}
+
+// Typed closures expose their signature through reflection
+$typed = test_closure_typed();
+$r = new ReflectionFunction($typed);
+assert($r->getNumberOfParameters() === 2);
+assert($r->getNumberOfRequiredParameters() === 2);
+assert($r->getParameters()[0]->getName() === 'name');
+assert((string) $r->getParameters()[0]->getType() === 'string');
+assert((string) $r->getParameters()[1]->getType() === 'int');
+assert((string) $r->getReturnType() === 'string');
+assert($typed('a', 3) === 'aaa');
+assert($typed(times: 2, name: 'b') === 'bb');
+assert(Closure::fromCallable($typed) === $typed);
+
+// Bound closures receive the object as their first argument
+$bound = test_closure_bound(new \stdClass());
+assert((new ReflectionFunction($bound))->getNumberOfParameters() === 1);
+assert($bound('!') === 'stdClass!');
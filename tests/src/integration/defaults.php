@@ -0,0 +1,34 @@
+<?php
+
+require('_utils.php');
+
+// Default values are applied when arguments are omitted or skipped by name.
+assert(test_defaults() === "5:it's:Some([1, 2])");
+assert(test_defaults(1) === "1:it's:Some([1, 2])");
+assert(test_defaults(1, 'a', null) === '1:a:None');
+assert(test_defaults(s: 'b') === '5:b:Some([1, 2])');
+assert(test_defaults(list: [3]) === "5:it's:Some([3])");
+assert(test_defaults(null) === "5:it's:Some([1, 2])");
+
+$obj = new TestProperties(1);
+assert($obj->increment() === 2);
+assert($obj->increment(3) === 5);
+assert($obj->increment(null) === 6);
+
+// Default values are visible through reflection.
+$params = (new ReflectionFunction('test_defaults'))->getParameters();
+assert($params[0]->isDefaultValueAvailable());
+assert($params[0]->getDefaultValue() === 5);
+assert($params[1]->getDefaultValue() === "it's");
+assert($params[2]->getDefaultValue() === [1, 2]);
+assert((new ReflectionMethod(TestProperties::class, 'increment'))->getParameters()[0]->getDefaultValue() === 1);
+
+// Default values can be constant expressions.
+if (PHP_VERSION_ID >= 80100) {
+    assert(test_enum_default() === TestSuit::Hearts);
+    assert(test_enum_default(TestSuit::Spades) === TestSuit::Spades);
+
+    $param = (new ReflectionFunction('test_enum_default'))->getParameters()[0];
+    assert($param->isDefaultValueConstant());
+    assert($param->getDefaultValue() === TestSuit::Hearts);
+}
@@ -0,0 +1,4 @@
+#[test]
+fn defaults_works() {
+    assert!(crate::integration::run_php("defaults.php"));
+}
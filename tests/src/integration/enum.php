@@ -0,0 +1,25 @@
+<?php
+
+require('_utils.php');
+
+// Pure enums
+assert(TestSuit::Hearts instanceof UnitEnum);
+assert(!(TestSuit::Hearts instanceof BackedEnum));
+assert(test_enum(TestSuit::Hearts) === TestSuit::Spades);
+assert(test_enum(TestSuit::Spades) === TestSuit::Hearts);
+assert(count(TestSuit::cases()) === 2);
+
+// Backed enums
+assert(TestStatus::Active instanceof BackedEnum);
+assert(TestStatus::Active->value === 1);
+assert(TestStatus::Disabled->value === 0);
+assert(TestStatus::from(0) === TestStatus::Disabled);
+assert(TestStatus::tryFrom(5) === null);
+assert(test_enum_backed(TestStatus::Active) === TestStatus::Active);
+
+// Methods and constants
+assert(TestStatus::Active->label() === 'active');
+assert(TestStatus::Disabled->label() === 'inactive');
+assert(TestStatus::DEFAULT === 1);
+
+assert_exception_thrown(fn () => test_enum(TestStatus::Active));
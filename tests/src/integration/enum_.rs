@@ -0,0 +1,4 @@
+#[test]
+fn enum_works() {
+    assert!(crate::integration::run_php("enum.php"));
+}
@@ -0,0 +1,18 @@
+<?php
+
+require('_utils.php');
+
+// Rust functions called inside of a PHP fiber can suspend it.
+$fiber = new Fiber(fn () => test_fiber_suspend(10));
+assert($fiber->start() === 10);
+$fiber->resume(5);
+assert($fiber->isTerminated());
+assert($fiber->getReturn() === 6);
+
+assert_exception_thrown(fn () => test_fiber_suspend(1));
+
+// Fibers can be created and driven from Rust.
+assert(test_fiber_roundtrip() === [1, 1, 42]);
+
+// Fibers are resumed in the order their futures are woken.
+assert(test_fiber_executor() === [2, 3, 1]);
@@ -0,0 +1,4 @@
+#[test]
+fn fiber_works() {
+    assert!(crate::integration::run_php("fiber.php"));
+}
@@ -0,0 +1,68 @@
+<?php
+
+require('_utils.php');
+
+class TestChild extends TestBase {
+    public int $calls = 0;
+
+    public function __construct(string $name) {
+        $this->calls++;
+        parent::__construct(strtoupper($name));
+    }
+
+    public function greeting(): string {
+        return 'Hi, ' . $this->name;
+    }
+}
+
+class TestUnconstructed extends TestBase {
+    public function __construct() {}
+}
+
+class TestUnconstructedVector extends TestVector {
+    public function __construct() {}
+}
+
+// Subclasses are constructed through `parent::__construct`
+$base = new TestBase('base');
+$child = new TestChild('child');
+assert($child instanceof TestBase);
+assert($child->name === 'CHILD');
+assert($child->calls === 1);
+
+// Rust properties are accessed on instances of subclasses
+$child->name = 'changed';
+assert($child->name === 'changed');
+
+// Methods called from Rust resolve to the overrides of subclasses
+assert($base->greet() === 'Hello, base');
+assert($child->greet() === 'Hi, changed');
+assert($child->greeting() === 'Hi, changed');
+
+// Properties are read while the object is borrowed by a method
+assert($base->describe(fn () => $base->name . ' ' . $base->greeting()) === 'base: base Hello, base');
+
+// Methods called while the object is being modified throw
+$base->rename(function () use ($base) {
+    try {
+        $base->greeting();
+    } catch (Error $e) {
+        return $e->getMessage();
+    }
+    return 'not thrown';
+});
+assert($base->greeting() === 'Hello, Cannot access the TestBase object while it is being modified');
+$base->rename(fn () => 'base');
+assert($base->greeting() === 'Hello, base');
+
+// Objects which were not initialized by the Rust constructor throw
+$obj = new TestUnconstructed();
+assert(!isset($obj->name));
+assert_exception_thrown(fn () => $obj->name);
+assert_exception_thrown(fn () => $obj->greeting());
+assert(is_array(get_object_vars($obj)));
+assert_error_message(
+    fn () => clone new TestUnconstructedVector(),
+    Error::class,
+    'The TestUnconstructedVector object has not been correctly initialized by its constructor'
+);
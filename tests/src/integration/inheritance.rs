@@ -0,0 +1,4 @@
+#[test]
+fn inheritance_works() {
+    assert!(crate::integration::run_php("inheritance.php"));
+}
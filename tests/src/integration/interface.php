@@ -0,0 +1,17 @@
+<?php
+
+require('_utils.php');
+
+assert(interface_exists('TestShape'));
+assert(TestShape::SIDES === 0);
+
+$square = new TestSquare(2.0);
+assert($square instanceof TestShape);
+assert($square->area() === 4.0);
+assert(TestSquare::describe('a') === 'square a');
+
+$methods = array_map(fn ($m) => $m->getName(), (new ReflectionClass('TestShape'))->getMethods());
+assert(in_array('area', $methods));
+assert(in_array('describe', $methods));
+assert((new ReflectionMethod('TestShape', 'describe'))->isStatic());
+assert((new ReflectionMethod('TestShape', 'area'))->isAbstract());
@@ -0,0 +1,4 @@
+#[test]
+fn interface_works() {
+    assert!(crate::integration::run_php("interface.php"));
+}
@@ -0,0 +1,61 @@
+<?php
+
+require('_utils.php');
+
+$collection = new TestCollection([1, 2, 3]);
+
+assert($collection instanceof Countable);
+assert($collection instanceof ArrayAccess);
+assert($collection instanceof IteratorAggregate);
+assert($collection instanceof Stringable);
+
+// Countable
+assert(count($collection) === 3);
+assert($collection->count() === 3);
+
+// ArrayAccess
+assert($collection[0] === 1);
+assert($collection->offsetGet(1) === 2);
+assert($collection[10] === null);
+assert($collection[-1] === null);
+$collection[0] = 5;
+$collection[] = 4;
+assert($collection[0] === 5);
+assert($collection[3] === 4);
+assert(isset($collection[3]));
+assert(!isset($collection[4]));
+assert($collection->offsetExists(2));
+assert_exception_thrown(function () use ($collection) {
+    unset($collection[3]);
+});
+assert(count($collection) === 4);
+assert_exception_thrown(fn () => $collection['a']);
+assert_exception_thrown(fn () => $collection[0] = 'a');
+assert_exception_thrown(fn () => $collection[10] = 1);
+
+// IteratorAggregate
+$items = [];
+foreach ($collection as $key => $value) {
+    $items[$key] = $value;
+}
+assert($items === [5, 2, 3, 4]);
+assert(iterator_to_array($collection) === [5, 2, 3, 4]);
+assert($collection->getIterator() instanceof Iterator);
+foreach ($collection as $value) {
+    break;
+}
+$collection[0] = 1;
+assert_error_message(
+    function () use ($collection) {
+        foreach ($collection as $value) {
+            $collection[] = $value;
+        }
+    },
+    Error::class,
+    'Cannot access the TestCollection object while it is in use'
+);
+assert(count($collection) === 4);
+$collection[0] = 5;
+
+// Stringable
+assert((string) $collection === '[5, 2, 3, 4]');
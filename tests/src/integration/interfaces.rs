@@ -0,0 +1,4 @@
+#[test]
+fn interfaces_work() {
+    assert!(crate::integration::run_php("interfaces.php"));
+}
@@ -0,0 +1,35 @@
+<?php
+
+require('_utils.php');
+
+$it = test_iterator_range(3);
+assert($it instanceof Iterator);
+assert(iterator_to_array($it) === [0, 1, 2]);
+
+// Iterators can only be traversed once.
+assert_exception_thrown(fn () => iterator_to_array($it));
+
+$it = test_iterator_boxed(2);
+assert($it->valid());
+assert($it->key() === 0);
+assert($it->current() === 0);
+$it->rewind();
+$it->next();
+assert($it->key() === 1);
+assert($it->current() === 2);
+$it->next();
+assert(!$it->valid());
+assert($it->current() === null);
+assert_exception_thrown(fn () => $it->rewind());
+
+assert(iterator_to_array(test_iterator_pairs()) === ['a' => 1, 'b' => 2]);
+
+// Items are pulled lazily, so infinite iterators can be consumed.
+foreach (test_iterator_infinite() as $key => $value) {
+    if ($value === 1000) {
+        break;
+    }
+}
+assert($key === 1000);
+
+assert_exception_thrown(fn () => new RustIterator());
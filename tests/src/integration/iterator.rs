@@ -0,0 +1,4 @@
+#[test]
+fn iterator_works() {
+    assert!(crate::integration::run_php("iterator.php"));
+}
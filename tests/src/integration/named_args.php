@@ -0,0 +1,19 @@
+<?php
+
+require('_utils.php');
+
+// Named arguments are resolved against the parameter names.
+assert(test_named_args(1) === '1:None:None');
+assert(test_named_args(1, c: 3) === '1:None:Some(3)');
+assert(test_named_args(c: 3, a: 1, b: 2) === '1:Some(2):Some(3)');
+
+assert_error_message(fn () => test_named_args(1, d: 4), Error::class, 'Unknown named parameter $d');
+assert_error_message(fn () => test_named_args(1, a: 2), Error::class, 'Named parameter $a overwrites previous argument');
+assert_error_message(fn () => test_named_args(b: 2), ArgumentCountError::class, 'test_named_args(): Argument #1 ($a) not passed');
+
+// Variadic parameters collect the remaining positional and unknown named arguments.
+assert(test_variadic('a') === ['a']);
+assert(test_variadic('a', 'b', 'c') === ['a', 'b', 'c']);
+assert(test_variadic('a', 'b', z: 'z', y: 'y') === ['a', 'b', 'y=y', 'z=z']);
+assert(test_variadic(first: 'a', x: 'x') === ['a', 'x=x']);
+assert(test_variadic(...['a', 'b', 'k' => 'v']) === ['a', 'b', 'k=v']);
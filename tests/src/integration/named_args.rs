@@ -0,0 +1,4 @@
+#[test]
+fn named_args_works() {
+    assert!(crate::integration::run_php("named_args.php"));
+}
@@ -4,3 +4,5 @@ require('_utils.php');
 
 assert(is_null(test_nullable()));
 assert(!is_null(test_nullable('value')));
+assert(is_null(test_nullable(null)));
+assert_exception_thrown(fn () => test_nullable([]));
@@ -16,3 +16,15 @@ assert_exception_thrown(fn () => test_number_unsigned(-12));
 assert(round(test_number_float(-1.2), 2) === round(-1.2, 2));
 assert(round(test_number_float(0.0), 2) === round(0.0, 2));
 assert(round(test_number_float(1.2), 2) === round(1.2, 2));
+
+// Scalars are coerced without `strict_types`
+assert(test_number_signed('12') === 12);
+assert(test_number_signed(true) === 1);
+assert(test_number_unsigned('12') === 12);
+assert(test_number_float(2) === 2.0);
+
+// Errors match the ones of PHP's own functions
+assert_error_message(fn () => test_number_signed('abc'), TypeError::class, 'test_number_signed(): Argument #1 ($a) must be of type int, string given');
+assert_error_message(fn () => test_number_unsigned(-12), ValueError::class, 'test_number_unsigned(): Argument #1 ($a) must be between 0 and 4294967295');
+assert_error_message(fn () => test_number_signed(), ArgumentCountError::class, 'test_number_signed() expects exactly 1 argument, 0 given');
+assert_error_message(fn () => test_number_signed(1, 2), ArgumentCountError::class, 'test_number_signed() expects exactly 1 argument, 2 given');
@@ -0,0 +1,49 @@
+<?php
+
+require('_utils.php');
+
+$vec = new TestVector([1, 2, 3]);
+
+// Comparison
+assert($vec == new TestVector([6]));
+assert($vec < new TestVector([4, 5]));
+assert(($vec <=> 6) === 0);
+assert((10 <=> $vec) === 1);
+
+// Casts
+assert((string) $vec === '[1, 2, 3]');
+assert((int) $vec === 6);
+assert((bool) $vec === true);
+assert((bool) new TestVector([]) === false);
+
+// Clone
+$copy = clone $vec;
+$copy[] = 4;
+assert(count($copy) === 4);
+assert(count($vec) === 3);
+assert_exception_thrown(fn () => clone test_class('lorem ipsum', 2022));
+
+// Dimension access
+assert($vec[1] === 2);
+$vec[1] = 5;
+assert($vec[1] === 5);
+assert(isset($vec[2]));
+assert(!isset($vec[3]));
+$zero = new TestVector([0]);
+assert(empty($zero[0]));
+unset($vec[0]);
+assert($vec[0] === 5);
+assert_exception_thrown(fn () => $vec[10]);
+assert_exception_thrown(fn () => $vec['a'] = 1);
+
+// Count
+assert(count($vec) === 2);
+
+// Operators
+$sum = $vec + 1;
+assert($sum instanceof TestVector);
+assert($sum->sum() === 10);
+assert(($vec * 2)->sum() === 16);
+$vec += 1;
+assert($vec->sum() === 10);
+assert_exception_thrown(fn () => $vec - 1);
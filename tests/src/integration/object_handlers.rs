@@ -0,0 +1,4 @@
+#[test]
+fn object_handlers_work() {
+    assert!(crate::integration::run_php("object_handlers.php"));
+}
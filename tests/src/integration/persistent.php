@@ -0,0 +1,19 @@
+<?php
+
+require('_utils.php');
+
+// Tests reading shared values
+$config = test_persistent_get('config');
+assert($config === ['hosts' => ['10.0.0.1', '10.0.0.2']]);
+assert(isset($config['hosts']));
+assert(test_persistent_get('answer') === 42);
+assert(test_persistent_get('missing') === null);
+
+// Tests shared values are copied on write
+$config['hosts'][] = '10.0.0.3';
+$config['port'] = 8080;
+assert(count($config['hosts']) === 3);
+assert(test_persistent_get('config') === ['hosts' => ['10.0.0.1', '10.0.0.2']]);
+
+// Tests request-bound values are rejected
+assert(test_persistent_request_bound());
@@ -0,0 +1,4 @@
+#[test]
+fn persistent_works() {
+    assert!(crate::integration::run_php("persistent.php"));
+}
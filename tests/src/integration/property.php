@@ -0,0 +1,35 @@
+<?php
+
+require('_utils.php');
+
+// Typed properties
+$obj = new TestProperties(5);
+assert($obj->count === 5);
+$obj->count = 10;
+assert($obj->count === 10);
+assert($obj->label === null);
+
+$class = new ReflectionClass(TestProperties::class);
+assert((string) $class->getProperty('count')->getType() === 'int');
+assert((string) $class->getProperty('label')->getType() === '?string');
+
+// Visibility
+assert($class->getProperty('secret')->isProtected());
+assert(!isset($obj->secret));
+assert_exception_thrown(fn () => $obj->secret);
+assert_exception_thrown(fn () => $obj->secret = 'visible');
+
+// Static properties
+assert($class->getProperty('instances')->isStatic());
+assert(TestProperties::$instances === 0);
+assert(test_static_property(3) === 3);
+assert(TestProperties::$instances === 3);
+
+// Readonly properties
+if (PHP_VERSION_ID >= 80100) {
+    $readonly = new TestReadonly(1);
+    assert($readonly->id === 1);
+    assert((new ReflectionProperty(TestReadonly::class, 'id'))->isReadOnly());
+    assert_exception_thrown(fn () => $readonly->id = 2);
+    assert($readonly->id === 1);
+}
@@ -0,0 +1,4 @@
+#[test]
+fn property_works() {
+    assert!(crate::integration::run_php("property.php"));
+}
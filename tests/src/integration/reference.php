@@ -0,0 +1,37 @@
+<?php
+
+require('_utils.php');
+
+// Tests out parameters
+assert(test_ref_parse_int('42', $parsed) === true);
+assert($parsed === 42);
+assert(test_ref_parse_int('nope', $unparsed) === false);
+assert($unparsed === null);
+
+$greeting = 'hello';
+$alias = &$greeting;
+test_ref_append($greeting);
+assert($greeting === 'hello!');
+assert($alias === 'hello!');
+
+$values = ['a' => 'x'];
+test_ref_append($values['a']);
+test_ref_append($values['b']);
+assert($values === ['a' => 'x!', 'b' => '!']);
+
+// Tests typed property references
+class TestRefHolder {
+    public int $count = 0;
+    public ?string $label = null;
+}
+
+$holder = new TestRefHolder();
+test_ref_append($holder->label);
+assert($holder->label === '!');
+assert_exception_thrown(fn () => test_ref_append($holder->count));
+assert($holder->count === 0);
+
+// Tests references created in Rust
+$shared = test_reference_shared();
+$shared[0] = 5;
+assert($shared[1] === 5);
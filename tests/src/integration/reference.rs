@@ -0,0 +1,4 @@
+#[test]
+fn reference_works() {
+    assert!(crate::integration::run_php("reference.php"));
+}
@@ -0,0 +1,30 @@
+<?php
+
+require('_utils.php');
+
+// Tests creating resources
+$counter = test_counter_open(5);
+assert(is_resource($counter));
+assert(get_resource_type($counter) === 'test counter');
+assert(test_counter_get($counter) === 5);
+
+// Tests mutating resources
+assert(test_counter_bump($counter) === 6);
+assert(test_counter_bump($counter) === 7);
+assert(test_counter_get($counter) === 7);
+
+// Tests resources of other types are rejected
+$file = fopen('php://memory', 'r');
+assert_exception_thrown(fn () => test_counter_get($file));
+fclose($file);
+assert_exception_thrown(fn () => test_counter_get('not a resource'));
+
+// Tests closed resources are rejected
+test_counter_close($counter);
+assert(!is_resource($counter));
+assert(get_resource_type($counter) === 'Unknown');
+assert_exception_thrown(fn () => test_counter_get($counter));
+
+// Tests persistent resources
+assert(test_counter_persistent('test_counter') === 0);
+assert(test_counter_persistent('test_counter') === 1);
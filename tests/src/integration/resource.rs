@@ -0,0 +1,4 @@
+#[test]
+fn resource_works() {
+    assert!(crate::integration::run_php("resource.php"));
+}
@@ -0,0 +1,49 @@
+<?php
+
+require('_utils.php');
+
+$user = [
+    'name' => 'Alice',
+    'age' => 42,
+    'tags' => ['admin', 'ops'],
+    'address' => ['city' => 'Paris'],
+    'role' => ['Admin' => ['level' => 3]],
+];
+
+// Structs become associative arrays, enums become tagged arrays.
+assert(test_serde_roundtrip($user) === $user);
+
+$guest = test_serde_roundtrip(['address' => null, 'role' => 'Guest'] + $user);
+assert($guest['address'] === null);
+assert($guest['role'] === 'Guest');
+
+// Objects can be deserialized like arrays.
+$object = (object) $user;
+$object->address = (object) $user['address'];
+assert(test_serde_roundtrip($object) === $user);
+
+// Structs can be serialized as `stdClass` objects.
+$object = test_serde_object($user);
+assert($object instanceof stdClass);
+assert($object->name === 'Alice');
+assert($object->address instanceof stdClass);
+assert($object->address->city === 'Paris');
+assert($object->role['Admin'] instanceof stdClass);
+assert($object->role['Admin']->level === 3);
+
+// Errors carry the path of the failing value.
+try {
+    test_serde_roundtrip(['tags' => ['admin', 5]] + $user);
+    assert(false);
+} catch (Exception $e) {
+    assert(str_starts_with($e->getMessage(), 'tags[1]: invalid type'));
+}
+
+try {
+    test_serde_roundtrip(['age' => 300] + $user);
+    assert(false);
+} catch (Exception $e) {
+    assert(str_starts_with($e->getMessage(), 'age: invalid value'));
+}
+
+assert_exception_thrown(fn () => test_serde_roundtrip(['role' => 'Owner'] + $user));
@@ -0,0 +1,12 @@
+<?php
+
+declare(strict_types=1);
+
+require('_utils.php');
+
+// Only integers are coerced into floats with `strict_types`
+assert(test_number_signed(12) === 12);
+assert(test_number_float(2) === 2.0);
+
+assert_error_message(fn () => test_number_signed('12'), TypeError::class, 'test_number_signed(): Argument #1 ($a) must be of type int, string given');
+assert_error_message(fn () => test_number_float('1.5'), TypeError::class, 'test_number_float(): Argument #1 ($a) must be of type float, string given');
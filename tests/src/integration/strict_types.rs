@@ -0,0 +1,4 @@
+#[test]
+fn strict_types_works() {
+    assert!(crate::integration::run_php("strict_types.php"));
+}
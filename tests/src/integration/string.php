@@ -4,3 +4,30 @@ require('_utils.php');
 
 assert(test_str('abc') === 'abc');
 assert(test_string('abc') === 'abc');
+
+$interned = 'abc';
+assert(test_str_upper($interned) === 'ABC');
+assert($interned === 'abc');
+
+$dynamic = str_repeat('ab', 2);
+$upper = test_str_upper($dynamic);
+assert($dynamic === 'abab');
+$lookup = [$upper => true];
+assert(isset($lookup['ABAB']));
+
+// Tests binary-safe strings
+$binary = "\xff\x00\xfe";
+assert(test_bytes($binary) === $binary);
+assert(test_bytes_cow($binary) === "\xff");
+assert(test_bytes_cow('abc') === 'abc');
+assert(test_bytes_vec($binary) === "\xfe\x00\xff");
+assert_exception_thrown(fn () => test_str($binary));
+
+// Tests building strings
+assert(test_smart_str(['a', 'b'], 7) === 'a7b7|2');
+assert(test_smart_str([], 0) === '|0');
+
+// Tests string helpers
+assert(test_str_helpers("Ab\xff") === ["Ab\xff\x00!", "b\xff", "ab\xff", "AB\xff"]);
+assert(test_str_helpers('') === ["\x00!", '', '', '']);
+assert(test_str_intern(str_repeat('interned', 2)));
@@ -15,8 +15,8 @@ const TYPES = [
     'test_binary' => [['string'], 'string'],
     'test_nullable' => [['?string'], '?string'],
     'test_object' => [['object'], 'object'],
-    'test_closure' => [[], 'RustClosure'],
-    'test_closure_once' => [['string'], 'RustClosure'],
+    'test_closure' => [[], 'Closure'],
+    'test_closure_once' => [['string'], 'Closure'],
     'test_callable' => [['callable', 'string'], 'mixed']
 ];
 
@@ -0,0 +1,45 @@
+<?php
+
+require('_utils.php');
+
+$listeners = new TestListeners();
+$a = new stdClass();
+$b = new stdClass();
+$listeners->listen($a, 1);
+$listeners->listen($b, 2);
+assert($listeners->priority($a) === 1);
+assert($listeners->priority($b) === 2);
+
+// The map does not keep its keys alive.
+unset($a);
+assert($listeners->live() === 1);
+
+// A new object reusing the id of a destroyed key is not found.
+$c = new stdClass();
+assert($listeners->priority($c) === null);
+assert($listeners->purge() === 1);
+assert($listeners->priority($b) === 2);
+
+// Cycles going through values held by the Rust struct are collected.
+$listeners->hold($listeners);
+$weak = WeakReference::create($listeners);
+unset($listeners);
+assert($weak->get() !== null);
+gc_collect_cycles();
+assert($weak->get() === null);
+
+// Declared properties of subclasses are visited once, including after the
+// properties table has been built.
+class TestGcListeners extends TestListeners {
+    public ?object $peer = null;
+}
+
+$listeners = new TestGcListeners();
+$peer = new stdClass();
+$listeners->peer = $peer;
+$peer->owner = $listeners;
+assert(count(get_object_vars($listeners)) === 1);
+unset($listeners);
+gc_collect_cycles();
+assert($peer->owner instanceof TestGcListeners);
+assert($peer->owner->peer === $peer);
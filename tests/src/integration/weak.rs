@@ -0,0 +1,4 @@
+#[test]
+fn weak_works() {
+    assert!(crate::integration::run_php("weak.php"));
+}
@@ -1,6 +1,47 @@
 #![cfg_attr(windows, feature(abi_vectorcall))]
-use ext_php_rs::{binary::Binary, prelude::*, types::ZendObject, types::Zval};
-use std::collections::HashMap;
+#[cfg(php81_plus)]
+use ext_php_rs::fiber::{Fiber, FiberExecutor};
+use ext_php_rs::{
+    alloc::ZendAllocator,
+    args::Variadic,
+    binary::Binary,
+    boxed::ZBox,
+    class::RegisteredClass,
+    convert::IntoZval,
+    flags::{DataType, Operator},
+    persistent::{PersistentCache, PersistentZval},
+    prelude::*,
+    rc::ZRc,
+    types::ZendClassObject,
+    types::ZendObject,
+    types::Zval,
+    types::{
+        ArgList, ArrayKey, ArrayView, MapView, Ref, SmartStr, WeakMap, ZendHashTable,
+        ZendReference, ZendResource, ZendStr,
+    },
+    zend::{ClassEntry, GcBuffer},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    alloc::Layout,
+    borrow::Cow,
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    fmt::Write,
+    ops::{Index, IndexMut},
+    sync::OnceLock,
+};
+#[cfg(php81_plus)]
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+};
 
 #[php_function]
 pub fn test_str(a: &str) -> &str {
@@ -12,6 +53,59 @@ pub fn test_string(a: String) -> String {
     a
 }
 
+#[php_function]
+pub fn test_str_upper(mut a: ZRc<ZendStr>) -> ZRc<ZendStr> {
+    ZRc::make_mut(&mut a).as_bytes_mut().make_ascii_uppercase();
+    a
+}
+
+#[php_function]
+pub fn test_bytes(a: &[u8]) -> &[u8] {
+    a
+}
+
+#[php_function]
+pub fn test_bytes_cow(a: Cow<[u8]>) -> Cow<[u8]> {
+    match a.iter().position(|&b| b == 0) {
+        Some(pos) => Cow::Owned(a[..pos].to_vec()),
+        None => a,
+    }
+}
+
+#[php_function]
+pub fn test_bytes_vec(a: Binary<u8>) -> Binary<u8> {
+    let mut bytes: Vec<u8> = a.into();
+    bytes.reverse();
+    Binary::new(bytes)
+}
+
+#[php_function]
+pub fn test_smart_str(parts: Vec<String>, count: i64) -> SmartStr {
+    let mut buf = SmartStr::new(false);
+    for part in &parts {
+        buf.append(part);
+        buf.append_long(count);
+    }
+    write!(buf, "|{}", parts.len()).unwrap();
+    buf
+}
+
+#[php_function]
+pub fn test_str_helpers(a: ZRc<ZendStr>) -> Vec<ZBox<ZendStr>> {
+    vec![
+        a.concat(b"\0!", false),
+        a.substr(1.., false)
+            .unwrap_or_else(|| ZendStr::new("", false)),
+        a.to_ascii_lowercase(false),
+        a.to_ascii_uppercase(false),
+    ]
+}
+
+#[php_function]
+pub fn test_str_intern(a: ZRc<ZendStr>) -> bool {
+    ZendStr::intern(a.concat("", false)).is_interned()
+}
+
 #[php_function]
 pub fn test_bool(a: bool) -> bool {
     a
@@ -42,6 +136,77 @@ pub fn test_array_assoc(a: HashMap<String, String>) -> HashMap<String, String> {
     a
 }
 
+#[php_function]
+pub fn test_array_view(a: ArrayView<i64>, index: u64) -> PhpResult<Option<i64>> {
+    Ok(a.get(index)?)
+}
+
+#[php_function]
+pub fn test_array_view_sum(a: ArrayView<i64>) -> PhpResult<i64> {
+    let mut sum = 0;
+    for n in a {
+        sum += n?;
+    }
+    Ok(sum)
+}
+
+#[php_function]
+pub fn test_map_view(a: MapView<String, &str>, key: &str) -> PhpResult<Option<String>> {
+    Ok(a.get(key)?.map(str::to_owned))
+}
+
+#[php_function]
+pub fn test_map_view_keys(a: MapView<ArrayKey, &Zval>) -> PhpResult<Vec<String>> {
+    a.iter()
+        .map(|entry| {
+            let (key, _) = entry?;
+            Ok(match key {
+                ArrayKey::Long(key) => format!("int:{}", key),
+                key => format!("string:{}", key),
+            })
+        })
+        .collect()
+}
+
+#[php_function]
+pub fn test_array_keys(a: &ZendHashTable) -> ZBox<ZendHashTable> {
+    let mut a = a.to_owned();
+    a.insert_key(-1, "negative");
+    a.insert_key("10", "numeric");
+    a.entry("count").or_insert(0).unwrap();
+    a.entry("count")
+        .and_modify(|count| count.set_long(count.long().unwrap_or_default() + 1))
+        .or_insert(0)
+        .unwrap();
+    a.retain(|key, _| key != &ArrayKey::from("remove"));
+    a
+}
+
+#[php_function]
+pub fn test_array_ops(a: &ZendHashTable, b: &ZendHashTable) -> Vec<Zval> {
+    let mut a = a.to_owned();
+    a.append(b);
+    let popped = a.pop().unwrap_or_default();
+    let shifted = a.shift().unwrap_or_default();
+    a.sort_by(|x, y| x.long().cmp(&y.long()));
+    let mut sorted = Zval::new();
+    sorted.set_hashtable(a);
+    vec![popped, shifted, sorted]
+}
+
+#[php_function]
+pub fn test_array_push(a: &mut ZendHashTable) -> usize {
+    a.push("pushed").unwrap();
+    a.len()
+}
+
+#[php_function]
+pub fn test_array_rc(mut a: ZRc<ZendHashTable>) -> ZRc<ZendHashTable> {
+    let total = a.len() as i64;
+    ZRc::make_mut(&mut a).insert("total", total).unwrap();
+    a
+}
+
 #[php_function]
 pub fn test_binary(a: Binary<u32>) -> Binary<u32> {
     a
@@ -52,11 +217,295 @@ pub fn test_nullable(a: Option<String>) -> Option<String> {
     a
 }
 
+#[php_function]
+pub fn test_named_args(a: i64, b: Option<i64>, c: Option<i64>) -> String {
+    format!("{a}:{b:?}:{c:?}")
+}
+
+#[php_function]
+pub fn test_variadic(first: &str, rest: Variadic) -> Vec<String> {
+    let mut named: Vec<_> = rest.named.into_iter().collect();
+    named.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    std::iter::once(first.to_string())
+        .chain(rest.positional.iter().filter_map(|zv| zv.string()))
+        .chain(
+            named
+                .into_iter()
+                .filter_map(|(name, zv)| Some(format!("{name}={}", zv.string()?))),
+        )
+        .collect()
+}
+
+#[php_function(defaults(n = 5, s = "it's"), default_exprs(list = "[1, 2]"))]
+pub fn test_defaults(n: i64, s: &str, list: Option<Vec<i64>>) -> String {
+    format!("{n}:{s}:{list:?}")
+}
+
 #[php_function]
 pub fn test_object(a: &mut ZendObject) -> &mut ZendObject {
     a
 }
 
+#[php_function]
+pub fn test_ref_parse_int(input: &str, mut result: Ref<i64>) -> PhpResult<bool> {
+    match input.parse() {
+        Ok(value) => {
+            result.set(value)?;
+            Ok(true)
+        }
+        Err(_) => Ok(false),
+    }
+}
+
+#[php_function]
+pub fn test_ref_append(mut value: Ref<String>) -> PhpResult<()> {
+    let current = value.get().unwrap_or_default();
+    value.set(current + "!")?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_reference_shared() -> PhpResult<Vec<Zval>> {
+    let mut first = Zval::new();
+    first.set_reference(ZendReference::new(1)?);
+    let second = first.shallow_clone();
+    Ok(vec![first, second])
+}
+
+#[php_resource(name = "test counter")]
+pub struct TestCounter {
+    count: i64,
+}
+
+#[php_function]
+pub fn test_counter_open(start: i64) -> TestCounter {
+    TestCounter { count: start }
+}
+
+#[php_function]
+pub fn test_counter_get(counter: &TestCounter) -> i64 {
+    counter.count
+}
+
+#[php_function]
+pub fn test_counter_bump(counter: &mut TestCounter) -> i64 {
+    counter.count += 1;
+    counter.count
+}
+
+#[php_function]
+pub fn test_counter_close(counter: &mut ZendResource) {
+    counter.close();
+}
+
+#[php_resource(name = "test shared counter")]
+pub struct TestSharedCounter {
+    count: std::cell::Cell<i64>,
+}
+
+#[php_function]
+pub fn test_counter_persistent(key: &str) -> i64 {
+    match ZendResource::find_persistent(key).and_then(|res| res.value::<TestSharedCounter>()) {
+        Some(counter) => {
+            counter.count.set(counter.count.get() + 1);
+            counter.count.get()
+        }
+        None => {
+            ZendResource::new_persistent(
+                key,
+                TestSharedCounter {
+                    count: Default::default(),
+                },
+            );
+            0
+        }
+    }
+}
+
+static TEST_CACHE: OnceLock<PersistentCache> = OnceLock::new();
+
+fn test_cache() -> &'static PersistentCache {
+    TEST_CACHE.get_or_init(|| {
+        let mut config = HashMap::new();
+        config.insert("hosts", vec!["10.0.0.1", "10.0.0.2"]);
+        let mut cache = PersistentCache::new();
+        cache.insert("config", config).unwrap();
+        cache.insert("answer", 42).unwrap();
+        cache
+    })
+}
+
+#[php_function]
+pub fn test_persistent_get(key: &str) -> Option<&'static PersistentZval> {
+    test_cache().get(key)
+}
+
+#[php_function]
+pub fn test_persistent_request_bound() -> bool {
+    let mut ht = ZendHashTable::new();
+    ht.push(1).unwrap();
+    PersistentZval::new(ht).is_err()
+}
+
+#[php_function]
+pub fn test_zend_allocator(size: usize) -> Option<bool> {
+    let usage = || {
+        ZendCallable::try_from_name("memory_get_usage")
+            .ok()?
+            .try_call(vec![])
+            .ok()?
+            .long()
+    };
+    let layout = Layout::from_size_align(size, 64).ok()?;
+    let before = usage()?;
+    ZendAllocator::scope(|alloc| unsafe {
+        let ptr = alloc.alloc(layout);
+        if ptr.is_null() || ptr as usize % 64 != 0 {
+            return Some(false);
+        }
+        ptr.write_bytes(1, size);
+        let allocated = usage()? - before;
+        alloc.dealloc(ptr, layout);
+        let freed = usage()? - before;
+        Some(allocated >= size as i64 && freed < allocated)
+    })?
+}
+
+#[php_function]
+pub fn test_zend_allocator_realloc(size: usize) -> bool {
+    ZendAllocator::scope(|alloc| unsafe {
+        let mut ptr = alloc.alloc(Layout::new::<u8>());
+        for len in 1..size {
+            ptr.add(len - 1).write(len as u8);
+            ptr = alloc.realloc(ptr, Layout::array::<u8>(len).unwrap(), len + 1);
+        }
+        let valid = (1..size).all(|len| *ptr.add(len - 1) == len as u8);
+        alloc.dealloc(ptr, Layout::array::<u8>(size).unwrap());
+        valid
+    })
+    .unwrap_or(false)
+}
+
+#[php_function]
+pub fn test_iterator_range(count: i64) -> RustIterator {
+    RustIterator::new(0..count)
+}
+
+#[php_function]
+pub fn test_iterator_boxed(count: i64) -> Box<dyn Iterator<Item = i64>> {
+    Box::new((0..count).map(|i| i * 2))
+}
+
+#[php_function]
+pub fn test_iterator_pairs() -> Box<dyn Iterator<Item = (String, i64)>> {
+    let keys = ["a", "b"].into_iter().map(String::from);
+    Box::new(keys.zip(1..))
+}
+
+#[php_function]
+pub fn test_iterator_infinite() -> RustIterator {
+    RustIterator::new(0i64..)
+}
+
+#[cfg(php81_plus)]
+#[php_function]
+pub fn test_fiber_suspend(value: i64) -> PhpResult<i64> {
+    let sent = Fiber::suspend(value)?;
+    Ok(sent.long().unwrap_or_default() + 1)
+}
+
+#[cfg(php81_plus)]
+#[php_function]
+pub fn test_fiber_roundtrip() -> PhpResult<Vec<i64>> {
+    let mut fiber = Fiber::new(|| {
+        let sent = Fiber::suspend(1).ok().and_then(|v| v.long());
+        sent.unwrap_or_default() * 2
+    })?;
+    let suspended = fiber.start()?.and_then(|v| v.long()).unwrap_or_default();
+    let finished = fiber.resume(21)?.is_none();
+    let returned = fiber.return_value()?.long().unwrap_or_default();
+    Ok(vec![suspended, finished as i64, returned])
+}
+
+/// Future which completes once its [`TestSignal`] is raised.
+#[cfg(php81_plus)]
+struct TestDelay {
+    state: Arc<Mutex<(bool, Option<Waker>)>>,
+}
+
+/// Handle completing a [`TestDelay`].
+#[cfg(php81_plus)]
+struct TestSignal {
+    state: Arc<Mutex<(bool, Option<Waker>)>>,
+}
+
+#[cfg(php81_plus)]
+impl TestDelay {
+    fn new() -> (Self, TestSignal) {
+        let state = Arc::new(Mutex::new((false, None::<Waker>)));
+        let signal = TestSignal {
+            state: state.clone(),
+        };
+        (Self { state }, signal)
+    }
+}
+
+#[cfg(php81_plus)]
+impl TestSignal {
+    fn raise(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.0 = true;
+        if let Some(waker) = state.1.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(php81_plus)]
+impl Future for TestDelay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.0 {
+            Poll::Ready(())
+        } else {
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(php81_plus)]
+#[php_function]
+pub fn test_fiber_executor() -> PhpResult<Vec<i64>> {
+    let order = Rc::new(RefCell::new(vec![]));
+    let mut executor = FiberExecutor::new();
+    let mut signals = vec![];
+    for value in [1, 2, 3] {
+        let (delay, signal) = TestDelay::new();
+        let order = order.clone();
+        executor.spawn(async move {
+            delay.await;
+            order.borrow_mut().push(value);
+            value
+        })?;
+        signals.push(signal);
+    }
+    // Every task is pending by now, wake them from another thread in a fixed
+    // order which differs from the order they were spawned in.
+    let waker = thread::spawn(move || {
+        for index in [1, 2, 0] {
+            signals[index].raise();
+        }
+    });
+    executor.run()?;
+    waker.join().unwrap();
+    let order = order.borrow().clone();
+    Ok(order)
+}
+
 #[php_function]
 pub fn test_closure() -> Closure {
     Closure::wrap(Box::new(|a| a) as Box<dyn Fn(String) -> String>)
@@ -67,11 +516,74 @@ pub fn test_closure_once(a: String) -> Closure {
     Closure::wrap_once(Box::new(move || a) as Box<dyn FnOnce() -> String>)
 }
 
+#[php_function]
+pub fn test_closure_typed() -> Closure {
+    Closure::wrap(
+        Box::new(|name: String, times: i64| name.repeat(times as usize))
+            as Box<dyn Fn(String, i64) -> String>,
+    )
+    .with_arg_names(&["name", "times"])
+}
+
+#[php_function]
+pub fn test_closure_bound(obj: &ZendObject) -> Closure {
+    Closure::wrap(Box::new(|this: ZRc<ZendObject>, suffix: String| {
+        format!("{}{}", this.get_class_name().unwrap_or_default(), suffix)
+    }) as Box<dyn Fn(ZRc<ZendObject>, String) -> String>)
+    .bind(obj)
+}
+
 #[php_function]
 pub fn test_callable(call: ZendCallable, a: String) -> Zval {
     call.try_call(vec![&a]).expect("Failed to call function")
 }
 
+#[php_function]
+pub fn test_callable_typed(call: ZendCallable) -> PhpResult<i64> {
+    let mut total = 0;
+    for i in 0..100i64 {
+        total += call.call::<_, i64>((i, 2))?;
+    }
+    Ok(total)
+}
+
+#[php_function]
+pub fn test_call_named(call: ZendCallable) -> PhpResult<Zval> {
+    Ok(call.call_zval(ArgList::new().arg(1).named("c", 3).named("b", 2))?)
+}
+
+#[php_function]
+pub fn test_call_spread(call: ZendCallable, args: &ZendHashTable) -> PhpResult<Zval> {
+    Ok(call.call_zval(ArgList::new().arg(0).spread(args))?)
+}
+
+#[php_function]
+pub fn test_call_positional_after_named(call: ZendCallable) -> PhpResult<Zval> {
+    Ok(call.call_zval(ArgList::new().named("a", 1).arg(2))?)
+}
+
+#[php_function]
+pub fn test_call_method_named(obj: &ZendObject, name: &str) -> PhpResult<Zval> {
+    Ok(obj.try_call_method_with("greet", ArgList::new().arg(name).named("suffix", "!"))?)
+}
+
+#[php_function]
+pub fn test_callable_ref(call: ZendCallable, value: String) -> PhpResult<Option<String>> {
+    let mut value = value.into_zval(false)?;
+    call.call_zval((&mut value,))?;
+    Ok(value.string())
+}
+
+#[php_function]
+pub fn test_callable_ref_invalid(call: ZendCallable, value: String) -> PhpResult<Option<String>> {
+    let mut value = value.into_zval(false)?;
+    // The second argument overflows, so the function is not called.
+    match call.call_zval((&mut value, u64::MAX)) {
+        Ok(_) => Ok(None),
+        Err(_) => Ok(value.string()),
+    }
+}
+
 #[php_class]
 pub struct TestClass {
     string: String,
@@ -112,6 +624,444 @@ pub fn test_class(string: String, number: i32) -> TestClass {
     }
 }
 
+#[php_class]
+pub struct TestFactory {
+    #[prop]
+    value: i64,
+}
+
+#[php_impl]
+impl TestFactory {
+    #[constant]
+    pub fn defaults() -> Vec<i64> {
+        (1..=3).collect()
+    }
+
+    #[constant]
+    #[rename("LABEL")]
+    pub fn label() -> String {
+        format!("factory-{}", Self::defaults().len())
+    }
+
+    pub fn create(
+        #[called_scope] ce: &ClassEntry,
+        value: i64,
+    ) -> Option<ZBox<ZendClassObject<Self>>> {
+        ZendClassObject::new_in(Self { value }, ce)
+    }
+
+    pub fn called_class(#[called_scope] ce: &ClassEntry) -> Option<String> {
+        ce.name().map(ToString::to_string)
+    }
+
+    pub fn instance_class(&self, #[called_scope] ce: &ClassEntry) -> Option<String> {
+        ce.name().map(ToString::to_string)
+    }
+}
+
+#[php_class]
+pub struct TestBase {
+    #[prop]
+    name: String,
+}
+
+#[php_impl]
+impl TestBase {
+    pub fn __construct(name: String) -> Self {
+        Self { name }
+    }
+
+    pub fn greeting(&self) -> String {
+        format!("Hello, {}", self.name)
+    }
+
+    pub fn greet(#[this] this: &mut ZendClassObject<TestBase>) -> PhpResult<String> {
+        Ok(this
+            .call_method("greeting", ())?
+            .string()
+            .unwrap_or_default())
+    }
+
+    pub fn describe(&self, callback: ZendCallable) -> PhpResult<String> {
+        let description = callback.try_call(vec![])?.string().unwrap_or_default();
+        Ok(format!("{}: {}", self.name, description))
+    }
+
+    pub fn rename(&mut self, callback: ZendCallable) -> PhpResult<()> {
+        self.name = callback.try_call(vec![])?.string().unwrap_or_default();
+        Ok(())
+    }
+}
+
+#[cfg(php81_plus)]
+#[php_enum]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TestSuit {
+    Hearts,
+    Spades,
+}
+
+#[cfg(php81_plus)]
+#[php_enum]
+#[derive(Clone, Copy)]
+pub enum TestStatus {
+    Active = 1,
+    #[case(name = "Disabled")]
+    Inactive = 0,
+}
+
+#[cfg(php81_plus)]
+#[php_impl]
+impl TestStatus {
+    const DEFAULT: i32 = 1;
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Inactive => "inactive",
+        }
+    }
+}
+
+#[cfg(php81_plus)]
+#[php_function]
+pub fn test_enum(suit: TestSuit) -> TestSuit {
+    match suit {
+        TestSuit::Hearts => TestSuit::Spades,
+        TestSuit::Spades => TestSuit::Hearts,
+    }
+}
+
+#[cfg(php81_plus)]
+#[php_function]
+pub fn test_enum_backed(status: TestStatus) -> TestStatus {
+    status
+}
+
+#[cfg(php81_plus)]
+#[php_function(default_exprs(suit = "TestSuit::Hearts"))]
+pub fn test_enum_default(suit: TestSuit) -> TestSuit {
+    suit
+}
+
+#[php_class]
+pub struct TestProperties {
+    #[prop]
+    count: i64,
+    #[prop]
+    label: Option<String>,
+    #[prop(flags = PropertyFlags::Protected)]
+    secret: String,
+}
+
+#[php_impl]
+impl TestProperties {
+    #[prop(rename = "instances")]
+    const INSTANCES: i64 = 0;
+
+    pub fn __construct(count: i64) -> Self {
+        Self {
+            count,
+            label: None,
+            secret: "hidden".into(),
+        }
+    }
+
+    #[defaults(step = 1)]
+    pub fn increment(&mut self, step: i64) -> i64 {
+        self.count += step;
+        self.count
+    }
+}
+
+#[php_function]
+pub fn test_static_property(value: i64) -> Option<i64> {
+    let ce = TestProperties::get_metadata().ce();
+    ce.set_static_property("instances", value).ok()?;
+    ce.static_property("instances")?.long()
+}
+
+#[cfg(php81_plus)]
+#[php_class]
+pub struct TestReadonly {
+    #[prop(readonly)]
+    id: i64,
+}
+
+#[cfg(php81_plus)]
+#[php_impl]
+impl TestReadonly {
+    pub fn __construct(id: i64) -> Self {
+        Self { id }
+    }
+}
+
+#[php_interface]
+pub trait TestShape {
+    const SIDES: i64 = 0;
+
+    fn area(&self) -> f64;
+
+    fn describe(name: String) -> String;
+}
+
+#[php_class]
+#[implements(TestShape)]
+pub struct TestSquare {
+    side: f64,
+}
+
+#[php_impl]
+impl TestSquare {
+    pub fn __construct(side: f64) -> Self {
+        Self { side }
+    }
+
+    pub fn area(&self) -> f64 {
+        self.side * self.side
+    }
+
+    pub fn describe(name: String) -> String {
+        format!("square {}", name)
+    }
+}
+
+#[php_class]
+#[derive(Clone)]
+pub struct TestVector {
+    items: Vec<i64>,
+}
+
+#[php_impl]
+impl TestVector {
+    pub fn __construct(items: Vec<i64>) -> Self {
+        Self { items }
+    }
+
+    pub fn sum(&self) -> i64 {
+        self.items.iter().sum()
+    }
+
+    #[handler]
+    fn compare(&self, other: &Zval) -> PhpResult<Option<Ordering>> {
+        let other = match other.extract::<&TestVector>() {
+            Some(other) => other.sum(),
+            None => other.long().ok_or("Cannot compare vector with value")?,
+        };
+        Ok(Some(self.sum().cmp(&other)))
+    }
+
+    #[handler]
+    fn cast(&self, ty: DataType) -> PhpResult<Option<Zval>> {
+        Ok(match ty {
+            DataType::String => Some(format!("{:?}", self.items).into_zval(false)?),
+            DataType::Long => Some(self.sum().into_zval(false)?),
+            DataType::Bool => Some((!self.items.is_empty()).into_zval(false)?),
+            _ => None,
+        })
+    }
+
+    #[handler]
+    fn clone_obj(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
+    #[handler]
+    fn read_dimension(&self, offset: &Zval) -> PhpResult<Option<Zval>> {
+        let item = offset
+            .long()
+            .and_then(|i| self.items.get(i as usize))
+            .ok_or("Undefined vector offset")?;
+        Ok(Some(item.into_zval(false)?))
+    }
+
+    #[handler]
+    fn write_dimension(&mut self, offset: Option<&Zval>, value: &Zval) -> PhpResult<bool> {
+        let value = value.long().ok_or("Vector items must be integers")?;
+        match offset {
+            Some(offset) => {
+                let item = offset
+                    .long()
+                    .and_then(|i| self.items.get_mut(i as usize))
+                    .ok_or("Undefined vector offset")?;
+                *item = value;
+            }
+            None => self.items.push(value),
+        }
+        Ok(true)
+    }
+
+    #[handler]
+    fn has_dimension(&self, offset: &Zval, check_empty: bool) -> PhpResult<Option<bool>> {
+        let item = offset.long().and_then(|i| self.items.get(i as usize));
+        Ok(Some(match item {
+            Some(item) => !check_empty || *item != 0,
+            None => false,
+        }))
+    }
+
+    #[handler]
+    fn unset_dimension(&mut self, offset: &Zval) -> PhpResult<bool> {
+        if let Some(i) = offset.long().filter(|i| (*i as usize) < self.items.len()) {
+            self.items.remove(i as usize);
+        }
+        Ok(true)
+    }
+
+    #[handler]
+    fn count_elements(&self) -> PhpResult<Option<i64>> {
+        Ok(Some(self.items.len() as i64))
+    }
+
+    #[handler]
+    fn do_operation(op: Operator, lhs: &Zval, rhs: Option<&Zval>) -> PhpResult<Option<Zval>> {
+        let (lhs, rhs) = match (lhs.extract::<&TestVector>(), rhs.and_then(Zval::long)) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            _ => return Ok(None),
+        };
+        let items = match op {
+            Operator::Add => lhs.items.iter().map(|i| i + rhs).collect(),
+            Operator::Mul => lhs.items.iter().map(|i| i * rhs).collect(),
+            _ => return Ok(None),
+        };
+        Ok(Some(TestVector { items }.into_zval(false)?))
+    }
+}
+
+#[php_class]
+#[countable]
+#[array_access]
+#[iterable]
+#[stringable]
+pub struct TestCollection {
+    items: Vec<i64>,
+}
+
+#[php_impl]
+impl TestCollection {
+    pub fn __construct(items: Vec<i64>) -> Self {
+        Self { items }
+    }
+}
+
+impl TestCollection {
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+impl Index<usize> for TestCollection {
+    type Output = i64;
+
+    fn index(&self, index: usize) -> &i64 {
+        &self.items[index]
+    }
+}
+
+impl IndexMut<usize> for TestCollection {
+    fn index_mut(&mut self, index: usize) -> &mut i64 {
+        &mut self.items[index]
+    }
+}
+
+impl Extend<i64> for TestCollection {
+    fn extend<I: IntoIterator<Item = i64>>(&mut self, iter: I) {
+        self.items.extend(iter)
+    }
+}
+
+impl<'a> IntoIterator for &'a TestCollection {
+    type Item = i64;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, i64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter().copied()
+    }
+}
+
+impl fmt::Display for TestCollection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.items)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TestSerdeUser {
+    name: String,
+    age: u8,
+    tags: Vec<String>,
+    address: Option<TestSerdeAddress>,
+    role: TestSerdeRole,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TestSerdeAddress {
+    city: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum TestSerdeRole {
+    Guest,
+    Admin { level: u8 },
+}
+
+#[php_function]
+pub fn test_serde_roundtrip(user: &Zval) -> PhpResult<Zval> {
+    let user: TestSerdeUser = ext_php_rs::serde::from_zval(user)?;
+    Ok(ext_php_rs::serde::to_zval(&user)?)
+}
+
+#[php_function]
+pub fn test_serde_object(user: &Zval) -> PhpResult<Zval> {
+    let user: TestSerdeUser = ext_php_rs::serde::from_zval(user)?;
+    let serializer = ext_php_rs::serde::Serializer::new().structs_as_objects(true);
+    Ok(user.serialize(serializer)?)
+}
+
+#[php_class]
+pub struct TestListeners {
+    priorities: WeakMap<i64>,
+    held: Vec<Zval>,
+}
+
+#[php_impl]
+impl TestListeners {
+    pub fn __construct() -> Self {
+        Self {
+            priorities: WeakMap::new(),
+            held: vec![],
+        }
+    }
+
+    pub fn listen(&mut self, target: &ZendObject, priority: i64) -> PhpResult<()> {
+        self.priorities.insert(target, priority)?;
+        Ok(())
+    }
+
+    pub fn priority(&self, target: &ZendObject) -> Option<i64> {
+        self.priorities.get(target).copied()
+    }
+
+    pub fn live(&self) -> usize {
+        self.priorities.iter().count()
+    }
+
+    pub fn purge(&mut self) -> usize {
+        self.priorities.purge()
+    }
+
+    pub fn hold(&mut self, value: &Zval) {
+        self.held.push(value.shallow_clone());
+    }
+
+    #[handler]
+    fn get_gc(&self, gc: &mut GcBuffer) {
+        for value in &self.held {
+            gc.add_zval(value);
+        }
+    }
+}
+
 #[php_function_hook]
 pub fn chr() {
     "Hello from the hook!";
@@ -178,16 +1128,35 @@ mod integration {
         }
     }
 
+    mod allocator;
     mod array;
     mod binary;
     mod bool;
     mod callable;
     mod class;
     mod closure;
+    mod defaults;
+    #[cfg(php81_plus)]
+    mod enum_;
+    #[cfg(php81_plus)]
+    mod fiber;
+    mod inheritance;
+    mod interface;
+    mod interfaces;
+    mod iterator;
+    mod named_args;
     mod nullable;
     mod number;
     mod object;
+    mod object_handlers;
+    mod persistent;
+    mod property;
+    mod reference;
+    mod resource;
+    mod serde;
+    mod strict_types;
     mod string;
     mod types;
+    mod weak;
     mod function_hooks;
 }